@@ -0,0 +1,667 @@
+//! `#[derive(OpenApiSchema)]` and `#[openapi_operation]` for `actix-web-openapi`.
+//!
+//! The generated `OpenApiSchema` implementation follows the type's serde attributes:
+//! `rename`, `rename_all`, `skip`, `default`, `flatten` and the `tag`, `content` and
+//! `untagged` enum representations. `Option` fields are not required, doc comments become
+//! descriptions, and nested types are registered in `components.schemas` and referenced.
+//! `#[openapi(rename = "...")]` names the schema of a type without changing its serde form,
+//! e.g. to tell apart two types of the same name.
+
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{
+    parse_macro_input, parse_quote, Attribute, AttributeArgs, Data, DataEnum, DeriveInput, FnArg, Fields, FieldsNamed,
+    GenericArgument, GenericParam, ItemFn, Lit, Meta, NestedMeta, PathArguments, ReturnType, Type,
+};
+
+#[proc_macro_derive(OpenApiSchema, attributes(serde, openapi))]
+pub fn derive_openapi_schema(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input).into()
+}
+
+/// The serde attributes that shape a schema.
+#[derive(Default)]
+struct SerdeAttrs {
+    rename: Option<String>,
+    rename_all: Option<String>,
+    tag: Option<String>,
+    content: Option<String>,
+    untagged: bool,
+    skip: bool,
+    flatten: bool,
+    default: bool,
+}
+
+fn serde_attrs(attrs: &[Attribute]) -> SerdeAttrs {
+    let mut serde = SerdeAttrs::default();
+    for attr in attrs.iter().filter(|attr| attr.path.is_ident("serde")) {
+        let list = match attr.parse_meta() {
+            Ok(Meta::List(list)) => list,
+            _ => continue,
+        };
+        for nested in list.nested.iter() {
+            match *nested {
+                NestedMeta::Meta(Meta::NameValue(ref pair)) => {
+                    let value = match pair.lit {
+                        Lit::Str(ref value) => value.value(),
+                        _ => continue,
+                    };
+                    if pair.path.is_ident("rename") {
+                        serde.rename = Some(value);
+                    } else if pair.path.is_ident("rename_all") {
+                        serde.rename_all = Some(value);
+                    } else if pair.path.is_ident("tag") {
+                        serde.tag = Some(value);
+                    } else if pair.path.is_ident("content") {
+                        serde.content = Some(value);
+                    } else if pair.path.is_ident("default") || pair.path.is_ident("skip_serializing_if") {
+                        serde.default = true;
+                    }
+                }
+                NestedMeta::Meta(Meta::Path(ref path)) => {
+                    if path.is_ident("untagged") {
+                        serde.untagged = true;
+                    } else if path.is_ident("skip") || path.is_ident("skip_serializing") || path.is_ident("skip_deserializing") {
+                        serde.skip = true;
+                    } else if path.is_ident("flatten") {
+                        serde.flatten = true;
+                    } else if path.is_ident("default") {
+                        serde.default = true;
+                    }
+                }
+                // `rename(serialize = "..", deserialize = "..")`: describe the serialized form
+                NestedMeta::Meta(Meta::List(ref list)) => {
+                    let serialized = list.nested.iter().find_map(|nested| match *nested {
+                        NestedMeta::Meta(Meta::NameValue(ref pair)) if pair.path.is_ident("serialize") => {
+                            match pair.lit {
+                                Lit::Str(ref value) => Some(value.value()),
+                                _ => None,
+                            }
+                        }
+                        _ => None,
+                    });
+                    if list.path.is_ident("rename") {
+                        serde.rename = serialized.or(serde.rename);
+                    } else if list.path.is_ident("rename_all") {
+                        serde.rename_all = serialized.or(serde.rename_all);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    serde
+}
+
+/// The schema name given by `#[openapi(rename = "...")]`, which names the schema without
+/// changing how the type serializes.
+fn schema_rename(attrs: &[Attribute]) -> Option<String> {
+    attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("openapi"))
+        .filter_map(|attr| match attr.parse_meta() {
+            Ok(Meta::List(list)) => Some(list),
+            _ => None,
+        })
+        .flat_map(|list| list.nested.into_iter())
+        .filter_map(|nested| match nested {
+            NestedMeta::Meta(Meta::NameValue(pair)) if pair.path.is_ident("rename") => match pair.lit {
+                Lit::Str(value) => Some(value.value()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .last()
+}
+
+/// The doc comment of an item, with the leading space of each line removed.
+fn doc(attrs: &[Attribute]) -> String {
+    let lines: Vec<String> = attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("doc"))
+        .filter_map(|attr| match attr.parse_meta() {
+            Ok(Meta::NameValue(pair)) => match pair.lit {
+                Lit::Str(value) => Some(value.value().trim().to_string()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect();
+    lines.join("\n").trim().to_string()
+}
+
+/// Split a `PascalCase` variant name into its words.
+fn variant_words(name: &str) -> Vec<String> {
+    let mut words: Vec<String> = Vec::new();
+    for ch in name.chars() {
+        if ch.is_uppercase() || words.is_empty() {
+            words.push(String::new());
+        }
+        if let Some(word) = words.last_mut() {
+            word.push(ch);
+        }
+    }
+    words
+}
+
+/// Apply a `rename_all` rule to words, as serde does.
+fn apply_rule(words: &[String], rule: &str) -> Option<String> {
+    let lower: Vec<String> = words.iter().map(|word| word.to_lowercase()).collect();
+    let capitalized: Vec<String> = lower
+        .iter()
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect();
+
+    let renamed = match rule {
+        "lowercase" => lower.concat(),
+        "UPPERCASE" => lower.concat().to_uppercase(),
+        "PascalCase" => capitalized.concat(),
+        "camelCase" => {
+            let mut camel = lower.first().cloned().unwrap_or_default();
+            camel.push_str(&capitalized.iter().skip(1).cloned().collect::<String>());
+            camel
+        }
+        "snake_case" => lower.join("_"),
+        "SCREAMING_SNAKE_CASE" => lower.join("_").to_uppercase(),
+        "kebab-case" => lower.join("-"),
+        "SCREAMING-KEBAB-CASE" => lower.join("-").to_uppercase(),
+        _ => return None,
+    };
+    Some(renamed)
+}
+
+fn rename_field(name: &str, rule: Option<&str>) -> String {
+    let name = name.trim_start_matches("r#");
+    match rule {
+        // serde keeps the underscores of snake_case fields for these rules
+        Some("lowercase") => name.to_lowercase(),
+        Some("UPPERCASE") => name.to_uppercase(),
+        Some(rule) => {
+            let words: Vec<String> = name.split('_').map(str::to_string).collect();
+            apply_rule(&words, rule).unwrap_or_else(|| name.to_string())
+        }
+        None => name.to_string(),
+    }
+}
+
+fn rename_variant(name: &str, rule: Option<&str>) -> String {
+    match rule {
+        Some(rule) => apply_rule(&variant_words(name), rule).unwrap_or_else(|| name.to_string()),
+        None => name.to_string(),
+    }
+}
+
+fn expand(mut input: DeriveInput) -> TokenStream2 {
+    let attrs = serde_attrs(&input.attrs);
+    let description = doc(&input.attrs);
+    let ident = input.ident.clone();
+
+    // generic types are inlined, as their name depends on the type arguments
+    let generic = input.generics.type_params().next().is_some();
+    let schema_name = if generic {
+        quote! { None }
+    } else {
+        let name = schema_rename(&input.attrs)
+            .or_else(|| attrs.rename.clone())
+            .unwrap_or_else(|| ident.to_string());
+        quote! { Some(#name.to_string()) }
+    };
+
+    let body = match input.data {
+        Data::Struct(ref data) => match data.fields {
+            Fields::Named(ref fields) => named_fields(fields, attrs.rename_all.as_deref(), attrs.default),
+            Fields::Unnamed(ref fields) if fields.unnamed.len() == 1 => {
+                let ty = &fields.unnamed[0].ty;
+                quote! { <#ty as ::actix_web_openapi::schema::OpenApiSchema>::schema_ref(schemas) }
+            }
+            Fields::Unnamed(ref fields) => {
+                let types = fields.unnamed.iter().map(|field| &field.ty);
+                quote! {
+                    ::actix_web_openapi::schema::tuple(vec![
+                        #(<#types as ::actix_web_openapi::schema::OpenApiSchema>::schema_ref(schemas)),*
+                    ])
+                }
+            }
+            Fields::Unit => quote! { ::actix_web_openapi::schema::unit() },
+        },
+        Data::Enum(ref data) => enum_schema(data, &attrs),
+        Data::Union(_) => {
+            return syn::Error::new_spanned(&input.ident, "OpenApiSchema cannot be derived for unions")
+                .to_compile_error()
+        }
+    };
+
+    for param in input.generics.params.iter_mut() {
+        if let GenericParam::Type(ref mut param) = *param {
+            param.bounds.push(parse_quote!(::actix_web_openapi::schema::OpenApiSchema));
+        }
+    }
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    quote! {
+        impl #impl_generics ::actix_web_openapi::schema::OpenApiSchema for #ident #ty_generics #where_clause {
+            fn schema_name() -> Option<String> {
+                #schema_name
+            }
+
+            fn schema(schemas: &mut ::actix_web_openapi::schema::Schemas) -> ::actix_web_openapi::spec::Schema {
+                let schema = { #body };
+                ::actix_web_openapi::schema::describe(schema, #description)
+            }
+        }
+    }
+}
+
+/// An object with one property per field. `all_optional` is set by a container `#[serde(default)]`.
+fn named_fields(fields: &FieldsNamed, rename_all: Option<&str>, all_optional: bool) -> TokenStream2 {
+    let mut statements = Vec::new();
+    for field in fields.named.iter() {
+        let attrs = serde_attrs(&field.attrs);
+        if attrs.skip {
+            continue;
+        }
+        let ty = &field.ty;
+        if attrs.flatten {
+            statements.push(quote! {
+                ::actix_web_openapi::schema::flatten(
+                    &mut object,
+                    <#ty as ::actix_web_openapi::schema::OpenApiSchema>::schema(schemas),
+                );
+            });
+            continue;
+        }
+
+        let field_name = field.ident.as_ref().map(ToString::to_string).unwrap_or_default();
+        let name = attrs.rename.unwrap_or_else(|| rename_field(&field_name, rename_all));
+        let description = doc(&field.attrs);
+        let optional = all_optional || attrs.default;
+        statements.push(quote! {
+            ::actix_web_openapi::schema::property(
+                &mut object,
+                #name,
+                ::actix_web_openapi::schema::describe(
+                    <#ty as ::actix_web_openapi::schema::OpenApiSchema>::schema_ref(schemas),
+                    #description,
+                ),
+                #optional || <#ty as ::actix_web_openapi::schema::OpenApiSchema>::is_optional(),
+            );
+        });
+    }
+
+    quote! {
+        let mut object = ::actix_web_openapi::schema::object();
+        #(#statements)*
+        object
+    }
+}
+
+/// One schema per variant in the enum's representation, combined with `oneOf`.
+fn enum_schema(data: &DataEnum, attrs: &SerdeAttrs) -> TokenStream2 {
+    let rename_all = attrs.rename_all.as_deref();
+    let variants: Vec<_> = data
+        .variants
+        .iter()
+        .map(|variant| (variant, serde_attrs(&variant.attrs)))
+        .filter(|(_, variant_attrs)| !variant_attrs.skip)
+        .collect();
+
+    // externally tagged unit variants are plain strings
+    let all_unit = variants.iter().all(|(variant, _)| matches!(variant.fields, Fields::Unit));
+    if all_unit && attrs.tag.is_none() && !attrs.untagged {
+        let names = variants.iter().map(|(variant, variant_attrs)| {
+            variant_attrs
+                .rename
+                .clone()
+                .unwrap_or_else(|| rename_variant(&variant.ident.to_string(), rename_all))
+        });
+        return quote! { ::actix_web_openapi::schema::string_enum(&[#(#names),*]) };
+    }
+
+    let schemas = variants.iter().map(|(variant, variant_attrs)| {
+        let name = variant_attrs
+            .rename
+            .clone()
+            .unwrap_or_else(|| rename_variant(&variant.ident.to_string(), rename_all));
+        let description = doc(&variant.attrs);
+
+        let content = match variant.fields {
+            Fields::Unit => None,
+            Fields::Unnamed(ref fields) if fields.unnamed.len() == 1 => {
+                let ty = &fields.unnamed[0].ty;
+                Some(quote! { <#ty as ::actix_web_openapi::schema::OpenApiSchema>::schema_ref(schemas) })
+            }
+            Fields::Unnamed(ref fields) => {
+                let types = fields.unnamed.iter().map(|field| &field.ty);
+                Some(quote! {
+                    ::actix_web_openapi::schema::tuple(vec![
+                        #(<#types as ::actix_web_openapi::schema::OpenApiSchema>::schema_ref(schemas)),*
+                    ])
+                })
+            }
+            Fields::Named(ref fields) => {
+                let body = named_fields(fields, variant_attrs.rename_all.as_deref(), false);
+                Some(quote! { { #body } })
+            }
+        };
+
+        let schema = match (content, attrs.tag.as_ref(), attrs.content.as_ref(), attrs.untagged) {
+            (None, _, _, true) => quote! { ::actix_web_openapi::schema::unit() },
+            (Some(content), _, _, true) => content,
+            (None, Some(tag), _, _) => quote! {
+                ::actix_web_openapi::schema::with_tag(::actix_web_openapi::schema::object(), #tag, #name)
+            },
+            (Some(content), Some(tag), Some(content_key), _) => quote! {
+                ::actix_web_openapi::schema::adjacent(#tag, #name, #content_key, #content)
+            },
+            (Some(content), Some(tag), None, _) => quote! {
+                ::actix_web_openapi::schema::with_tag(#content, #tag, #name)
+            },
+            (None, None, _, _) => quote! { ::actix_web_openapi::schema::string_enum(&[#name]) },
+            (Some(content), None, _, _) => quote! { ::actix_web_openapi::schema::external(#name, #content) },
+        };
+        quote! { ::actix_web_openapi::schema::describe(#schema, #description) }
+    });
+
+    quote! { ::actix_web_openapi::schema::one_of(vec![#(#schemas),*]) }
+}
+
+/// The arguments of `#[openapi_operation(...)]`.
+#[derive(Default)]
+struct OperationArgs {
+    method: Option<String>,
+    path: Option<String>,
+    operation_id: Option<String>,
+    summary: Option<String>,
+    description: Option<String>,
+    tags: Vec<String>,
+    status: Option<String>,
+    deprecated: bool,
+    responses: Vec<(String, String)>,
+}
+
+const METHODS: &[&str] = &["get", "put", "post", "delete", "options", "head", "patch", "trace"];
+
+fn lit_string(lit: &Lit) -> Option<String> {
+    match *lit {
+        Lit::Str(ref value) => Some(value.value()),
+        Lit::Int(ref value) => Some(value.base10_digits().to_string()),
+        _ => None,
+    }
+}
+
+fn operation_args(args: AttributeArgs) -> syn::Result<OperationArgs> {
+    let mut parsed = OperationArgs::default();
+    for arg in args {
+        match arg {
+            NestedMeta::Meta(Meta::Path(ref path)) => {
+                match METHODS.iter().find(|method| path.is_ident(method)) {
+                    Some(method) => parsed.method = Some(method.to_string()),
+                    None if path.is_ident("deprecated") => parsed.deprecated = true,
+                    None => return Err(syn::Error::new_spanned(path, "unknown openapi_operation argument")),
+                }
+            }
+            NestedMeta::Lit(ref lit) => parsed.path = lit_string(lit),
+            NestedMeta::Meta(Meta::NameValue(ref pair)) => {
+                let value = lit_string(&pair.lit);
+                if pair.path.is_ident("path") {
+                    parsed.path = value;
+                } else if pair.path.is_ident("method") {
+                    parsed.method = value.map(|method| method.to_lowercase());
+                } else if pair.path.is_ident("operation_id") {
+                    parsed.operation_id = value;
+                } else if pair.path.is_ident("summary") {
+                    parsed.summary = value;
+                } else if pair.path.is_ident("description") {
+                    parsed.description = value;
+                } else if pair.path.is_ident("status") {
+                    parsed.status = value;
+                } else {
+                    return Err(syn::Error::new_spanned(&pair.path, "unknown openapi_operation argument"));
+                }
+            }
+            NestedMeta::Meta(Meta::List(ref list)) => {
+                let values: Vec<String> = list
+                    .nested
+                    .iter()
+                    .filter_map(|nested| match *nested {
+                        NestedMeta::Lit(ref lit) => lit_string(lit),
+                        _ => None,
+                    })
+                    .collect();
+                if list.path.is_ident("tags") {
+                    parsed.tags.extend(values);
+                } else if list.path.is_ident("response") && values.len() == 2 {
+                    parsed.responses.push((values[0].clone(), values[1].clone()));
+                } else {
+                    return Err(syn::Error::new_spanned(&list.path, "expected `tags(..)` or `response(status, description)`"));
+                }
+            }
+        }
+    }
+    Ok(parsed)
+}
+
+/// The last path segment of a type and its first type argument, e.g. `Json` and `Pet` for
+/// `web::Json<Pet>`.
+fn extractor(ty: &Type) -> Option<(String, &Type)> {
+    let segment = match *ty {
+        Type::Path(ref path) => path.path.segments.last()?,
+        _ => return None,
+    };
+    let argument = match segment.arguments {
+        PathArguments::AngleBracketed(ref arguments) => arguments.args.iter().find_map(|argument| match *argument {
+            GenericArgument::Type(ref ty) => Some(ty),
+            _ => None,
+        })?,
+        _ => return None,
+    };
+    Some((segment.ident.to_string(), argument))
+}
+
+/// The first `Json<T>` anywhere within a return type, e.g. in `Result<Json<T>, Error>`.
+fn json_body(ty: &Type) -> Option<&Type> {
+    let segment = match *ty {
+        Type::Path(ref path) => path.path.segments.last()?,
+        _ => return None,
+    };
+    let arguments = match segment.arguments {
+        PathArguments::AngleBracketed(ref arguments) => arguments,
+        _ => return None,
+    };
+    let types = arguments.args.iter().filter_map(|argument| match *argument {
+        GenericArgument::Type(ref ty) => Some(ty),
+        _ => None,
+    });
+    if segment.ident == "Json" {
+        return types.into_iter().next();
+    }
+    types.into_iter().find_map(json_body)
+}
+
+fn is_text(ty: &Type) -> bool {
+    match *ty {
+        Type::Path(ref path) => path.path.segments.last().is_some_and(|segment| segment.ident == "String"),
+        Type::Reference(ref reference) => is_text(&reference.elem) || match *reference.elem {
+            Type::Path(ref path) => path.path.is_ident("str"),
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+/// Statements adding the parameters and request body implied by a handler argument.
+fn argument_statements(ty: &Type) -> TokenStream2 {
+    let (name, inner) = match extractor(ty) {
+        Some(extractor) => extractor,
+        None => return TokenStream2::new(),
+    };
+    let schema_trait = quote! { ::actix_web_openapi::schema::OpenApiSchema };
+    match name.as_str() {
+        "Path" => match *inner {
+            Type::Tuple(ref tuple) => {
+                let elems = tuple.elems.iter();
+                quote! {
+                    ::actix_web_openapi::handler::path_parameters(
+                        &mut operation,
+                        PATH,
+                        vec![#(<#elems as #schema_trait>::schema_ref(schemas)),*],
+                    );
+                }
+            }
+            _ => quote! {
+                ::actix_web_openapi::handler::path_object_parameters(
+                    &mut operation,
+                    PATH,
+                    <#inner as #schema_trait>::schema(schemas),
+                );
+            },
+        },
+        "Query" => quote! {
+            ::actix_web_openapi::handler::query_parameters(&mut operation, <#inner as #schema_trait>::schema(schemas));
+        },
+        "Json" => quote! {
+            ::actix_web_openapi::handler::request_body(
+                &mut operation,
+                "application/json",
+                <#inner as #schema_trait>::schema_ref(schemas),
+            );
+        },
+        "Form" => quote! {
+            ::actix_web_openapi::handler::request_body(
+                &mut operation,
+                "application/x-www-form-urlencoded",
+                <#inner as #schema_trait>::schema_ref(schemas),
+            );
+        },
+        _ => TokenStream2::new(),
+    }
+}
+
+/// Describe an actix-web handler as an `Operation`.
+///
+/// ```ignore
+/// /// Find a pet by id.
+/// #[openapi_operation(get, "/pets/{id}", tags("pets"), response(404, "Pet not found"))]
+/// fn get_pet(id: Path<u64>) -> Result<Json<Pet>> { .. }
+/// ```
+///
+/// Generates a struct named like the handler that implements `OpenApiOperation`, to be
+/// collected with `SpecBuilder::operation::<get_pet>()`. The struct is declared next to the
+/// handler, so only free functions are supported, not methods.
+#[proc_macro_attribute]
+pub fn openapi_operation(args: TokenStream, item: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(args as AttributeArgs);
+    let function = parse_macro_input!(item as ItemFn);
+    match expand_operation(args, &function) {
+        Ok(described) => quote! { #function #described }.into(),
+        Err(err) => {
+            let err = err.to_compile_error();
+            quote! { #function #err }.into()
+        }
+    }
+}
+
+fn expand_operation(args: AttributeArgs, function: &ItemFn) -> syn::Result<TokenStream2> {
+    let args = operation_args(args)?;
+    let ident = &function.sig.ident;
+    let vis = &function.vis;
+    let method = args
+        .method
+        .ok_or_else(|| syn::Error::new_spanned(ident, "missing the HTTP method, e.g. `get`"))?;
+    let path = args
+        .path
+        .ok_or_else(|| syn::Error::new_spanned(ident, "missing the path, e.g. \"/pets/{id}\""))?;
+
+    // the first line of the doc comment is the summary, the rest the description
+    let doc = doc(&function.attrs);
+    let mut doc_lines = doc.splitn(2, '\n');
+    let doc_summary = doc_lines.next().map(str::trim).filter(|line| !line.is_empty()).map(str::to_string);
+    let doc_description = doc_lines.next().map(str::trim).filter(|rest| !rest.is_empty()).map(str::to_string);
+
+    let operation_id = args.operation_id.unwrap_or_else(|| ident.to_string());
+    let summary = option_tokens(args.summary.or(doc_summary));
+    let description = option_tokens(args.description.or(doc_description));
+    let tags = if args.tags.is_empty() {
+        quote! { None }
+    } else {
+        let tags = args.tags.iter();
+        quote! { Some(vec![#(#tags.to_string()),*]) }
+    };
+    let deprecated = if args.deprecated { quote! { Some(true) } } else { quote! { None } };
+
+    // the generated struct sits next to the function, which cannot be in an `impl` block
+    let mut arguments = Vec::new();
+    for input in function.sig.inputs.iter() {
+        match *input {
+            FnArg::Typed(ref typed) => arguments.push(argument_statements(&typed.ty)),
+            FnArg::Receiver(ref receiver) => {
+                return Err(syn::Error::new_spanned(receiver, "only free functions are supported"))
+            }
+        }
+    }
+
+    let status = args.status.unwrap_or_else(|| "200".to_string());
+    let success = match function.sig.output {
+        ReturnType::Type(_, ref ty) => match json_body(ty) {
+            Some(body) => quote! {
+                Some(("application/json", <#body as ::actix_web_openapi::schema::OpenApiSchema>::schema_ref(schemas)))
+            },
+            None if is_text(ty) => quote! {
+                Some(("text/plain", <String as ::actix_web_openapi::schema::OpenApiSchema>::schema_ref(schemas)))
+            },
+            None => quote! { None },
+        },
+        ReturnType::Default => quote! { None },
+    };
+    let responses = args.responses.iter().map(|(status, description)| {
+        quote! { ::actix_web_openapi::handler::response(&mut operation, #status, #description, None); }
+    });
+
+    Ok(quote! {
+        #[allow(non_camel_case_types)]
+        #vis struct #ident {}
+
+        impl ::actix_web_openapi::handler::OpenApiOperation for #ident {
+            fn method() -> &'static str {
+                #method
+            }
+
+            fn path() -> &'static str {
+                #path
+            }
+
+            fn operation(schemas: &mut ::actix_web_openapi::schema::Schemas) -> ::actix_web_openapi::operation::Operation {
+                const PATH: &str = #path;
+                let mut operation = ::actix_web_openapi::operation::Operation {
+                    operation_id: Some(#operation_id.to_string()),
+                    summary: #summary,
+                    description: #description,
+                    tags: #tags,
+                    deprecated: #deprecated,
+                    ..Default::default()
+                };
+                #(#arguments)*
+                ::actix_web_openapi::handler::response(&mut operation, #status, "Successful response", #success);
+                #(#responses)*
+                operation
+            }
+        }
+    })
+}
+
+fn option_tokens(value: Option<String>) -> TokenStream2 {
+    match value {
+        Some(value) => quote! { Some(#value.to_string()) },
+        None => quote! { None },
+    }
+}
@@ -0,0 +1,430 @@
+//! The `openapi` command, for everyday operations on specs. Built with the `cli` feature,
+//! and the `docs` feature for the `docs` subcommand.
+//!
+//! Reports are written to stdout, as text or with `--format json` as JSON. Specs are
+//! written to stdout or to `--output`, as YAML or with `--to json` as JSON. The exit code is
+//! 0 on success, 1 when the spec is invalid, has lint errors or breaking changes, and 2 when
+//! the command fails. Warnings and invalid arguments are reported on stderr, also as JSON with
+//! `--format json`. A reader closing stdout early, e.g. `head`, is not an error.
+
+use std::env;
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+use std::process;
+
+use actix_web::http::Method;
+use clap::{App, AppSettings, Arg, ArgMatches, ErrorKind, SubCommand};
+use serde_json::json;
+
+use actix_web_openapi::conversion::LossyConversion;
+use actix_web_openapi::diff;
+use actix_web_openapi::filter::Filter;
+use actix_web_openapi::lint::{self, Config, Linter};
+use actix_web_openapi::merge::{Conflict, Merger};
+use actix_web_openapi::mock::MockServer;
+use actix_web_openapi::spec::Spec;
+use actix_web_openapi::{bundle, openapi31, validation};
+use actix_web_openapi::{
+    from_path, from_path_with, from_path_with_report, to_json, to_yaml, Error, Format, LoadOptions, Result,
+};
+
+/// The spec is invalid, has lint errors or breaking changes.
+const EXIT_PROBLEMS: i32 = 1;
+/// The command could not be run.
+const EXIT_ERROR: i32 = 2;
+
+/// Write a line to stdout, failing rather than panicking when stdout is closed.
+macro_rules! outln {
+    ($($arg:tt)*) => {
+        writeln!(io::stdout().lock(), $($arg)*)?
+    };
+}
+
+fn main() {
+    let matches = match app().get_matches_safe() {
+        Ok(matches) => matches,
+        Err(e) => match e.kind {
+            ErrorKind::HelpDisplayed | ErrorKind::VersionDisplayed => e.exit(),
+            // the arguments could not be parsed, so look for `--format json` by hand
+            _ if json_requested() => {
+                eprintln!("{}", json!({ "error": e.message }));
+                process::exit(EXIT_ERROR);
+            }
+            _ => {
+                eprintln!("{}", e.message);
+                process::exit(EXIT_ERROR);
+            }
+        },
+    };
+    let (name, subcommand) = matches.subcommand();
+    let subcommand = match subcommand {
+        Some(subcommand) => subcommand,
+        None => process::exit(EXIT_ERROR),
+    };
+    let json = subcommand.value_of("format") == Some("json");
+
+    match run(name, subcommand, json) {
+        Ok(true) => {}
+        Ok(false) => process::exit(EXIT_PROBLEMS),
+        Err(Error::Io(ref e)) if e.kind() == io::ErrorKind::BrokenPipe => {}
+        Err(e) => {
+            if json {
+                let _ = writeln!(io::stdout().lock(), "{}", json!({ "error": e.to_string() }));
+            } else {
+                eprintln!("error: {}", e);
+            }
+            process::exit(EXIT_ERROR);
+        }
+    }
+}
+
+fn json_requested() -> bool {
+    let args = env::args().collect::<Vec<_>>();
+    args.iter().any(|arg| arg == "--format=json")
+        || args.windows(2).any(|pair| pair[0] == "--format" && pair[1] == "json")
+}
+
+fn app() -> App<'static, 'static> {
+    let spec = || Arg::with_name("spec").help("The spec file, YAML or JSON").required(true);
+    let output = || {
+        vec![
+            Arg::with_name("output")
+                .long("output")
+                .short("o")
+                .takes_value(true)
+                .help("Write the spec to a file rather than to stdout"),
+            Arg::with_name("to")
+                .long("to")
+                .takes_value(true)
+                .possible_values(&["yaml", "json"])
+                .help("The format of the spec written, by default that of the output file or YAML"),
+        ]
+    };
+    let addr = || {
+        Arg::with_name("addr")
+            .long("addr")
+            .takes_value(true)
+            .default_value("127.0.0.1:8080")
+            .help("The address to listen on")
+    };
+
+    App::new("openapi")
+        .version(env!("CARGO_PKG_VERSION"))
+        .about("Validate, lint, convert, diff, merge and serve OpenApi specs")
+        .setting(AppSettings::SubcommandRequiredElseHelp)
+        .setting(AppSettings::VersionlessSubcommands)
+        .arg(
+            Arg::with_name("format")
+                .long("format")
+                .global(true)
+                .takes_value(true)
+                .possible_values(&["text", "json"])
+                .default_value("text")
+                .help("The format of reports and errors"),
+        )
+        .subcommand(
+            SubCommand::with_name("validate")
+                .about("Check that a spec loads and that its examples match their schemas")
+                .arg(spec())
+                .arg(Arg::with_name("strict").long("strict").help("Reject unsupported versions")),
+        )
+        .subcommand(
+            SubCommand::with_name("lint")
+                .about("Check a spec against style rules")
+                .arg(spec())
+                .arg(
+                    Arg::with_name("config")
+                        .long("config")
+                        .takes_value(true)
+                        .help("The rules configuration, YAML or TOML"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("convert")
+                .about("Convert a spec between YAML and JSON, Swagger 2.0 to OpenApi 3.0, or OpenApi 3.1 to 3.0")
+                .arg(spec())
+                .args(&output())
+                .arg(
+                    Arg::with_name("openapi")
+                        .long("openapi")
+                        .takes_value(true)
+                        .possible_values(&["3.0"])
+                        .help("Downgrade OpenApi 3.1 specs to this version"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("bundle")
+                .about("Bring a spec split across files into one document")
+                .arg(spec())
+                .args(&output()),
+        )
+        .subcommand(
+            SubCommand::with_name("dereference")
+                .about("Replace the local references of a spec with their targets")
+                .arg(spec())
+                .args(&output()),
+        )
+        .subcommand(
+            SubCommand::with_name("diff")
+                .about("List the changes between two versions of a spec, failing on breaking ones")
+                .arg(Arg::with_name("old").help("The old spec").required(true))
+                .arg(Arg::with_name("new").help("The new spec").required(true)),
+        )
+        .subcommand(
+            SubCommand::with_name("merge")
+                .about("Merge specs into one document")
+                .arg(
+                    Arg::with_name("specs")
+                        .help("The spec files, each optionally followed by a path prefix, e.g. store.yaml@/store")
+                        .required(true)
+                        .multiple(true),
+                )
+                .arg(Arg::with_name("title").long("title").takes_value(true).help("The title of the merged spec"))
+                .arg(
+                    Arg::with_name("conflict")
+                        .long("conflict")
+                        .takes_value(true)
+                        .possible_values(&["fail", "keep-first", "rename"])
+                        .default_value("rename")
+                        .help("How to resolve names defined differently by several specs"),
+                )
+                .args(&output()),
+        )
+        .subcommand(
+            SubCommand::with_name("filter")
+                .about("Keep the operations of a spec that match all the criteria given")
+                .arg(spec())
+                .arg(Arg::with_name("tag").long("tag").takes_value(true).multiple(true).number_of_values(1))
+                .arg(
+                    Arg::with_name("path")
+                        .long("path")
+                        .takes_value(true)
+                        .multiple(true)
+                        .number_of_values(1)
+                        .help("A path glob, e.g. /pets/**"),
+                )
+                .arg(Arg::with_name("method").long("method").takes_value(true).multiple(true).number_of_values(1))
+                .arg(
+                    Arg::with_name("without-extension")
+                        .long("without-extension")
+                        .takes_value(true)
+                        .multiple(true)
+                        .number_of_values(1)
+                        .help("Remove operations with an extension value, e.g. x-internal=true"),
+                )
+                .args(&output()),
+        )
+        .subcommand(
+            SubCommand::with_name("mock")
+                .about("Serve canned responses for the operations of a spec")
+                .arg(spec())
+                .arg(addr()),
+        )
+        .subcommand(
+            SubCommand::with_name("docs")
+                .about("Serve the documentation of a spec")
+                .arg(spec())
+                .arg(addr()),
+        )
+}
+
+/// Run a subcommand, returning whether the spec passed its checks.
+fn run(name: &str, matches: &ArgMatches, json: bool) -> Result<bool> {
+    let spec = || from_path(matches.value_of("spec").unwrap_or_default());
+    match name {
+        "validate" => validate(matches, json),
+        "lint" => {
+            let linter = match matches.value_of("config") {
+                Some(config) => Linter::new().configure(&Config::from_path(config)?)?,
+                None => Linter::new(),
+            };
+            let problems = linter.lint(&spec()?);
+            if json {
+                outln!("{}", lint::to_json(&problems)?);
+            } else {
+                write!(io::stdout().lock(), "{}", lint::to_text(&problems))?;
+            }
+            Ok(!lint::has_errors(&problems))
+        }
+        "convert" => {
+            let path = matches.value_of("spec").unwrap_or_default();
+            let conversion = from_path_with_report(path, LoadOptions::default())?;
+            print_lossy(&conversion.lossy, json);
+            let mut spec = conversion.spec;
+            if matches.value_of("openapi").is_some() && spec.is_openapi31() {
+                let conversion = openapi31::downgrade(&spec);
+                print_lossy(&conversion.lossy, json);
+                spec = conversion.spec;
+            }
+            write_spec(&spec, matches)
+        }
+        "bundle" => write_spec(&bundle::bundle(matches.value_of("spec").unwrap_or_default())?, matches),
+        "dereference" => write_spec(&bundle::dereference(&spec()?)?, matches),
+        "diff" => {
+            let old = from_path(matches.value_of("old").unwrap_or_default())?;
+            let new = from_path(matches.value_of("new").unwrap_or_default())?;
+            let changes = diff::diff(&old, &new);
+            let breaking = diff::is_breaking(&changes);
+            if json {
+                outln!("{}", serde_json::to_string_pretty(&json!({ "breaking": breaking, "changes": changes }))?);
+            } else {
+                for change in changes.iter() {
+                    outln!("{}", change);
+                }
+                let count = changes.iter().filter(|change| change.breaking).count();
+                outln!("{} changes, {} breaking", changes.len(), count);
+            }
+            Ok(!breaking)
+        }
+        "merge" => merge(matches),
+        "filter" => {
+            let mut filter = Filter::new();
+            for tag in matches.values_of("tag").into_iter().flatten() {
+                filter = filter.tag(tag);
+            }
+            for glob in matches.values_of("path").into_iter().flatten() {
+                filter = filter.path(glob);
+            }
+            for method in matches.values_of("method").into_iter().flatten() {
+                let method = Method::from_bytes(method.to_uppercase().as_bytes())
+                    .map_err(|_| Error::InvalidParameter("method".to_string(), method.to_string()))?;
+                filter = filter.method(method);
+            }
+            for extension in matches.values_of("without-extension").into_iter().flatten() {
+                let (name, value) = match extension.find('=') {
+                    Some(i) => (&extension[..i], &extension[i + 1..]),
+                    None => (extension, "true"),
+                };
+                // `true` or `1` are JSON values, anything else a string
+                let value = serde_json::from_str(value).unwrap_or_else(|_| json!(value));
+                filter = filter.without_extension(name, value);
+            }
+            write_spec(&filter.apply(&spec()?)?, matches)
+        }
+        "mock" => {
+            let addr = matches.value_of("addr").unwrap_or_default();
+            eprintln!("Serving a mock on http://{}", addr);
+            MockServer::new(spec()?).run(addr)?;
+            Ok(true)
+        }
+        "docs" => docs(spec()?, matches.value_of("addr").unwrap_or_default()),
+        _ => Ok(true),
+    }
+}
+
+fn validate(matches: &ArgMatches, json: bool) -> Result<bool> {
+    let options = LoadOptions {
+        strict: matches.is_present("strict"),
+    };
+    let spec = match from_path_with(matches.value_of("spec").unwrap_or_default(), options) {
+        Ok(spec) => spec,
+        Err(e @ Error::Io(_)) => return Err(e),
+        Err(e) => {
+            if json {
+                outln!("{}", serde_json::to_string_pretty(&json!({ "valid": false, "error": e.to_string() }))?);
+            } else {
+                outln!("{}", e);
+            }
+            return Ok(false);
+        }
+    };
+
+    let errors = validation::validate_examples(&spec);
+    if json {
+        let report = json!({ "valid": errors.is_empty(), "examples": errors });
+        outln!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        for error in errors.iter() {
+            outln!("{}", error);
+        }
+        match errors.len() {
+            0 => outln!("{} is valid", spec.info.title),
+            count => outln!("{} examples do not match their schemas", count),
+        }
+    }
+    Ok(errors.is_empty())
+}
+
+fn merge(matches: &ArgMatches) -> Result<bool> {
+    let conflict = match matches.value_of("conflict") {
+        Some("fail") => Conflict::Fail,
+        Some("keep-first") => Conflict::KeepFirst,
+        _ => Conflict::default(),
+    };
+    let mut merger = Merger::new().conflict(conflict);
+    for source in matches.values_of("specs").into_iter().flatten() {
+        let (path, prefix) = match source.rfind('@') {
+            Some(i) => (&source[..i], Some(&source[i + 1..])),
+            None => (source, None),
+        };
+        let name = Path::new(path)
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or(path)
+            .to_string();
+        let spec = from_path(path)?;
+        merger = match prefix {
+            Some(prefix) => merger.prefixed_source(&name, prefix, spec),
+            None => merger.source(&name, spec),
+        };
+    }
+    let mut spec = merger.merge()?;
+    if let Some(title) = matches.value_of("title") {
+        spec.info.title = title.to_string();
+    }
+    write_spec(&spec, matches)
+}
+
+/// Report what converting a spec lost on stderr.
+fn print_lossy(lossy: &[LossyConversion], json: bool) {
+    for lossy in lossy.iter() {
+        if json {
+            eprintln!("{}", json!({ "warning": { "path": lossy.path, "message": lossy.message } }));
+        } else {
+            eprintln!("warning: {}: {}", lossy.path, lossy.message);
+        }
+    }
+}
+
+#[cfg(feature = "docs")]
+fn docs(spec: Spec, addr: &str) -> Result<bool> {
+    use actix_web_openapi::docs::{self, DocsConfig};
+    use actix_web_openapi::serve::{self, SpecEndpoint};
+
+    let config = DocsConfig::new("/openapi.json").title(&spec.info.title);
+    let endpoint = SpecEndpoint::new(spec)?;
+    eprintln!("Serving the documentation on http://{}/docs", addr);
+    actix_web::server::new(move || {
+        let app = serve::mount(actix_web::App::new(), "/openapi", endpoint.clone());
+        docs::mount(app, "/docs", config.clone())
+    })
+    .bind(addr)?
+    .run();
+    Ok(true)
+}
+
+#[cfg(not(feature = "docs"))]
+fn docs(_spec: Spec, _addr: &str) -> Result<bool> {
+    let message = "the command was built without the docs feature".to_string();
+    Err(Error::InvalidParameter("docs".to_string(), message))
+}
+
+/// Write a spec to the output, or to stdout.
+fn write_spec(spec: &Spec, matches: &ArgMatches) -> Result<bool> {
+    let output = matches.value_of("output");
+    let format = match matches.value_of("to") {
+        Some("json") => Format::Json,
+        Some(_) => Format::Yaml,
+        None => output.and_then(Format::from_path).unwrap_or(Format::Yaml),
+    };
+    let text = match format {
+        Format::Json => to_json(spec)?,
+        Format::Yaml => to_yaml(spec)?,
+    };
+    match output {
+        Some(output) => fs::write(output, text)?,
+        None => outln!("{}", text),
+    }
+    Ok(true)
+}
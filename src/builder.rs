@@ -0,0 +1,552 @@
+//! Fluent builders for constructing a `Spec` in code.
+//!
+//! ```ignore
+//! let spec = Spec::builder()
+//!     .title("Petstore")
+//!     .version("1.0.0")
+//!     .server("http://petstore.swagger.io/v1")
+//!     .path("/pets/{id}", |p| {
+//!         p.get(|op| {
+//!             op.operation_id("getPet")
+//!                 .path_param::<u64>("id")
+//!                 .json_response::<Pet, _>(200, "the pet")
+//!         })
+//!     })
+//!     .build()?;
+//! ```
+//!
+//! Types passed as type parameters are described with `OpenApiSchema`; named types end up
+//! in `components.schemas`. Handlers annotated with `#[openapi_operation]` are added with
+//! `operation`. `build` checks that the result is a consistent document.
+
+use std::collections::HashSet;
+
+use crate::components::{Components, ObjectOrReference};
+use crate::handler::{path_template_names, OpenApiOperation};
+use crate::operation::Operation;
+use crate::path::{Parameter, PathItem};
+use crate::schema::{OpenApiSchema, Schemas};
+use crate::server::Server;
+use crate::spec::{Info, MediaType, RequestBody, Response, Schema, SecurityRequirement, SecurityScheme, Spec, Tag};
+use crate::{Error, Map, Result};
+
+/// The OpenApi version of built documents.
+const BUILT_VERSION: &str = "3.0.3";
+const JSON: &str = "application/json";
+
+impl Spec {
+    /// Start building a spec in code.
+    pub fn builder() -> SpecBuilder {
+        SpecBuilder::default()
+    }
+}
+
+/// Builds a `Spec`. See the module documentation.
+#[derive(Debug, Default)]
+pub struct SpecBuilder {
+    spec: Spec,
+    schemas: Schemas,
+}
+
+impl SpecBuilder {
+    /// The OpenApi version, `3.0.3` unless set.
+    pub fn openapi(mut self, version: &str) -> SpecBuilder {
+        self.spec.openapi = version.to_string();
+        self
+    }
+
+    pub fn info(mut self, info: Info) -> SpecBuilder {
+        self.spec.info = info;
+        self
+    }
+
+    pub fn title(mut self, title: &str) -> SpecBuilder {
+        self.spec.info.title = title.to_string();
+        self
+    }
+
+    /// The version of the described API.
+    pub fn version(mut self, version: &str) -> SpecBuilder {
+        self.spec.info.version = version.to_string();
+        self
+    }
+
+    pub fn description(mut self, description: &str) -> SpecBuilder {
+        self.spec.info.description = Some(description.to_string());
+        self
+    }
+
+    pub fn server(self, url: &str) -> SpecBuilder {
+        self.server_with(Server {
+            url: url.to_string(),
+            ..Default::default()
+        })
+    }
+
+    pub fn server_with(mut self, server: Server) -> SpecBuilder {
+        self.spec.servers.get_or_insert_with(Vec::new).push(server);
+        self
+    }
+
+    pub fn tag(mut self, name: &str, description: &str) -> SpecBuilder {
+        self.spec.tags.get_or_insert_with(Vec::new).push(Tag {
+            name: name.to_string(),
+            description: Some(description.to_string()),
+            extensions: Map::new(),
+        });
+        self
+    }
+
+    /// Describe the operations of `path`. Calling `path` again for the same path extends it.
+    pub fn path<F>(mut self, path: &str, build: F) -> SpecBuilder
+    where
+        F: FnOnce(PathItemBuilder) -> PathItemBuilder,
+    {
+        let item = self.spec.paths.get(path).cloned().unwrap_or_default();
+        let builder = build(PathItemBuilder {
+            item,
+            schemas: self.schemas,
+        });
+        self.schemas = builder.schemas;
+        self.spec.paths.insert(path.to_string(), builder.item);
+        self
+    }
+
+    /// Add the operation of a handler annotated with `#[openapi_operation]`. An operation
+    /// already declared for the same path and method is replaced.
+    pub fn operation<O: OpenApiOperation>(mut self) -> SpecBuilder {
+        let operation = O::operation(&mut self.schemas);
+        let item = self.spec.paths.entry(O::path().to_string()).or_insert_with(PathItem::default);
+        let slot = match O::method() {
+            "get" => &mut item.get,
+            "put" => &mut item.put,
+            "post" => &mut item.post,
+            "delete" => &mut item.delete,
+            "options" => &mut item.options,
+            "head" => &mut item.head,
+            "patch" => &mut item.patch,
+            "trace" => &mut item.trace,
+            method => {
+                warn!("Ignoring operation with unsupported method `{}`", method);
+                return self;
+            }
+        };
+        *slot = Some(operation);
+        self
+    }
+
+    /// Register the schema of `T` in `components.schemas`, e.g. for types only referenced
+    /// by hand written schemas.
+    pub fn schema<T: OpenApiSchema>(mut self) -> SpecBuilder {
+        T::schema_ref(&mut self.schemas);
+        self
+    }
+
+    /// Add a named schema to `components.schemas`.
+    pub fn component_schema(mut self, name: &str, schema: Schema) -> SpecBuilder {
+        self.schemas.insert(name.to_string(), ObjectOrReference::Object(schema));
+        self
+    }
+
+    pub fn security_scheme(mut self, name: &str, scheme: SecurityScheme) -> SpecBuilder {
+        self.components()
+            .security_schemes
+            .get_or_insert_with(Map::new)
+            .insert(name.to_string(), ObjectOrReference::Object(scheme));
+        self
+    }
+
+    /// Require `scheme` with `scopes` for every operation that does not declare its own security.
+    pub fn security(mut self, scheme: &str, scopes: &[&str]) -> SpecBuilder {
+        self.spec.security.get_or_insert_with(Vec::new).push(requirement(scheme, scopes));
+        self
+    }
+
+    fn components(&mut self) -> &mut Components {
+        self.spec.components.get_or_insert_with(Components::default)
+    }
+
+    /// The finished spec, or `Error::InvalidSpec` listing every inconsistency found, including
+    /// schema names registered by two different types.
+    pub fn build(mut self) -> Result<Spec> {
+        if self.spec.openapi.is_empty() {
+            self.spec.openapi = BUILT_VERSION.to_string();
+        }
+        let mut problems = self.schemas.collisions().to_vec();
+        if !self.schemas.is_empty() {
+            let schemas = std::mem::replace(&mut self.schemas, Schemas::new());
+            self.components().schemas.get_or_insert_with(Map::new).extend(schemas.into_map());
+        }
+
+        problems.extend(check(&self.spec));
+        if problems.is_empty() {
+            Ok(self.spec)
+        } else {
+            Err(Error::InvalidSpec(problems.join("; ")))
+        }
+    }
+}
+
+/// Builds a `PathItem` for `SpecBuilder::path`.
+#[derive(Debug)]
+pub struct PathItemBuilder {
+    item: PathItem,
+    schemas: Schemas,
+}
+
+macro_rules! operation_methods {
+    ($($method:ident),*) => {
+        $(
+            pub fn $method<F>(mut self, build: F) -> PathItemBuilder
+            where
+                F: FnOnce(OperationBuilder) -> OperationBuilder,
+            {
+                let builder = build(OperationBuilder {
+                    operation: self.item.$method.take().unwrap_or_default(),
+                    schemas: self.schemas,
+                });
+                self.schemas = builder.schemas;
+                self.item.$method = Some(builder.operation);
+                self
+            }
+        )*
+    };
+}
+
+impl PathItemBuilder {
+    operation_methods!(get, put, post, delete, options, head, patch, trace);
+
+    pub fn summary(mut self, summary: &str) -> PathItemBuilder {
+        self.item.summary = Some(summary.to_string());
+        self
+    }
+
+    pub fn description(mut self, description: &str) -> PathItemBuilder {
+        self.item.description = Some(description.to_string());
+        self
+    }
+
+    /// A parameter shared by all operations of the path.
+    pub fn parameter(mut self, parameter: Parameter) -> PathItemBuilder {
+        self.item
+            .parameters
+            .get_or_insert_with(Vec::new)
+            .push(ObjectOrReference::Object(parameter));
+        self
+    }
+
+    /// A required path parameter of type `T`, shared by all operations of the path.
+    pub fn path_param<T: OpenApiSchema>(mut self, name: &str) -> PathItemBuilder {
+        let parameter = parameter::<T>(&mut self.schemas, name, "path", true);
+        self.parameter(parameter)
+    }
+}
+
+/// Builds an `Operation` for the method functions of `PathItemBuilder`.
+#[derive(Debug)]
+pub struct OperationBuilder {
+    operation: Operation,
+    schemas: Schemas,
+}
+
+impl OperationBuilder {
+    pub fn operation_id(mut self, operation_id: &str) -> OperationBuilder {
+        self.operation.operation_id = Some(operation_id.to_string());
+        self
+    }
+
+    pub fn summary(mut self, summary: &str) -> OperationBuilder {
+        self.operation.summary = Some(summary.to_string());
+        self
+    }
+
+    pub fn description(mut self, description: &str) -> OperationBuilder {
+        self.operation.description = Some(description.to_string());
+        self
+    }
+
+    pub fn tag(mut self, tag: &str) -> OperationBuilder {
+        self.operation.tags.get_or_insert_with(Vec::new).push(tag.to_string());
+        self
+    }
+
+    pub fn deprecated(mut self) -> OperationBuilder {
+        self.operation.deprecated = Some(true);
+        self
+    }
+
+    pub fn security(mut self, scheme: &str, scopes: &[&str]) -> OperationBuilder {
+        self.operation
+            .security
+            .get_or_insert_with(Vec::new)
+            .push(requirement(scheme, scopes));
+        self
+    }
+
+    pub fn parameter(mut self, parameter: Parameter) -> OperationBuilder {
+        self.operation
+            .parameters
+            .get_or_insert_with(Vec::new)
+            .push(ObjectOrReference::Object(parameter));
+        self
+    }
+
+    /// A required path parameter of type `T`.
+    pub fn path_param<T: OpenApiSchema>(mut self, name: &str) -> OperationBuilder {
+        let parameter = parameter::<T>(&mut self.schemas, name, "path", true);
+        self.parameter(parameter)
+    }
+
+    /// A query parameter of type `T`, required unless `T` is an `Option`.
+    pub fn query_param<T: OpenApiSchema>(mut self, name: &str) -> OperationBuilder {
+        let parameter = parameter::<T>(&mut self.schemas, name, "query", !T::is_optional());
+        self.parameter(parameter)
+    }
+
+    /// A header parameter of type `T`, required unless `T` is an `Option`.
+    pub fn header_param<T: OpenApiSchema>(mut self, name: &str) -> OperationBuilder {
+        let parameter = parameter::<T>(&mut self.schemas, name, "header", !T::is_optional());
+        self.parameter(parameter)
+    }
+
+    /// A required request body of type `T` for each media type.
+    pub fn request_body<T: OpenApiSchema>(mut self, media_types: &[&str]) -> OperationBuilder {
+        let schema = T::schema_ref(&mut self.schemas);
+        self.operation.request_body = Some(ObjectOrReference::Object(RequestBody {
+            description: None,
+            content: content(schema, media_types),
+            required: Some(!T::is_optional()),
+            extensions: Map::new(),
+        }));
+        self
+    }
+
+    /// A required `application/json` request body of type `T`.
+    pub fn json_body<T: OpenApiSchema>(self) -> OperationBuilder {
+        self.request_body::<T>(&[JSON])
+    }
+
+    /// A response without a body. `code` is a status code or `default`.
+    pub fn response<C: ToString>(self, code: C, description: &str) -> OperationBuilder {
+        self.response_with(
+            code,
+            Response {
+                description: Some(description.to_string()),
+                ..Default::default()
+            },
+        )
+    }
+
+    /// An `application/json` response with a body of type `T`.
+    pub fn json_response<T: OpenApiSchema, C: ToString>(mut self, code: C, description: &str) -> OperationBuilder {
+        let schema = T::schema_ref(&mut self.schemas);
+        self.response_with(
+            code,
+            Response {
+                description: Some(description.to_string()),
+                content: Some(content(schema, &[JSON])),
+                ..Default::default()
+            },
+        )
+    }
+
+    pub fn response_with<C: ToString>(mut self, code: C, response: Response) -> OperationBuilder {
+        self.operation.responses.insert(code.to_string(), response);
+        self
+    }
+}
+
+fn parameter<T: OpenApiSchema>(schemas: &mut Schemas, name: &str, location: &str, required: bool) -> Parameter {
+    Parameter {
+        name: name.to_string(),
+        location: location.to_string(),
+        required: Some(required),
+        schema: Some(T::schema_ref(schemas)),
+        ..Default::default()
+    }
+}
+
+fn content(schema: Schema, media_types: &[&str]) -> Map<String, MediaType> {
+    media_types
+        .iter()
+        .map(|media_type| {
+            (
+                media_type.to_string(),
+                MediaType {
+                    schema: Some(ObjectOrReference::Object(schema.clone())),
+                    ..Default::default()
+                },
+            )
+        })
+        .collect()
+}
+
+fn requirement(scheme: &str, scopes: &[&str]) -> SecurityRequirement {
+    let mut requirement = SecurityRequirement::new();
+    requirement.insert(scheme.to_string(), scopes.iter().map(|scope| scope.to_string()).collect());
+    requirement
+}
+
+/// The inconsistencies of a built spec, as messages.
+fn check(spec: &Spec) -> Vec<String> {
+    let mut problems = Vec::new();
+    if spec.info.title.is_empty() {
+        problems.push("info.title is required".to_string());
+    }
+    if spec.info.version.is_empty() {
+        problems.push("info.version is required".to_string());
+    }
+
+    let parameters = spec.components.as_ref().and_then(|components| components.parameters.as_ref());
+    let mut operation_ids = HashSet::new();
+    for (path, item) in spec.paths.iter() {
+        if !path.starts_with('/') {
+            problems.push(format!("path `{}` must start with `/`", path));
+        }
+        let templated = path_template_names(path);
+
+        for (method, operation) in item.operations() {
+            let name = format!("{} {}", method, path);
+            if operation.responses.is_empty() {
+                problems.push(format!("{} declares no responses", name));
+            }
+            if let Some(ref operation_id) = operation.operation_id {
+                if !operation_ids.insert(operation_id.clone()) {
+                    problems.push(format!("operationId `{}` is not unique", operation_id));
+                }
+            }
+
+            let mut declared: Vec<&Parameter> = Vec::new();
+            for parameter in item.parameters.iter().chain(operation.parameters.iter()).flatten() {
+                match parameter.resolve(parameters) {
+                    Some(parameter) if parameter.location == "path" => declared.push(parameter),
+                    Some(_) => {}
+                    None => {
+                        if let ObjectOrReference::Ref { ref ref_path } = *parameter {
+                            problems.push(format!("{} references missing parameter `{}`", name, ref_path));
+                        }
+                    }
+                }
+            }
+            for template in templated.iter() {
+                if !declared.iter().any(|parameter| parameter.name == *template) {
+                    problems.push(format!("{} does not declare path parameter `{}`", name, template));
+                }
+            }
+            for parameter in declared.iter() {
+                if !templated.contains(&parameter.name.as_str()) {
+                    problems.push(format!("{} declares path parameter `{}` missing from the path", name, parameter.name));
+                } else if parameter.required != Some(true) {
+                    problems.push(format!("{} path parameter `{}` must be required", name, parameter.name));
+                }
+            }
+        }
+    }
+    problems
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Pet;
+
+    impl OpenApiSchema for Pet {
+        fn schema_name() -> Option<String> {
+            Some("Pet".to_string())
+        }
+
+        fn schema(schemas: &mut Schemas) -> Schema {
+            let mut properties = Map::new();
+            properties.insert("name".to_string(), String::schema_ref(schemas));
+            Schema {
+                schema_type: Some("object".into()),
+                properties: Some(properties),
+                ..Default::default()
+            }
+        }
+    }
+
+    #[test]
+    fn test_build() {
+        let spec = Spec::builder()
+            .title("Petstore")
+            .version("1.0.0")
+            .server("http://petstore.swagger.io/v1")
+            .path("/pets/{id}", |p| {
+                p.get(|op| {
+                    op.operation_id("getPet")
+                        .tag("pets")
+                        .path_param::<u64>("id")
+                        .query_param::<Option<bool>>("verbose")
+                        .json_response::<Pet, _>(200, "the pet")
+                        .response("default", "unexpected error")
+                })
+                .delete(|op| op.path_param::<u64>("id").response(204, "deleted"))
+            })
+            .build()
+            .unwrap();
+
+        assert_eq!(spec.openapi, "3.0.3");
+        let item = &spec.paths["/pets/{id}"];
+        let get = item.get.as_ref().unwrap();
+        assert_eq!(get.parameters.as_ref().unwrap().len(), 2);
+        assert_eq!(get.responses.keys().collect::<Vec<_>>(), vec!["200", "default"]);
+        assert!(item.delete.is_some());
+        assert!(spec.components.unwrap().schemas.unwrap().contains_key("Pet"));
+    }
+
+    #[test]
+    fn test_build_validates() {
+        let result = Spec::builder()
+            .path("/pets/{id}", |p| {
+                p.get(|op| op.operation_id("pet").response(200, "ok"))
+                    .post(|op| op.operation_id("pet").path_param::<u64>("name"))
+            })
+            .build();
+
+        match result {
+            Err(Error::InvalidSpec(problems)) => {
+                assert!(problems.contains("info.title is required"));
+                assert!(problems.contains("does not declare path parameter `id`"));
+                assert!(problems.contains("operationId `pet` is not unique"));
+                assert!(problems.contains("declares no responses"));
+            }
+            other => panic!("expected an invalid spec, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_check_referenced_parameters() {
+        let spec: Spec = serde_yaml::from_str(
+            r#"
+openapi: 3.0.3
+info: { title: Petstore, version: 1.0.0 }
+paths:
+  /pets/{id}:
+    parameters:
+      - $ref: '#/components/parameters/Id'
+    get:
+      responses: { '200': { description: ok } }
+  /pets:
+    get:
+      parameters:
+        - $ref: '#/components/parameters/Id'
+        - $ref: '#/components/parameters/Limit'
+      responses: { '200': { description: ok } }
+components:
+  parameters:
+    Id: { name: id, in: path, required: true, schema: { type: integer } }
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            check(&spec),
+            vec![
+                "GET /pets references missing parameter `#/components/parameters/Limit`".to_string(),
+                "GET /pets declares path parameter `id` missing from the path".to_string(),
+            ]
+        );
+    }
+}
@@ -0,0 +1,256 @@
+//! Resolve the references of a spec: `bundle` brings a spec split across files into one
+//! document, `dereference` replaces its local references with what they point to.
+//!
+//! References are replaced by a copy of their target, so a schema referred to from two
+//! places is copied twice; `cleanup::Cleanup::hoist_schemas` shares the copies again.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde_json::Value;
+
+use crate::spec::Spec;
+use crate::{Error, Format, Result};
+
+/// The fields whose keys are names, e.g. of properties or responses, rather than keywords.
+const NAMED: &[&str] = &[
+    "paths",
+    "properties",
+    "patternProperties",
+    "definitions",
+    "schemas",
+    "responses",
+    "parameters",
+    "examples",
+    "requestBodies",
+    "headers",
+    "securitySchemes",
+    "links",
+    "callbacks",
+    "content",
+    "encoding",
+    "variables",
+];
+
+/// Load the spec at `path`, replacing the references to other files, e.g.
+/// `schemas/pet.yaml#/Pet`, with their targets. Files are found relative to the file
+/// referring to them. References within the spec itself are kept, those within the other
+/// files are resolved as well.
+pub fn bundle<P>(path: P) -> Result<Spec>
+where
+    P: AsRef<Path>,
+{
+    let root = fs::canonicalize(path)?;
+    let mut inliner = Inliner {
+        documents: HashMap::new(),
+        stack: Vec::new(),
+        root: root.clone(),
+        dereference: false,
+    };
+    let mut document = inliner.load(&root)?;
+    inliner.inline(&mut document, &root, false)?;
+    crate::from_value(document)
+}
+
+/// Replace the local references of a spec, e.g. `#/components/schemas/Pet`, with their
+/// targets. References to other files are kept, and so are those that would have to be
+/// replaced within their own target, such as that of a recursive schema. The components
+/// themselves are kept.
+pub fn dereference(spec: &Spec) -> Result<Spec> {
+    let root = PathBuf::new();
+    let mut document = serde_json::to_value(spec)?;
+    let mut inliner = Inliner {
+        documents: HashMap::new(),
+        stack: Vec::new(),
+        root: root.clone(),
+        dereference: true,
+    };
+    inliner.documents.insert(root.clone(), document.clone());
+    inliner.inline(&mut document, &root, false)?;
+    crate::from_value(document)
+}
+
+struct Inliner {
+    /// The documents read so far, by canonical path.
+    documents: HashMap<PathBuf, Value>,
+    /// The targets being inlined, by file and pointer, to detect cycles.
+    stack: Vec<(PathBuf, String)>,
+    /// The document being resolved.
+    root: PathBuf,
+    /// Whether local references of the root are replaced, rather than other files.
+    dereference: bool,
+}
+
+impl Inliner {
+    fn load(&mut self, path: &Path) -> Result<Value> {
+        if let Some(document) = self.documents.get(path) {
+            return Ok(document.clone());
+        }
+        let bytes = fs::read(path)?;
+        let document: Value = match Format::from_path(path).unwrap_or_else(|| Format::detect(&bytes)) {
+            Format::Json => serde_json::from_slice(&bytes)?,
+            Format::Yaml => serde_yaml::from_slice(&bytes)?,
+        };
+        self.documents.insert(path.to_path_buf(), document.clone());
+        Ok(document)
+    }
+
+    /// Replace the references found in `value`, part of the document at `file`. `named` is
+    /// set for objects keyed by names, such as `properties`, rather than by keywords.
+    fn inline(&mut self, value: &mut Value, file: &Path, named: bool) -> Result<()> {
+        let object = match value {
+            Value::Object(object) => object,
+            Value::Array(items) => {
+                for item in items.iter_mut() {
+                    self.inline(item, file, false)?;
+                }
+                return Ok(());
+            }
+            _ => return Ok(()),
+        };
+
+        let reference = match object.get("$ref") {
+            Some(Value::String(reference)) => reference.clone(),
+            _ => {
+                for (key, value) in object.iter_mut() {
+                    // example values are data, but a property may be named `example`
+                    if named {
+                        self.inline(value, file, false)?;
+                    } else if key != "example" {
+                        self.inline(value, file, NAMED.contains(&key.as_str()))?;
+                    }
+                }
+                return Ok(());
+            }
+        };
+
+        let (target_file, pointer) = match reference.find('#') {
+            Some(i) => (&reference[..i], reference[i + 1..].to_string()),
+            None => (reference.as_str(), String::new()),
+        };
+        let target_file = if target_file.is_empty() {
+            file.to_path_buf()
+        } else if self.dereference {
+            return Ok(());
+        } else {
+            let relative = file.parent().unwrap_or_else(|| Path::new("")).join(target_file);
+            fs::canonicalize(&relative).map_err(|_| Error::UnresolvedReference(reference.clone()))?
+        };
+
+        if target_file == self.root && !self.dereference {
+            object.insert("$ref".to_string(), Value::String(format!("#{}", pointer)));
+            return Ok(());
+        }
+        let key = (target_file.clone(), pointer.clone());
+        if self.stack.contains(&key) && self.dereference {
+            return Ok(());
+        }
+        if self.stack.contains(&key) {
+            return Err(Error::UnresolvedReference(format!("{} (circular)", reference)));
+        }
+
+        let document = self.load(&target_file)?;
+        let mut target = match document.pointer(&pointer) {
+            Some(target) => target.clone(),
+            None => return Err(Error::UnresolvedReference(reference)),
+        };
+        self.stack.push(key);
+        self.inline(&mut target, &target_file, false)?;
+        self.stack.pop();
+        *value = target;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dereference() {
+        let spec = crate::from_path("data/v3.0/petstore.yaml").unwrap();
+        let spec = dereference(&spec).unwrap();
+
+        let json = serde_json::to_value(&spec).unwrap();
+        let pets = &json["paths"]["/pets"]["get"]["responses"]["200"]["content"]["application/json"]["schema"];
+        assert_eq!(pets["type"], "array");
+        assert_eq!(pets["items"]["properties"]["name"]["type"], "string");
+        assert!(json["components"]["schemas"]["Pet"].is_object());
+    }
+
+    #[test]
+    fn test_dereference_recursive() {
+        let spec = crate::from_str(
+            r#"
+openapi: 3.0.0
+info: {title: Tree, version: "1"}
+paths: {}
+components:
+  schemas:
+    Node:
+      type: object
+      properties:
+        children:
+          type: array
+          items: {$ref: '#/components/schemas/Node'}
+"#,
+        )
+        .unwrap();
+        let json = serde_json::to_value(dereference(&spec).unwrap()).unwrap();
+        let children = &json["components"]["schemas"]["Node"]["properties"]["children"];
+        assert_eq!(children["items"]["properties"]["children"]["items"]["$ref"], "#/components/schemas/Node");
+    }
+
+    #[test]
+    fn test_bundle() {
+        let dir = std::env::temp_dir().join(format!("openapi-bundle-{}", std::process::id()));
+        fs::create_dir_all(dir.join("schemas")).unwrap();
+        fs::write(
+            dir.join("openapi.yaml"),
+            r#"
+openapi: 3.0.0
+info: {title: Pets, version: "1"}
+paths:
+  /pets:
+    get:
+      responses:
+        '200':
+          description: The pets
+          content:
+            application/json:
+              schema: {$ref: 'schemas/pet.yaml#/Pets'}
+components:
+  schemas:
+    Error: {type: object}
+"#,
+        )
+        .unwrap();
+        fs::write(
+            dir.join("schemas/pet.yaml"),
+            r#"
+Pets:
+  type: array
+  items: {$ref: '#/Pet'}
+Pet:
+  type: object
+  properties:
+    error: {$ref: '../openapi.yaml#/components/schemas/Error'}
+    example: {$ref: '#/Tag'}
+  example: {tag: {$ref: not a reference}}
+Tag:
+  type: string
+"#,
+        )
+        .unwrap();
+
+        let spec = bundle(dir.join("openapi.yaml"));
+        fs::remove_dir_all(&dir).unwrap();
+        let json = serde_json::to_value(spec.unwrap()).unwrap();
+        let pets = &json["paths"]["/pets"]["get"]["responses"]["200"]["content"]["application/json"]["schema"];
+        assert_eq!(pets["type"], "array");
+        assert_eq!(pets["items"]["properties"]["error"]["$ref"], "#/components/schemas/Error");
+        assert_eq!(pets["items"]["properties"]["example"]["type"], "string");
+        assert_eq!(pets["items"]["example"]["tag"]["$ref"], "not a reference");
+    }
+}
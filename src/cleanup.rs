@@ -0,0 +1,473 @@
+//! Tidy the components of a spec: remove those nothing uses, and share the inline schemas
+//! it repeats.
+//!
+//! ```ignore
+//! let tidy = Cleanup::new().hoist_schemas(true).apply(&spec)?;
+//! ```
+//!
+//! Components are used when the paths, webhooks or security requirements of the document
+//! refer to them, directly or through other components. Schemas, responses, parameters,
+//! examples, request bodies, headers, links, callbacks, path items and security schemes
+//! that are not are removed.
+//!
+//! Hoisting moves an inline schema describing an object, an enumeration or a composition to
+//! `components.schemas` when it appears more than once, and replaces each copy with a
+//! `$ref`. Inline schemas equal to an existing component schema refer to it instead. New
+//! components are named after the `title` of the schema, else after where it first appears,
+//! e.g. `Owner` for an `owner` property or `ListPetsResponse` for the response of
+//! `listPets`.
+
+use std::collections::{HashMap, HashSet};
+
+use serde_json::{Map as JsonMap, Value};
+
+use crate::codegen::type_name;
+use crate::pointer;
+use crate::spec::Spec;
+use crate::Result;
+
+/// Removes unused components and optionally hoists repeated inline schemas.
+#[derive(Clone, Debug, Default)]
+pub struct Cleanup {
+    hoist_schemas: bool,
+}
+
+impl Cleanup {
+    pub fn new() -> Cleanup {
+        Cleanup::default()
+    }
+
+    /// Share repeated inline schemas as components, off by default.
+    pub fn hoist_schemas(mut self, hoist_schemas: bool) -> Cleanup {
+        self.hoist_schemas = hoist_schemas;
+        self
+    }
+
+    /// The spec with only the components it uses.
+    pub fn apply(&self, spec: &Spec) -> Result<Spec> {
+        let mut document = serde_json::to_value(spec)?;
+        if self.hoist_schemas {
+            hoist_schemas(&mut document);
+        }
+        remove_unused_components(&mut document);
+        crate::from_value(document)
+    }
+}
+
+/// Remove the components of a serialized spec that neither its paths, webhooks and security
+/// requirements nor the components they use refer to.
+pub fn remove_unused_components(document: &mut Value) {
+    let mut used = HashSet::new();
+    if let Value::Object(document) = &*document {
+        for (key, value) in document.iter().filter(|(key, _)| *key != "components") {
+            references(value, Some(key), &mut used);
+        }
+    }
+    let components = match document.get_mut("components") {
+        Some(Value::Object(components)) => components,
+        _ => return,
+    };
+
+    let mut pending: Vec<(String, String)> = used.iter().cloned().collect();
+    while let Some((kind, name)) = pending.pop() {
+        if let Some(component) = components.get(&kind).and_then(|definitions| definitions.get(&name)) {
+            let mut found = HashSet::new();
+            references(component, None, &mut found);
+            for reference in found {
+                if used.insert(reference.clone()) {
+                    pending.push(reference);
+                }
+            }
+        }
+    }
+
+    for (kind, definitions) in components.iter_mut() {
+        if let Value::Object(definitions) = definitions {
+            if !kind.starts_with("x-") {
+                definitions.retain(|name, _| used.contains(&(kind.clone(), name.clone())));
+            }
+        }
+    }
+    components.retain(|kind, definitions| match definitions {
+        Value::Object(definitions) => kind.starts_with("x-") || !definitions.is_empty(),
+        _ => true,
+    });
+    if components.is_empty() {
+        if let Value::Object(document) = document {
+            document.remove("components");
+        }
+    }
+}
+
+/// Collect the components `value` refers to, by kind and name: local `$ref`s, the schemes of
+/// security requirements and the schemas of discriminator mappings. `key` is the key
+/// `value` is found under.
+fn references(value: &Value, key: Option<&str>, found: &mut HashSet<(String, String)>) {
+    let component = |reference: &str| -> Option<(String, String)> {
+        let tokens = pointer::tokens(reference.strip_prefix('#')?);
+        match tokens.as_slice() {
+            [components, kind, name] if components == "components" => Some((kind.clone(), name.clone())),
+            _ => None,
+        }
+    };
+
+    match value {
+        Value::Object(object) => {
+            if let Some(Value::String(reference)) = object.get("$ref") {
+                found.extend(component(reference));
+            }
+            if key == Some("discriminator") {
+                let mapping = object.get("mapping").and_then(Value::as_object);
+                for target in mapping.into_iter().flatten().filter_map(|(_, target)| target.as_str()) {
+                    match component(target) {
+                        Some(reference) => found.insert(reference),
+                        None => found.insert(("schemas".to_string(), target.to_string())),
+                    };
+                }
+            }
+            for (key, value) in object.iter() {
+                references(value, Some(key), found);
+            }
+        }
+        Value::Array(items) => {
+            if key == Some("security") {
+                let requirements = items.iter().filter_map(Value::as_object);
+                for name in requirements.flat_map(|requirement| requirement.keys()) {
+                    found.insert(("securitySchemes".to_string(), name.clone()));
+                }
+            }
+            for item in items.iter() {
+                references(item, None, found);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Keys whose values are data rather than parts of the document.
+const DATA: &[&str] = &["example", "examples"];
+
+/// Schemas worth sharing, as opposed to e.g. `{type: string}`.
+fn is_shareable(schema: &JsonMap<String, Value>) -> bool {
+    !schema.contains_key("$ref")
+        && ["properties", "enum", "allOf", "oneOf", "anyOf"]
+            .iter()
+            .any(|key| schema.contains_key(*key))
+}
+
+/// A key for a schema that does not depend on the order of the fields of its objects.
+fn canonical(value: &Value) -> String {
+    match value {
+        Value::Object(object) => {
+            let mut fields: Vec<(&String, &Value)> = object.iter().collect();
+            fields.sort_by(|a, b| a.0.cmp(b.0));
+            let fields: Vec<String> = fields
+                .into_iter()
+                .map(|(key, value)| format!("{}:{}", Value::String(key.clone()), canonical(value)))
+                .collect();
+            format!("{{{}}}", fields.join(","))
+        }
+        Value::Array(items) => {
+            let items: Vec<String> = items.iter().map(canonical).collect();
+            format!("[{}]", items.join(","))
+        }
+        value => value.to_string(),
+    }
+}
+
+/// An inline schema found in the document.
+struct Found {
+    schema: Value,
+    count: usize,
+    hint: String,
+}
+
+/// Hoist repeated inline schemas one at a time, largest first, so that the schemas nested in
+/// a hoisted one are counted once rather than once per copy.
+fn hoist_schemas(document: &mut Value) {
+    loop {
+        let existing: HashMap<String, String> = document
+            .pointer("/components/schemas")
+            .and_then(Value::as_object)
+            .into_iter()
+            .flatten()
+            .map(|(name, schema)| (canonical(schema), name.clone()))
+            .collect();
+
+        let mut found: HashMap<String, Found> = HashMap::new();
+        inline_schemas(document, &mut |schema, hint| {
+            let key = canonical(schema);
+            let found = found.entry(key).or_insert_with(|| Found {
+                schema: schema.clone(),
+                count: 0,
+                hint: hint.to_string(),
+            });
+            found.count += 1;
+            true
+        });
+        let hoisted = found
+            .into_iter()
+            .filter(|(key, found)| found.count > 1 || existing.contains_key(key))
+            .max_by(|a, b| a.0.len().cmp(&b.0.len()).then_with(|| b.0.cmp(&a.0)));
+        let (key, found) = match hoisted {
+            Some(hoisted) => hoisted,
+            None => return,
+        };
+
+        let name = match existing.get(&key) {
+            Some(name) => name.clone(),
+            None => {
+                let title = found.schema.get("title").and_then(Value::as_str);
+                let name = title.map(type_name).unwrap_or(found.hint);
+                let taken = |name: &str| existing.values().any(|existing| existing == name);
+                let mut candidate = name.clone();
+                let mut number = 2;
+                while taken(&candidate) {
+                    candidate = format!("{}{}", name, number);
+                    number += 1;
+                }
+                add_schema(document, &candidate, found.schema);
+                candidate
+            }
+        };
+
+        let reference = Value::String(format!("#{}", pointer::push("/components/schemas", &name)));
+        inline_schemas(document, &mut |schema, _| {
+            if canonical(schema) != key {
+                return true;
+            }
+            let mut object = JsonMap::new();
+            object.insert("$ref".to_string(), reference.clone());
+            *schema = Value::Object(object);
+            false
+        });
+    }
+}
+
+fn add_schema(document: &mut Value, name: &str, schema: Value) {
+    let document = match document {
+        Value::Object(document) => document,
+        _ => return,
+    };
+    let components = document
+        .entry("components")
+        .or_insert_with(|| Value::Object(JsonMap::new()));
+    if let Value::Object(components) = components {
+        let schemas = components
+            .entry("schemas")
+            .or_insert_with(|| Value::Object(JsonMap::new()));
+        if let Value::Object(schemas) = schemas {
+            schemas.insert(name.to_string(), schema);
+        }
+    }
+}
+
+/// Call `visit` with the shareable inline schemas of a document and a name for each,
+/// outermost first, descending into a schema when `visit` returns true. Component schemas
+/// are not visited themselves, only the schemas nested in them.
+fn inline_schemas<F>(document: &mut Value, visit: &mut F)
+where
+    F: FnMut(&mut Value, &str) -> bool,
+{
+    if let Value::Object(document) = document {
+        for (key, value) in document.iter_mut() {
+            if key == "components" {
+                let components = value.as_object_mut().into_iter().flatten();
+                for (kind, components) in components.filter(|(kind, _)| !DATA.contains(&kind.as_str())) {
+                    for (name, component) in components.as_object_mut().into_iter().flatten() {
+                        if kind == "schemas" {
+                            nested_schemas(component, &type_name(name), visit);
+                        } else {
+                            walk(component, &type_name(name), visit);
+                        }
+                    }
+                }
+            } else if !key.starts_with("x-") {
+                walk(value, "", visit);
+            }
+        }
+    }
+}
+
+/// Find the schemas in a part of a document that is not a schema. `operation` names the
+/// operation it belongs to, if any.
+fn walk<F>(value: &mut Value, operation: &str, visit: &mut F)
+where
+    F: FnMut(&mut Value, &str) -> bool,
+{
+    match value {
+        Value::Object(object) => {
+            let operation = match object.get("operationId").and_then(Value::as_str) {
+                Some(operation_id) => type_name(operation_id),
+                None => operation.to_string(),
+            };
+            let parameter = match (object.get("in"), object.get("name").and_then(Value::as_str)) {
+                (Some(_), Some(name)) => type_name(name),
+                _ => String::new(),
+            };
+            for (key, value) in object.iter_mut() {
+                if DATA.contains(&key.as_str()) || key.starts_with("x-") {
+                    continue;
+                }
+                match key.as_str() {
+                    "schema" => {
+                        let hint = format!("{}{}", operation, parameter);
+                        schema(value, if hint.is_empty() { "Schema" } else { &hint }, visit);
+                    }
+                    "requestBody" => walk(value, &format!("{}Request", operation), visit),
+                    "responses" => walk(value, &format!("{}Response", operation), visit),
+                    _ => walk(value, &operation, visit),
+                }
+            }
+        }
+        Value::Array(items) => {
+            for item in items.iter_mut() {
+                walk(item, operation, visit);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn schema<F>(value: &mut Value, hint: &str, visit: &mut F)
+where
+    F: FnMut(&mut Value, &str) -> bool,
+{
+    let shareable = match value {
+        Value::Object(object) => is_shareable(object),
+        _ => return,
+    };
+    if !shareable || visit(value, hint) {
+        nested_schemas(value, hint, visit);
+    }
+}
+
+fn nested_schemas<F>(value: &mut Value, hint: &str, visit: &mut F)
+where
+    F: FnMut(&mut Value, &str) -> bool,
+{
+    let object = match value {
+        Value::Object(object) => object,
+        _ => return,
+    };
+    for (key, value) in object.iter_mut() {
+        match key.as_str() {
+            "properties" => {
+                for (name, property) in value.as_object_mut().into_iter().flatten() {
+                    schema(property, &type_name(name), visit);
+                }
+            }
+            "items" => schema(value, &format!("{}Item", hint), visit),
+            "additionalProperties" => schema(value, &format!("{}Value", hint), visit),
+            "not" => schema(value, hint, visit),
+            "allOf" | "oneOf" | "anyOf" => {
+                for part in value.as_array_mut().into_iter().flatten() {
+                    schema(part, hint, visit);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SPEC: &str = r#"
+openapi: 3.0.0
+info: {title: Pets, version: "1"}
+paths:
+  /pets:
+    get:
+      operationId: listPets
+      responses:
+        '200':
+          description: The pets
+          content:
+            application/json:
+              schema:
+                type: array
+                items:
+                  type: object
+                  properties:
+                    name: {type: string}
+                    owner:
+                      type: object
+                      properties:
+                        name: {type: string}
+  /pets/{id}:
+    get:
+      operationId: showPet
+      parameters:
+        - {name: id, in: path, required: true, schema: {type: string}}
+      responses:
+        '200':
+          description: The pet
+          content:
+            application/json:
+              schema:
+                properties:
+                  owner:
+                    properties:
+                      name: {type: string}
+                    type: object
+                  name: {type: string}
+                type: object
+        default:
+          description: An error
+          content:
+            application/json:
+              schema:
+                type: object
+                properties:
+                  message: {type: string}
+components:
+  schemas:
+    Error:
+      type: object
+      properties:
+        message: {type: string}
+    Unused:
+      type: object
+  responses:
+    Unused:
+      description: Not referred to
+      content:
+        application/json:
+          schema: {$ref: '#/components/schemas/Unused'}
+"#;
+
+    #[test]
+    fn test_remove_unused() {
+        let spec = crate::from_str(SPEC).unwrap();
+        let tidy = Cleanup::new().apply(&spec).unwrap();
+
+        // the error schema is inline until hoisted
+        assert!(tidy.components.is_none());
+        assert_eq!(tidy.paths, spec.paths);
+    }
+
+    #[test]
+    fn test_hoist_schemas() {
+        let spec = crate::from_str(SPEC).unwrap();
+        let tidy = Cleanup::new().hoist_schemas(true).apply(&spec).unwrap();
+
+        let document = serde_json::to_value(&tidy).unwrap();
+        let list = "/paths/~1pets/get/responses/200/content/application~1json/schema";
+        assert_eq!(document.pointer(&format!("{}/items/$ref", list)).unwrap(), "#/components/schemas/ListPetsResponseItem");
+        let show = "/paths/~1pets~1{id}/get/responses";
+        let pet = document.pointer(&format!("{}/200/content/application~1json/schema", show)).unwrap();
+        assert_eq!(pet["$ref"], "#/components/schemas/ListPetsResponseItem");
+        let error = document.pointer(&format!("{}/default/content/application~1json/schema", show)).unwrap();
+        assert_eq!(error["$ref"], "#/components/schemas/Error");
+
+        let schemas = document.pointer("/components/schemas").unwrap().as_object().unwrap();
+        let mut names: Vec<&String> = schemas.keys().collect();
+        names.sort();
+        assert_eq!(names, vec!["Error", "ListPetsResponseItem"]);
+        // the owner is nested in the shared schema only
+        assert_eq!(schemas["ListPetsResponseItem"]["properties"]["owner"]["type"], "object");
+        assert_eq!(document.pointer(&format!("{}/parameters/0/schema/type", show.trim_end_matches("/responses"))).unwrap(), "string");
+    }
+}
@@ -0,0 +1,320 @@
+//! Send requests for the operations of a spec with actix-web's client.
+//!
+//! Clients generated by `codegen::ClientGenerator` are made of these. An `OperationRequest`
+//! collects parameters serialized with `Parameter::serialize`, the credentials of a satisfied
+//! security requirement and a body. `send` resolves it against a base URL, usually one of
+//! the spec's servers expanded with `Server::url_with`, and hands the response status and
+//! body to a decoding closure.
+
+use actix_web::client::ClientRequest;
+use actix_web::HttpMessage;
+use futures::{future, Future};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use url::form_urlencoded;
+use url::percent_encoding::{utf8_percent_encode, PATH_SEGMENT_ENCODE_SET};
+
+pub use actix_web::http::Method;
+
+use crate::path::{Parameter, ParameterStyle};
+use crate::spec::SecurityScheme;
+use crate::{Error, Map, Result};
+
+/// The largest response body read, in bytes.
+const BODY_LIMIT: usize = 16 * 1024 * 1024;
+
+/// The response of an operation, decoded.
+pub type ResponseFuture<T> = Box<dyn Future<Item = T, Error = Error>>;
+
+/// A credential for a security scheme of the spec.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Credential {
+    /// The key of an `apiKey` scheme.
+    ApiKey(String),
+    /// The user and password of an `http` scheme of the `basic` scheme.
+    Basic { username: String, password: String },
+    /// The token of an `http` scheme of the `bearer` scheme, or of an `oauth2` or
+    /// `openIdConnect` scheme.
+    Bearer(String),
+}
+
+/// A request for an operation, built up by a generated client.
+///
+/// Building does not fail: the first error, e.g. of a parameter that does not serialize, is
+/// kept and returned by `send`.
+#[derive(Debug)]
+pub struct OperationRequest {
+    method: Method,
+    path: String,
+    query: Vec<(String, String)>,
+    headers: Vec<(String, String)>,
+    cookies: Vec<(String, String)>,
+    body: Option<(String, Vec<u8>)>,
+    error: Option<Error>,
+}
+
+impl OperationRequest {
+    /// A request for the operation at the templated `path`, e.g. `/pets/{id}`.
+    pub fn new(method: Method, path: &str) -> OperationRequest {
+        OperationRequest {
+            method,
+            path: path.to_string(),
+            query: Vec::new(),
+            headers: Vec::new(),
+            cookies: Vec::new(),
+            body: None,
+            error: None,
+        }
+    }
+
+    /// Add the parameter `name` in `location`, serialized as `style` and `explode` describe or
+    /// as their defaults for the location.
+    pub fn parameter<T>(
+        &mut self,
+        location: &str,
+        name: &str,
+        style: Option<ParameterStyle>,
+        explode: Option<bool>,
+        value: &T,
+    ) -> &mut OperationRequest
+    where
+        T: Serialize,
+    {
+        let value = match serde_json::to_value(value) {
+            Ok(value) => value,
+            Err(e) => return self.fail(Error::Serialize(e)),
+        };
+        let parameter = Parameter {
+            name: name.to_string(),
+            location: location.to_string(),
+            style,
+            explode,
+            ..Default::default()
+        };
+
+        for (name, value) in parameter.serialize(&value) {
+            match location {
+                "path" => {
+                    let encoded = utf8_percent_encode(&value, PATH_SEGMENT_ENCODE_SET).to_string();
+                    self.path = self.path.replace(&format!("{{{}}}", name), &encoded);
+                }
+                "header" => self.headers.push((name, value)),
+                "cookie" => self.cookies.push((name, value)),
+                _ => self.query.push((name, value)),
+            }
+        }
+        self
+    }
+
+    /// Apply the credentials of the first of `requirements`, each a list of scheme names,
+    /// for which `credentials` has every scheme. An empty requirement, making authentication
+    /// optional, is only used when no other is satisfied. Without a satisfied requirement the
+    /// request is sent as is.
+    pub fn authorize(
+        &mut self,
+        requirements: &[&[&str]],
+        schemes: &Map<String, SecurityScheme>,
+        credentials: &Map<String, Credential>,
+    ) -> &mut OperationRequest {
+        let satisfied = requirements.iter().find(|requirement| {
+            !requirement.is_empty()
+                && requirement
+                    .iter()
+                    .all(|name| schemes.contains_key(*name) && credentials.contains_key(*name))
+        });
+        match satisfied {
+            Some(requirement) => {
+                for name in requirement.iter() {
+                    self.credential(&schemes[*name], &credentials[*name]);
+                }
+            }
+            None if requirements.iter().any(|requirement| requirement.is_empty()) => {}
+            None if !requirements.is_empty() => debug!("No credentials for {} {}", self.method, self.path),
+            None => {}
+        }
+        self
+    }
+
+    fn credential(&mut self, scheme: &SecurityScheme, credential: &Credential) {
+        match (scheme, credential) {
+            (SecurityScheme::ApiKey { name, location, .. }, Credential::ApiKey(key)) => match location.as_str() {
+                "query" => self.query.push((name.clone(), key.clone())),
+                "cookie" => self.cookies.push((name.clone(), key.clone())),
+                _ => self.headers.push((name.clone(), key.clone())),
+            },
+            (SecurityScheme::Http { .. }, Credential::Basic { username, password }) => {
+                let encoded = base64::encode(&format!("{}:{}", username, password));
+                self.headers.push(("Authorization".to_string(), format!("Basic {}", encoded)));
+            }
+            (SecurityScheme::ApiKey { .. }, Credential::Bearer(_)) => {
+                warn!("A bearer token cannot be used for an apiKey scheme")
+            }
+            (_, Credential::Bearer(token)) => {
+                self.headers.push(("Authorization".to_string(), format!("Bearer {}", token)));
+            }
+            (scheme, _) => warn!("The credential does not apply to the scheme {:?}", scheme),
+        }
+    }
+
+    /// Send `body` as JSON.
+    pub fn json<T>(&mut self, body: &T) -> &mut OperationRequest
+    where
+        T: Serialize,
+    {
+        match serde_json::to_vec(body) {
+            Ok(body) => self.bytes("application/json", body),
+            Err(e) => self.fail(Error::Serialize(e)),
+        }
+    }
+
+    /// Send `body` as is, with the `Content-Type` header set to `content_type`.
+    pub fn bytes<B>(&mut self, content_type: &str, body: B) -> &mut OperationRequest
+    where
+        B: Into<Vec<u8>>,
+    {
+        self.body = Some((content_type.to_string(), body.into()));
+        self
+    }
+
+    fn fail(&mut self, error: Error) -> &mut OperationRequest {
+        if self.error.is_none() {
+            self.error = Some(error);
+        }
+        self
+    }
+
+    /// The URI of the request against `base_url`, with the query string.
+    pub fn uri(&self, base_url: &str) -> String {
+        let mut uri = format!("{}{}", base_url.trim_end_matches('/'), self.path);
+        if !self.query.is_empty() {
+            let query = form_urlencoded::Serializer::new(String::new())
+                .extend_pairs(self.query.iter())
+                .finish();
+            uri.push('?');
+            uri.push_str(&query);
+        }
+        uri
+    }
+
+    fn build(&self, base_url: &str) -> Result<ClientRequest> {
+        let mut builder = ClientRequest::build();
+        builder.method(self.method.clone()).uri(self.uri(base_url));
+        for (name, value) in self.headers.iter() {
+            builder.header(name.as_str(), value.as_str());
+        }
+        if !self.cookies.is_empty() {
+            let cookies: Vec<String> = self.cookies.iter().map(|(name, value)| format!("{}={}", name, value)).collect();
+            builder.header("Cookie", cookies.join("; ").as_str());
+        }
+
+        let request = match self.body {
+            Some((ref content_type, ref body)) => {
+                builder.header("Content-Type", content_type.as_str());
+                builder.body(body.clone())
+            }
+            None => builder.finish(),
+        };
+        request.map_err(|e| Error::Request(e.to_string()))
+    }
+
+    /// Send the request to `base_url` and decode the response with `decode`, which is given
+    /// the status and the body.
+    pub fn send<T, F>(self, base_url: &str, decode: F) -> ResponseFuture<T>
+    where
+        T: 'static,
+        F: FnOnce(u16, &[u8]) -> Result<T> + 'static,
+    {
+        if let Some(error) = self.error {
+            return Box::new(future::err(error));
+        }
+        let request = match self.build(base_url) {
+            Ok(request) => request,
+            Err(e) => return Box::new(future::err(e)),
+        };
+
+        Box::new(
+            request
+                .send()
+                .map_err(|e| Error::Request(e.to_string()))
+                .and_then(move |response| {
+                    let status = response.status().as_u16();
+                    response
+                        .body()
+                        .limit(BODY_LIMIT)
+                        .map_err(|e| Error::Request(e.to_string()))
+                        .and_then(move |body| decode(status, &body))
+                }),
+        )
+    }
+}
+
+/// Decode a JSON response body.
+pub fn json_body<T>(body: &[u8]) -> Result<T>
+where
+    T: DeserializeOwned,
+{
+    serde_json::from_slice(body).map_err(Error::Serialize)
+}
+
+/// Decode a text response body, replacing invalid UTF-8.
+pub fn text_body(body: &[u8]) -> String {
+    String::from_utf8_lossy(body).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_operation_request() {
+        let mut schemes = Map::new();
+        schemes.insert(
+            "api_key".to_string(),
+            SecurityScheme::ApiKey {
+                name: "key".to_string(),
+                location: "query".to_string(),
+                extensions: Map::new(),
+            },
+        );
+        let mut credentials = Map::new();
+        credentials.insert("api_key".to_string(), Credential::ApiKey("secret".to_string()));
+
+        let mut request = OperationRequest::new(Method::GET, "/owners/{owner}/pets");
+        request
+            .parameter("path", "owner", None, None, &"Alex Smith")
+            .parameter("query", "tags", None, None, &vec!["cat", "dog"])
+            .parameter("query", "limit", None, None, &None::<u32>)
+            .parameter("header", "X-Request-Id", None, None, &7)
+            .authorize(&[&["oauth"], &["api_key"]], &schemes, &credentials);
+
+        assert_eq!(
+            request.uri("http://localhost:8000/v1/"),
+            "http://localhost:8000/v1/owners/Alex%20Smith/pets?tags=cat&tags=dog&key=secret"
+        );
+        assert_eq!(request.headers, vec![("X-Request-Id".to_string(), "7".to_string())]);
+        assert!(request.error.is_none());
+    }
+
+    #[test]
+    fn test_authorize_optional() {
+        let mut schemes = Map::new();
+        schemes.insert(
+            "api_key".to_string(),
+            SecurityScheme::ApiKey {
+                name: "X-Key".to_string(),
+                location: "header".to_string(),
+                extensions: Map::new(),
+            },
+        );
+        let mut credentials = Map::new();
+
+        let mut request = OperationRequest::new(Method::GET, "/pets");
+        request.authorize(&[&[], &["api_key"]], &schemes, &credentials);
+        assert!(request.headers.is_empty());
+
+        credentials.insert("api_key".to_string(), Credential::ApiKey("secret".to_string()));
+        let mut request = OperationRequest::new(Method::GET, "/pets");
+        request.authorize(&[&[], &["api_key"]], &schemes, &credentials);
+        assert_eq!(request.headers, vec![("X-Key".to_string(), "secret".to_string())]);
+    }
+}
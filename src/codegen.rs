@@ -0,0 +1,398 @@
+//! Generate Rust code from a `Spec`, e.g. from a `build.rs`.
+//!
+//! ```ignore
+//! let spec = actix_web_openapi::from_path("partner.yaml")?;
+//! let code = TypeGenerator::new(&spec).generate();
+//! std::fs::write(Path::new(&env::var("OUT_DIR")?).join("partner.rs"), code)?;
+//! ```
+//!
+//! The generators emit source text; `include!` it into a module of the consuming crate.
+//! Each generator emits the types it needs, so give each its own module.
+
+pub mod client;
+pub mod service;
+pub mod types;
+
+pub use self::client::ClientGenerator;
+pub use self::service::ServiceGenerator;
+pub use self::types::TypeGenerator;
+
+use actix_web::http::StatusCode;
+
+use crate::components::ObjectOrReference;
+use crate::path::Parameter;
+use crate::spec::{MediaType, Schema, Spec};
+use crate::Map;
+
+/// The first line of generated sources.
+const HEADER: &str = "// Generated from an OpenApi document, do not edit.\n";
+
+const KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern", "false", "fn",
+    "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref", "return", "self", "Self",
+    "static", "struct", "super", "trait", "true", "type", "unsafe", "use", "where", "while", "abstract", "become",
+    "box", "do", "final", "macro", "override", "priv", "try", "typeof", "unsized", "virtual", "yield",
+];
+
+/// Split a name in any case convention into lower case words.
+fn words(name: &str) -> Vec<String> {
+    let mut words: Vec<String> = Vec::new();
+    let mut current = String::new();
+    let mut previous_lower = false;
+    for ch in name.chars() {
+        if !ch.is_alphanumeric() {
+            if !current.is_empty() {
+                words.push(current.clone());
+                current.clear();
+            }
+            previous_lower = false;
+            continue;
+        }
+        if ch.is_uppercase() && previous_lower && !current.is_empty() {
+            words.push(current.clone());
+            current.clear();
+        }
+        previous_lower = ch.is_lowercase() || ch.is_numeric();
+        current.extend(ch.to_lowercase());
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+    words
+}
+
+/// A `PascalCase` type or variant name, e.g. `PetOwner` for `pet_owner` or `pet-owner`.
+pub fn type_name(name: &str) -> String {
+    let mut pascal: String = words(name)
+        .iter()
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect();
+    if pascal.is_empty() {
+        pascal.push_str("Empty");
+    }
+    if pascal.starts_with(|ch: char| ch.is_numeric()) {
+        pascal.insert(0, 'N');
+    }
+    if pascal == "Self" {
+        pascal.push('_');
+    }
+    pascal
+}
+
+/// A `snake_case` field, function or argument name, escaping keywords.
+pub fn field_name(name: &str) -> String {
+    let mut snake = words(name).join("_");
+    if snake.is_empty() {
+        snake.push_str("value");
+    }
+    if snake.starts_with(|ch: char| ch.is_numeric()) {
+        snake.insert(0, '_');
+    }
+    match snake.as_str() {
+        "self" | "super" | "crate" | "Self" => format!("{}_", snake),
+        keyword if KEYWORDS.contains(&keyword) => format!("r#{}", snake),
+        _ => snake,
+    }
+}
+
+/// `///` doc comment lines for `text`, indented by `indent`.
+fn doc_comment(text: Option<&String>, indent: &str) -> String {
+    let mut out = String::new();
+    for line in text.map(|text| text.trim()).unwrap_or("").lines() {
+        out.push_str(indent);
+        out.push_str("///");
+        if !line.trim().is_empty() {
+            out.push(' ');
+            out.push_str(line.trim_end());
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// A Rust string literal for `value`.
+fn string_literal(value: &str) -> String {
+    format!("{:?}", value)
+}
+
+/// How a request or response body is encoded.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Content {
+    Json,
+    Text,
+    Binary,
+}
+
+/// A body of an operation: its media type, encoding and Rust type.
+struct Body {
+    media_type: String,
+    content: Content,
+    rust_type: String,
+}
+
+/// A parameter of an operation as an argument of a generated method.
+struct Argument {
+    name: String,
+    parameter: Parameter,
+    /// The Rust type, an `Option` unless the parameter is required.
+    rust_type: String,
+}
+
+/// A declared response of an operation as a variant of its response enum.
+struct Outcome {
+    status: String,
+    variant: String,
+    description: Option<String>,
+    body: Option<Body>,
+}
+
+impl Outcome {
+    /// Whether the variant carries the status, as for `default` and `2XX` responses.
+    fn has_status(&self) -> bool {
+        self.status.parse::<u16>().is_err()
+    }
+
+    /// The pattern matching the status, `None` for `default`.
+    fn pattern(&self) -> Option<String> {
+        if let Ok(status) = self.status.parse::<u16>() {
+            return Some(status.to_string());
+        }
+        let class = self.status.chars().next().and_then(|class| class.to_digit(10))?;
+        Some(format!("{}..={}", class * 100, class * 100 + 99))
+    }
+}
+
+/// An operation of a spec, with the Rust types of its inputs and responses.
+struct Signature {
+    /// The method name, from the `operationId` or else the HTTP method and path.
+    name: String,
+    method: String,
+    path: String,
+    summary: Option<String>,
+    description: Option<String>,
+    arguments: Vec<Argument>,
+    /// The request body and whether it is required.
+    body: Option<(Body, bool)>,
+    response_type: String,
+    outcomes: Vec<Outcome>,
+    /// The security requirements, as lists of scheme names.
+    security: Option<Vec<Vec<String>>>,
+}
+
+impl Signature {
+    /// `///` doc comment lines from the summary and description.
+    fn doc(&self, indent: &str) -> String {
+        let text = match (&self.summary, &self.description) {
+            (Some(summary), Some(description)) => Some(format!("{}\n\n{}", summary, description)),
+            (summary, description) => summary.clone().or_else(|| description.clone()),
+        };
+        doc_comment(text.as_ref(), indent)
+    }
+
+    /// The response enum, with a variant per declared response.
+    fn response_enum(&self) -> String {
+        let mut variants = String::new();
+        for outcome in self.outcomes.iter() {
+            variants.push_str(&doc_comment(outcome.description.as_ref(), "    "));
+            let mut fields = Vec::new();
+            if outcome.has_status() {
+                fields.push("u16".to_string());
+            }
+            if let Some(ref body) = outcome.body {
+                fields.push(body.rust_type.clone());
+            }
+            if fields.is_empty() {
+                variants.push_str(&format!("    {},\n", outcome.variant));
+            } else {
+                variants.push_str(&format!("    {}({}),\n", outcome.variant, fields.join(", ")));
+            }
+        }
+        format!(
+            "/// The responses of `{}`.\n#[derive(Clone, Debug, PartialEq)]\npub enum {} {{\n{}}}\n",
+            self.name, self.response_type, variants
+        )
+    }
+}
+
+/// The signatures of every operation of `spec`, in path order. Types of schemas are
+/// generated with `types`.
+fn signatures(spec: &Spec, types: &mut TypeGenerator) -> Vec<Signature> {
+    let components = spec.components.as_ref();
+    let mut signatures = Vec::new();
+    let mut names: Vec<String> = Vec::new();
+
+    for (path, item) in spec.paths.iter() {
+        for (method, operation) in item.operations() {
+            let method = method.as_str().to_string();
+            let mut name = match operation.operation_id {
+                Some(ref operation_id) => field_name(operation_id),
+                None => field_name(&format!("{} {}", method, path)),
+            };
+            while names.contains(&name) {
+                name.push('_');
+            }
+            names.push(name.clone());
+            let hint = type_name(&name);
+
+            // operation parameters override path item parameters of the same name and location
+            let parameter_components = components.and_then(|components| components.parameters.as_ref());
+            let mut parameters: Vec<Parameter> = Vec::new();
+            let declared = item.parameters.iter().flatten().chain(operation.parameters.iter().flatten());
+            for parameter in declared.filter_map(|parameter| parameter.resolve(parameter_components)) {
+                parameters.retain(|p| p.name != parameter.name || p.location != parameter.location);
+                parameters.push(parameter.clone());
+            }
+            // path parameters first, in the order of the template
+            let position = |parameter: &Parameter| match parameter.location.as_str() {
+                "path" => path.find(&format!("{{{}}}", parameter.name)).unwrap_or(0),
+                _ => path.len() + 1,
+            };
+            parameters.sort_by_key(position);
+
+            let mut arguments: Vec<Argument> = Vec::new();
+            for parameter in parameters {
+                let schema = parameter.schema.clone().unwrap_or_default();
+                let mut rust_type = types.rust_type(&schema, &format!("{}{}", hint, type_name(&parameter.name)));
+                let required = parameter.location == "path" || parameter.required == Some(true);
+                if !required && !rust_type.starts_with("Option<") {
+                    rust_type = format!("Option<{}>", rust_type);
+                }
+                let mut argument_name = field_name(&parameter.name);
+                while argument_name == "body" || arguments.iter().any(|argument| argument.name == argument_name) {
+                    argument_name.push('_');
+                }
+                arguments.push(Argument {
+                    name: argument_name,
+                    parameter,
+                    rust_type,
+                });
+            }
+
+            let body_components = components.and_then(|components| components.request_bodies.as_ref());
+            let body = operation
+                .request_body
+                .as_ref()
+                .and_then(|body| body.resolve(body_components))
+                .and_then(|body| {
+                    let required = body.required == Some(true);
+                    body_of(&body.content, types, &format!("{}Request", hint)).map(|body| (body, required))
+                });
+
+            let mut outcomes: Vec<Outcome> = Vec::new();
+            for (status, response) in operation.responses.iter() {
+                let variant = match status.parse::<u16>() {
+                    Ok(code) => StatusCode::from_u16(code)
+                        .ok()
+                        .and_then(|code| code.canonical_reason())
+                        .map(type_name)
+                        .unwrap_or_else(|| format!("Status{}", code)),
+                    Err(_) if status == "default" => "Default".to_string(),
+                    Err(_) => format!("Status{}", status.to_lowercase()),
+                };
+                let body = response
+                    .content
+                    .as_ref()
+                    .and_then(|content| body_of(content, types, &format!("{}{}", hint, variant)));
+                outcomes.push(Outcome {
+                    status: status.clone(),
+                    variant,
+                    description: response.description.clone(),
+                    body,
+                });
+            }
+            // exact statuses first, then ranges, then the default
+            outcomes.sort_by_key(|outcome| match outcome.pattern() {
+                Some(ref pattern) if pattern.contains("..") => 1,
+                Some(_) => 0,
+                None => 2,
+            });
+
+            let security = operation
+                .security
+                .as_ref()
+                .or(spec.security.as_ref())
+                .map(|requirements| {
+                    requirements
+                        .iter()
+                        .map(|requirement| requirement.keys().cloned().collect())
+                        .collect()
+                });
+
+            signatures.push(Signature {
+                response_type: types.unique_name(&format!("{}Response", hint)),
+                name,
+                method,
+                path: path.clone(),
+                summary: operation.summary.clone(),
+                description: operation.description.clone(),
+                arguments,
+                body,
+                outcomes,
+                security,
+            });
+        }
+    }
+    signatures
+}
+
+/// The body of `content`, preferring JSON when several media types are described.
+fn body_of(content: &Map<String, MediaType>, types: &mut TypeGenerator, hint: &str) -> Option<Body> {
+    let (media_type, media) = content
+        .iter()
+        .find(|(media_type, _)| content_of(media_type) == Content::Json)
+        .or_else(|| content.iter().next())?;
+    let content = content_of(media_type);
+    let rust_type = match content {
+        Content::Json => match media.schema {
+            Some(ObjectOrReference::Object(ref schema)) => types.rust_type(schema, hint),
+            Some(ObjectOrReference::Ref { ref ref_path }) => types.rust_type(
+                &Schema {
+                    ref_path: Some(ref_path.clone()),
+                    ..Default::default()
+                },
+                hint,
+            ),
+            None => "serde_json::Value".to_string(),
+        },
+        Content::Text => "String".to_string(),
+        Content::Binary => "Vec<u8>".to_string(),
+    };
+    Some(Body {
+        media_type: media_type.clone(),
+        content,
+        rust_type,
+    })
+}
+
+fn content_of(media_type: &str) -> Content {
+    let essence = media_type.split(';').next().unwrap_or("").trim();
+    if essence == "application/json" || essence.ends_with("+json") {
+        Content::Json
+    } else if essence.starts_with("text/") {
+        Content::Text
+    } else {
+        Content::Binary
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_names() {
+        assert_eq!(type_name("pet_owner"), "PetOwner");
+        assert_eq!(type_name("HTTPStatus"), "Httpstatus");
+        assert_eq!(type_name("2xx"), "N2xx");
+        assert_eq!(field_name("petName"), "pet_name");
+        assert_eq!(field_name("type"), "r#type");
+        assert_eq!(field_name("self"), "self_");
+        assert_eq!(field_name("X-Request-Id"), "x_request_id");
+    }
+}
@@ -0,0 +1,303 @@
+//! A typed client for the operations of a `Spec`.
+//!
+//! The generated `ApiClient` trait has a method per operation, named after its `operationId`.
+//! Path, query, header and cookie parameters are its arguments, optional ones as `Option`s,
+//! followed by the request body. It returns a future of the operation's response enum, with a
+//! variant per declared response; `default` and `2XX` style variants also carry the status.
+//!
+//! `Client` implements the trait with `client::OperationRequest`: parameters are serialized
+//! as their style describes, credentials are applied for the operation's security
+//! requirements, and `Client::for_server` expands a server of the spec with
+//! `Server::url_with`. Types are generated as `TypeGenerator` does.
+
+use std::fs;
+use std::path::Path;
+
+use super::{signatures, string_literal, Content, Signature, TypeGenerator, HEADER};
+use crate::spec::{SecurityScheme, Spec};
+use crate::{Map, Result};
+
+/// The crate is imported under an alias, so that component schemas cannot clash with it.
+const IMPORTS: &str = "\nuse actix_web_openapi as openapi;\n";
+
+/// Generates a typed client for the operations of a `Spec`.
+pub struct ClientGenerator<'a> {
+    spec: &'a Spec,
+    types: TypeGenerator<'a>,
+}
+
+impl<'a> ClientGenerator<'a> {
+    pub fn new(spec: &'a Spec) -> ClientGenerator<'a> {
+        ClientGenerator {
+            spec,
+            types: TypeGenerator::new(spec),
+        }
+    }
+
+    /// Map `date-time` and `date` strings to `chrono` types, as `TypeGenerator::chrono` does.
+    pub fn chrono(mut self, chrono: bool) -> ClientGenerator<'a> {
+        self.types = self.types.chrono(chrono);
+        self
+    }
+
+    /// Map `uuid` strings to `uuid::Uuid`, as `TypeGenerator::uuid` does.
+    pub fn uuid(mut self, uuid: bool) -> ClientGenerator<'a> {
+        self.types = self.types.uuid(uuid);
+        self
+    }
+
+    /// The source of the types, the response enums, the `ApiClient` trait and `Client`.
+    pub fn generate(self) -> String {
+        let spec = self.spec;
+        let mut types = self.types;
+        types.components();
+        let signatures = signatures(spec, &mut types);
+
+        let mut out = String::from(HEADER);
+        out.push_str(IMPORTS);
+        for item in types.into_items() {
+            out.push('\n');
+            out.push_str(&item);
+        }
+        for signature in signatures.iter() {
+            out.push('\n');
+            out.push_str(&signature.response_enum());
+        }
+
+        let servers = serde_json::to_string(&spec.servers.clone().unwrap_or_default()).expect("servers serialize");
+        let schemes: Map<String, SecurityScheme> = spec
+            .components
+            .as_ref()
+            .and_then(|components| components.security_schemes.as_ref())
+            .into_iter()
+            .flatten()
+            .filter_map(|(name, scheme)| {
+                let schemes = spec.components.as_ref().and_then(|c| c.security_schemes.as_ref());
+                scheme.resolve(schemes).map(|scheme| (name.clone(), scheme.clone()))
+            })
+            .collect();
+        let schemes = serde_json::to_string(&schemes).expect("security schemes serialize");
+        let title = &spec.info.title;
+
+        out.push_str(&format!(
+            "
+/// The servers of {title}, as JSON.
+const SERVERS: &str = {servers};
+
+/// The security schemes of {title}, as JSON.
+const SECURITY_SCHEMES: &str = {schemes};
+
+/// The operations of {title}.
+pub trait ApiClient {{
+{declarations}}}
+
+/// Sends requests to a server of {title} with actix-web's client.
+#[derive(Clone, Debug)]
+pub struct Client {{
+    base_url: String,
+    {unread}security_schemes: openapi::Map<String, openapi::spec::SecurityScheme>,
+    credentials: openapi::Map<String, openapi::client::Credential>,
+}}
+
+impl Client {{
+    /// A client for the API at `base_url`.
+    pub fn new(base_url: &str) -> Client {{
+        Client {{
+            base_url: base_url.to_string(),
+            security_schemes: serde_json::from_str(SECURITY_SCHEMES).expect(\"generated security schemes\"),
+            credentials: openapi::Map::new(),
+        }}
+    }}
+
+    /// A client for the server at `index` in the spec, its variables set to `variables` or
+    /// to their defaults.
+    pub fn for_server(index: usize, variables: &openapi::Map<String, String>) -> Option<Client> {{
+        let servers: Vec<openapi::server::Server> = serde_json::from_str(SERVERS).expect(\"generated servers\");
+        servers.get(index).map(|server| Client::new(&server.url_with(variables)))
+    }}
+
+    /// Use `credential` for the security scheme named `scheme`.
+    pub fn credential(mut self, scheme: &str, credential: openapi::client::Credential) -> Client {{
+        self.credentials.insert(scheme.to_string(), credential);
+        self
+    }}
+}}
+
+impl ApiClient for Client {{
+{implementations}}}
+",
+            title = title,
+            unread = if signatures.iter().any(|signature| signature.security.is_some()) {
+                ""
+            } else {
+                "#[allow(dead_code)]\n    "
+            },
+            servers = string_literal(&servers),
+            schemes = string_literal(&schemes),
+            declarations = signatures
+                .iter()
+                .map(|signature| format!("{}    {};\n", signature.doc("    "), declaration(signature)))
+                .collect::<Vec<_>>()
+                .join("\n"),
+            implementations = signatures
+                .iter()
+                .map(|signature| format!("    {} {{\n{}    }}\n", declaration(signature), implementation(signature)))
+                .collect::<Vec<_>>()
+                .join("\n"),
+        ));
+        out
+    }
+}
+
+/// The method signature of an operation, e.g.
+/// `fn show_pet_by_id(&self, pet_id: String) -> openapi::client::ResponseFuture<ShowPetByIdResponse>`.
+fn declaration(signature: &Signature) -> String {
+    let mut arguments = vec!["&self".to_string()];
+    for argument in signature.arguments.iter() {
+        arguments.push(format!("{}: {}", argument.name, argument.rust_type));
+    }
+    if let Some((ref body, required)) = signature.body {
+        let borrowed = match body.content {
+            Content::Json => format!("&{}", body.rust_type),
+            Content::Text => "&str".to_string(),
+            Content::Binary => "&[u8]".to_string(),
+        };
+        if required {
+            arguments.push(format!("body: {}", borrowed));
+        } else {
+            arguments.push(format!("body: Option<{}>", borrowed));
+        }
+    }
+    format!(
+        "fn {}({}) -> openapi::client::ResponseFuture<{}>",
+        signature.name,
+        arguments.join(", "),
+        signature.response_type
+    )
+}
+
+/// The body of the `Client` method of an operation.
+fn implementation(signature: &Signature) -> String {
+    let mutates = !signature.arguments.is_empty() || signature.body.is_some() || signature.security.is_some();
+    let mut out = format!(
+        "        let {}request = openapi::client::OperationRequest::new(openapi::client::Method::{}, {});\n",
+        if mutates { "mut " } else { "" },
+        signature.method,
+        string_literal(&signature.path)
+    );
+
+    for argument in signature.arguments.iter() {
+        let parameter = &argument.parameter;
+        let style = match parameter.style {
+            Some(ref style) => format!("Some(openapi::path::ParameterStyle::{:?})", style),
+            None => "None".to_string(),
+        };
+        out.push_str(&format!(
+            "        request.parameter({}, {}, {}, {:?}, &{});\n",
+            string_literal(&parameter.location),
+            string_literal(&parameter.name),
+            style,
+            parameter.explode,
+            argument.name
+        ));
+    }
+
+    if let Some((ref body, required)) = signature.body {
+        let send = match body.content {
+            Content::Json => "request.json(body);".to_string(),
+            _ => format!("request.bytes({}, body);", string_literal(&body.media_type)),
+        };
+        if required {
+            out.push_str(&format!("        {}\n", send));
+        } else {
+            out.push_str(&format!("        if let Some(body) = body {{\n            {}\n        }}\n", send));
+        }
+    }
+
+    if let Some(ref requirements) = signature.security {
+        let requirements: Vec<String> = requirements
+            .iter()
+            .map(|requirement| {
+                let names: Vec<String> = requirement.iter().map(|name| string_literal(name)).collect();
+                format!("&[{}]", names.join(", "))
+            })
+            .collect();
+        out.push_str(&format!(
+            "        request.authorize(&[{}], &self.security_schemes, &self.credentials);\n",
+            requirements.join(", ")
+        ));
+    }
+
+    let reads_body = signature.outcomes.iter().any(|outcome| outcome.body.is_some());
+    let mut arms = String::new();
+    for outcome in signature.outcomes.iter() {
+        let pattern = match outcome.pattern() {
+            Some(ref pattern) if outcome.has_status() => format!("status @ {}", pattern),
+            Some(pattern) => pattern,
+            None => "status".to_string(),
+        };
+        let mut fields = Vec::new();
+        if outcome.has_status() {
+            fields.push("status".to_string());
+        }
+        if let Some(ref body) = outcome.body {
+            fields.push(
+                match body.content {
+                    Content::Json => "openapi::client::json_body(response)?",
+                    Content::Text => "openapi::client::text_body(response)",
+                    Content::Binary => "response.to_vec()",
+                }
+                .to_string(),
+            );
+        }
+        let value = if fields.is_empty() {
+            format!("{}::{}", signature.response_type, outcome.variant)
+        } else {
+            format!("{}::{}({})", signature.response_type, outcome.variant, fields.join(", "))
+        };
+        arms.push_str(&format!("            {} => Ok({}),\n", pattern, value));
+    }
+    if signature.outcomes.iter().all(|outcome| outcome.pattern().is_some()) {
+        arms.push_str("            status => Err(openapi::Error::UnexpectedStatus(status)),\n");
+    }
+
+    out.push_str(&format!(
+        "        request.send(&self.base_url, |status, {}| match status {{\n{}        }})\n",
+        if reads_body { "response" } else { "_response" },
+        arms
+    ));
+    out
+}
+
+/// Generate a client for the spec at `spec_path` into `out_path`, for use from a `build.rs`.
+/// Cargo is told to rerun the build script when the spec changes.
+pub fn generate_client<P, Q>(spec_path: P, out_path: Q) -> Result<()>
+where
+    P: AsRef<Path>,
+    Q: AsRef<Path>,
+{
+    println!("cargo:rerun-if-changed={}", spec_path.as_ref().display());
+    let spec = crate::from_path(spec_path)?;
+    fs::write(out_path, ClientGenerator::new(&spec).generate())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate() {
+        let spec = crate::from_path("data/v3.0/petstore.yaml").unwrap();
+        let code = ClientGenerator::new(&spec).generate();
+
+        assert!(code.contains("pub enum ListPetsResponse {\n    /// An paged array of pets\n    Ok(Pets),\n"));
+        assert!(code.contains("    Default(u16, Error),\n"));
+        assert!(code.contains("    fn list_pets(&self, limit: Option<i32>) -> openapi::client::ResponseFuture<ListPetsResponse>;\n"));
+        assert!(code.contains("    fn show_pet_by_id(&self, pet_id: String) -> openapi::client::ResponseFuture<ShowPetByIdResponse>"));
+        assert!(code.contains("        request.parameter(\"query\", \"limit\", None, None, &limit);\n"));
+        assert!(code.contains("            201 => Ok(CreatePetsResponse::Created),\n"));
+        assert!(code.contains("            status => Ok(ListPetsResponse::Default(status, openapi::client::json_body(response)?)),\n"));
+        assert!(code.contains("impl ApiClient for Client {"));
+    }
+}
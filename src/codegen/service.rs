@@ -0,0 +1,322 @@
+//! A handler trait for the operations of a `Spec`, for spec-first servers.
+//!
+//! The generated `ApiService` trait has a method per operation, named after its
+//! `operationId`, taking the parameters and the request body as `ClientGenerator`'s
+//! `ApiClient` does, owned, and returning a future of the operation's response enum. Leaving
+//! an operation out of an implementation is a compile error.
+//!
+//! `register` routes every operation on an actix-web `App` to an implementation: parameters
+//! are read as their style describes, the body is decoded, and the response enum is encoded
+//! with its status and media type. Paths are routed as the spec lists them, so use
+//! `App::prefix` for the base path of a server. Security requirements are not checked;
+//! that is left to middleware.
+
+use std::fs;
+use std::path::Path;
+
+use super::{signatures, string_literal, Content, Signature, TypeGenerator, HEADER};
+use crate::spec::{Schema, Spec};
+use crate::Result;
+
+/// The crate is imported under an alias, so that component schemas cannot clash with it.
+const IMPORTS: &str = "\nuse actix_web_openapi as openapi;\n";
+
+/// Generates a handler trait for the operations of a `Spec` and the routes for it.
+pub struct ServiceGenerator<'a> {
+    spec: &'a Spec,
+    types: TypeGenerator<'a>,
+}
+
+impl<'a> ServiceGenerator<'a> {
+    pub fn new(spec: &'a Spec) -> ServiceGenerator<'a> {
+        ServiceGenerator {
+            spec,
+            types: TypeGenerator::new(spec),
+        }
+    }
+
+    /// Map `date-time` and `date` strings to `chrono` types, as `TypeGenerator::chrono` does.
+    pub fn chrono(mut self, chrono: bool) -> ServiceGenerator<'a> {
+        self.types = self.types.chrono(chrono);
+        self
+    }
+
+    /// Map `uuid` strings to `uuid::Uuid`, as `TypeGenerator::uuid` does.
+    pub fn uuid(mut self, uuid: bool) -> ServiceGenerator<'a> {
+        self.types = self.types.uuid(uuid);
+        self
+    }
+
+    /// The source of the types, the response enums, the `ApiService` trait and `register`.
+    pub fn generate(self) -> String {
+        let spec = self.spec;
+        let mut types = self.types;
+        types.components();
+        let signatures = signatures(spec, &mut types);
+
+        let mut out = String::from(HEADER);
+        out.push_str(IMPORTS);
+        for item in types.into_items() {
+            out.push('\n');
+            out.push_str(&item);
+        }
+        for signature in signatures.iter() {
+            out.push('\n');
+            out.push_str(&signature.response_enum());
+            out.push('\n');
+            out.push_str(&encoding(signature));
+        }
+
+        let title = &spec.info.title;
+        let declarations: Vec<String> = signatures
+            .iter()
+            .map(|signature| format!("{}    {};\n", signature.doc("    "), declaration(signature)))
+            .collect();
+        out.push_str(&format!(
+            "\n/// The operations of {}.\npub trait ApiService {{\n{}}}\n",
+            title,
+            declarations.join("\n")
+        ));
+        for signature in signatures.iter() {
+            out.push('\n');
+            out.push_str(&handler(spec, signature));
+        }
+
+        let routes: Vec<String> = signatures
+            .iter()
+            .map(|signature| {
+                format!(
+                    "openapi::service::route(app, {}, openapi::service::Method::{}, &service, handle_{}::<S, T>)",
+                    string_literal(&signature.path),
+                    signature.method,
+                    signature.name.trim_start_matches("r#")
+                )
+            })
+            .collect();
+        let routes = match routes.split_last() {
+            Some((last, routes)) => {
+                let routes: String = routes.iter().map(|route| format!("    let app = {};\n", route)).collect();
+                format!("    let service = std::rc::Rc::new(service);\n{}    {}\n", routes, last)
+            }
+            None => "    app\n".to_string(),
+        };
+        out.push_str(&format!(
+            "
+/// Route the operations of {title} on `app` to `service`.
+pub fn register<S, T>(app: actix_web::App<S>, {service}: T) -> actix_web::App<S>
+where
+    S: 'static,
+    T: ApiService + 'static,
+{{
+{routes}}}
+",
+            title = title,
+            service = if signatures.is_empty() { "_service" } else { "service" },
+            routes = routes,
+        ));
+        out
+    }
+}
+
+/// The `ApiService` method of an operation, e.g.
+/// `fn show_pet_by_id(&self, pet_id: String) -> openapi::service::ServiceFuture<ShowPetByIdResponse>`.
+fn declaration(signature: &Signature) -> String {
+    let mut arguments = vec!["&self".to_string()];
+    for argument in signature.arguments.iter() {
+        arguments.push(format!("{}: {}", argument.name, argument.rust_type));
+    }
+    if let Some((ref body, required)) = signature.body {
+        if required {
+            arguments.push(format!("body: {}", body.rust_type));
+        } else {
+            arguments.push(format!("body: Option<{}>", body.rust_type));
+        }
+    }
+    format!(
+        "fn {}({}) -> openapi::service::ServiceFuture<{}>",
+        signature.name,
+        arguments.join(", "),
+        signature.response_type
+    )
+}
+
+/// The `OperationResponse` implementation of the response enum of an operation.
+fn encoding(signature: &Signature) -> String {
+    let mut arms = String::new();
+    for outcome in signature.outcomes.iter() {
+        let status = if outcome.has_status() {
+            "status".to_string()
+        } else {
+            outcome.status.clone()
+        };
+        let mut fields = Vec::new();
+        if outcome.has_status() {
+            fields.push("status");
+        }
+        let response = match outcome.body {
+            Some(ref body) => {
+                fields.push("body");
+                match body.content {
+                    Content::Json => format!("openapi::service::json_response({}, &body)", status),
+                    _ => format!(
+                        "openapi::service::bytes_response({}, {}, body)",
+                        status,
+                        string_literal(&body.media_type)
+                    ),
+                }
+            }
+            None => format!("openapi::service::empty_response({})", status),
+        };
+        let pattern = if fields.is_empty() {
+            format!("{}::{}", signature.response_type, outcome.variant)
+        } else {
+            format!("{}::{}({})", signature.response_type, outcome.variant, fields.join(", "))
+        };
+        arms.push_str(&format!("            {} => {},\n", pattern, response));
+    }
+    format!(
+        "impl openapi::service::OperationResponse for {} {{
+    fn into_response(self) -> actix_web::HttpResponse {{
+        match self {{
+{}        }}
+    }}
+}}
+",
+        signature.response_type, arms
+    )
+}
+
+/// The handler routed to the `ApiService` method of an operation.
+fn handler(spec: &Spec, signature: &Signature) -> String {
+    let mut inputs = String::new();
+    let mut names = Vec::new();
+    for argument in signature.arguments.iter() {
+        let parameter = &argument.parameter;
+        let style = match parameter.style {
+            Some(ref style) => format!("Some(openapi::path::ParameterStyle::{:?})", style),
+            None => "None".to_string(),
+        };
+        let schema_type = match schema_type(spec, parameter.schema.as_ref()) {
+            Some(schema_type) => format!("Some({})", string_literal(&schema_type)),
+            None => "None".to_string(),
+        };
+        let read = format!(
+            "openapi::service::parameter(request, {}, {}, {}, {:?}, {})",
+            string_literal(&parameter.location),
+            string_literal(&parameter.name),
+            style,
+            parameter.explode,
+            schema_type
+        );
+        inputs.push_str(&input(&argument.name, &argument.rust_type, &read));
+        names.push(argument.name.clone());
+    }
+    if let Some((ref body, required)) = signature.body {
+        let decode = match body.content {
+            Content::Json => "openapi::service::json_body",
+            Content::Text => "openapi::service::text_body",
+            Content::Binary => "openapi::service::bytes_body",
+        };
+        if required {
+            inputs.push_str(&input("body", &body.rust_type, &format!("{}(body)", decode)));
+        } else {
+            let rust_type = format!("Option<{}>", body.rust_type);
+            inputs.push_str(&input("body", &rust_type, &format!("openapi::service::optional_body(body, {})", decode)));
+        }
+        names.push("body".to_string());
+    }
+
+    format!(
+        "fn handle_{name}<S, T>(service: &T, {request}: &actix_web::HttpRequest<S>, {body}: &[u8]) -> openapi::service::ServiceFuture<actix_web::HttpResponse>
+where
+    T: ApiService,
+{{
+{inputs}    openapi::service::respond(service.{method}({arguments}))
+}}
+",
+        name = signature.name.trim_start_matches("r#"),
+        request = if signature.arguments.is_empty() { "_request" } else { "request" },
+        body = if signature.body.is_some() { "body" } else { "_body" },
+        inputs = inputs,
+        method = signature.name,
+        arguments = names.join(", "),
+    )
+}
+
+/// A statement reading an input of a handler, answering `400 Bad Request` if it fails.
+fn input(name: &str, rust_type: &str, read: &str) -> String {
+    format!(
+        "    let {name}: {rust_type} = match {read} {{
+        Ok({name}) => {name},
+        Err(e) => return openapi::service::bad_request(e),
+    }};
+",
+        name = name,
+        rust_type = rust_type,
+        read = read
+    )
+}
+
+/// The type of a parameter schema, following a reference to a component schema, so that
+/// arrays and objects can be told from primitives.
+fn schema_type(spec: &Spec, schema: Option<&Schema>) -> Option<String> {
+    let mut schema = schema?;
+    if let Some(ref ref_path) = schema.ref_path {
+        let name = ref_path.trim_start_matches("#/components/schemas/");
+        let component = spec
+            .components
+            .as_ref()
+            .and_then(|components| components.schemas.as_ref())
+            .and_then(|schemas| schemas.get(name));
+        schema = match component {
+            Some(component) => component.resolve(None)?,
+            None => return None,
+        };
+    }
+    match schema.schema_type {
+        Some(ref schema_type) => schema_type
+            .types()
+            .into_iter()
+            .find(|schema_type| *schema_type != "null")
+            .map(str::to_string),
+        None if schema.items.is_some() => Some("array".to_string()),
+        None if schema.properties.is_some() || schema.all_of.is_some() => Some("object".to_string()),
+        None => None,
+    }
+}
+
+/// Generate a service for the spec at `spec_path` into `out_path`, for use from a
+/// `build.rs`. Cargo is told to rerun the build script when the spec changes.
+pub fn generate_service<P, Q>(spec_path: P, out_path: Q) -> Result<()>
+where
+    P: AsRef<Path>,
+    Q: AsRef<Path>,
+{
+    println!("cargo:rerun-if-changed={}", spec_path.as_ref().display());
+    let spec = crate::from_path(spec_path)?;
+    fs::write(out_path, ServiceGenerator::new(&spec).generate())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate() {
+        let spec = crate::from_path("data/v3.0/petstore.yaml").unwrap();
+        let code = ServiceGenerator::new(&spec).generate();
+
+        assert!(code.contains("    fn list_pets(&self, limit: Option<i32>) -> openapi::service::ServiceFuture<ListPetsResponse>;\n"));
+        assert!(code.contains("            ListPetsResponse::Ok(body) => openapi::service::json_response(200, &body),\n"));
+        assert!(code.contains("            ListPetsResponse::Default(status, body) => openapi::service::json_response(status, &body),\n"));
+        assert!(code.contains("            CreatePetsResponse::Created => openapi::service::empty_response(201),\n"));
+        assert!(code.contains(
+            "    let limit: Option<i32> = match openapi::service::parameter(request, \"query\", \"limit\", None, None, Some(\"integer\")) {\n"
+        ));
+        assert!(code.contains("    openapi::service::respond(service.show_pet_by_id(pet_id))\n"));
+        assert!(code.contains(
+            "    openapi::service::route(app, \"/pets/{petId}\", openapi::service::Method::GET, &service, handle_show_pet_by_id::<S, T>)\n"
+        ));
+    }
+}
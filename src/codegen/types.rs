@@ -0,0 +1,508 @@
+//! Rust types for the schemas of a `Spec`.
+//!
+//! Every `components.schemas` entry becomes a type named after it:
+//!
+//! * objects become structs, with `Option` fields for properties that are not `required`
+//!   or are `nullable`, and a flattened `HashMap` for `additionalProperties`
+//! * string `enum`s become enums of unit variants
+//! * `allOf` parts are merged into one struct, referenced parts as `#[serde(flatten)]` fields
+//! * `oneOf` with a `discriminator` becomes an internally tagged enum, without one an
+//!   untagged enum. The discriminator property is left out of the variant structs, as serde
+//!   reads and writes it as the enum tag, and is optional in the structs they flatten.
+//! * objects with only `additionalProperties` and every other schema become type aliases
+//!
+//! Inline objects and enums get types named after their parent and property. With the
+//! `chrono` option `date-time` and `date` strings map to `chrono` types, with the `uuid`
+//! option `uuid` strings map to `uuid::Uuid`; the options default to the `codegen-chrono`
+//! and `codegen-uuid` features. The generated code needs `serde` with the `derive` feature,
+//! and `serde_json`, `chrono` or `uuid` where it uses them.
+
+use std::collections::{BTreeSet, HashSet};
+use std::fs;
+use std::path::Path;
+
+use super::{doc_comment, field_name, string_literal, type_name, HEADER};
+use crate::components::ObjectOrReference;
+use crate::spec::{Schema, Spec};
+use crate::Result;
+
+const DERIVES: &str = "#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]\n";
+
+/// Generates Rust types from the schemas of a `Spec`.
+pub struct TypeGenerator<'a> {
+    spec: &'a Spec,
+    chrono: bool,
+    uuid: bool,
+    /// Type names in use, to keep names of inline types unique.
+    names: HashSet<String>,
+    /// The discriminator property of each component used as a tagged `oneOf` variant.
+    tagged: Vec<(String, String)>,
+    /// The discriminator property of each component flattened into a tagged variant.
+    flattened: Vec<(String, String)>,
+    items: Vec<String>,
+}
+
+impl<'a> TypeGenerator<'a> {
+    pub fn new(spec: &'a Spec) -> TypeGenerator<'a> {
+        let mut generator = TypeGenerator {
+            spec,
+            chrono: cfg!(feature = "codegen-chrono"),
+            uuid: cfg!(feature = "codegen-uuid"),
+            names: HashSet::new(),
+            tagged: Vec::new(),
+            flattened: Vec::new(),
+            items: Vec::new(),
+        };
+        for (name, schema) in generator.component_schemas() {
+            generator.names.insert(type_name(name));
+            generator.collect_tagged(schema);
+        }
+        for (variant, property) in generator.tagged.clone() {
+            generator.collect_flattened(&variant, &property);
+        }
+        generator
+    }
+
+    /// Map `date-time` and `date` strings to `chrono::DateTime<chrono::Utc>` and
+    /// `chrono::NaiveDate`.
+    pub fn chrono(mut self, chrono: bool) -> TypeGenerator<'a> {
+        self.chrono = chrono;
+        self
+    }
+
+    /// Map `uuid` strings to `uuid::Uuid`.
+    pub fn uuid(mut self, uuid: bool) -> TypeGenerator<'a> {
+        self.uuid = uuid;
+        self
+    }
+
+    /// The source of the types of every component schema.
+    pub fn generate(mut self) -> String {
+        self.components();
+        self.finish()
+    }
+
+    /// Generate the types of every component schema.
+    pub fn components(&mut self) {
+        let spec = self.spec;
+        let components = spec.components.as_ref().and_then(|components| components.schemas.as_ref());
+        for (name, schema) in components.into_iter().flatten() {
+            match *schema {
+                ObjectOrReference::Object(ref schema) => self.define(&type_name(name), schema),
+                ObjectOrReference::Ref { ref ref_path } => {
+                    let item = format!("pub type {} = {};\n", type_name(name), reference_type(ref_path));
+                    self.items.push(item);
+                }
+            }
+        }
+    }
+
+    /// The source of the types generated so far.
+    pub fn finish(self) -> String {
+        let mut out = String::from(HEADER);
+        for item in self.into_items() {
+            out.push('\n');
+            out.push_str(&item);
+        }
+        out
+    }
+
+    /// The sources of the types generated so far, one per type.
+    pub fn into_items(self) -> Vec<String> {
+        self.items
+    }
+
+    /// The Rust type of values of `schema`. Inline objects and enums are generated as types
+    /// named after `hint`.
+    pub fn rust_type(&mut self, schema: &Schema, hint: &str) -> String {
+        let rust_type = self.base_type(schema, hint);
+        if schema.is_nullable() {
+            format!("Option<{}>", rust_type)
+        } else {
+            rust_type
+        }
+    }
+
+    fn component_schemas(&self) -> Vec<(&'a String, &'a Schema)> {
+        let spec = self.spec;
+        spec.components
+            .as_ref()
+            .and_then(|components| components.schemas.as_ref())
+            .into_iter()
+            .flatten()
+            .filter_map(|(name, schema)| match *schema {
+                ObjectOrReference::Object(ref schema) => Some((name, schema)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn collect_tagged(&mut self, schema: &Schema) {
+        if let (Some(discriminator), Some(parts)) = (schema.discriminator.as_ref(), schema.one_of.as_ref()) {
+            for part in parts {
+                if let Some(ref_path) = part_ref(part) {
+                    let tagged = (reference_type(ref_path), discriminator.property_name.clone());
+                    self.tagged.push(tagged);
+                }
+            }
+        }
+    }
+
+    /// Record the components `variant` flattens through `allOf`, as they cannot require the
+    /// discriminator `property` serde reads as the tag.
+    fn collect_flattened(&mut self, variant: &str, property: &str) {
+        let schema = match self.component_schemas().into_iter().find(|(name, _)| type_name(name) == variant) {
+            Some((_, schema)) => schema,
+            None => return,
+        };
+        for part in schema.all_of.iter().flatten() {
+            if let Some(ref_path) = part_ref(part) {
+                let flattened = (reference_type(ref_path), property.to_string());
+                if !self.flattened.contains(&flattened) {
+                    self.flattened.push(flattened);
+                    self.collect_flattened(&reference_type(ref_path), property);
+                }
+            }
+        }
+    }
+
+    /// A type name based on `hint` that is not in use yet, reserving it.
+    pub fn unique_name(&mut self, hint: &str) -> String {
+        let base = type_name(hint);
+        let mut name = base.clone();
+        let mut n = 2;
+        while self.names.contains(&name) {
+            name = format!("{}{}", base, n);
+            n += 1;
+        }
+        self.names.insert(name.clone());
+        name
+    }
+
+    fn base_type(&mut self, schema: &Schema, hint: &str) -> String {
+        if let Some(ref ref_path) = schema.ref_path {
+            return reference_type(ref_path);
+        }
+        if needs_definition(schema) {
+            let name = self.unique_name(hint);
+            self.define(&name, schema);
+            return name;
+        }
+
+        let format = schema.format.as_deref();
+        match schema.type_name() {
+            Some("string") => match format {
+                Some("date-time") if self.chrono => "chrono::DateTime<chrono::Utc>",
+                Some("date") if self.chrono => "chrono::NaiveDate",
+                Some("uuid") if self.uuid => "uuid::Uuid",
+                _ => "String",
+            }
+            .to_string(),
+            Some("integer") => match format {
+                Some("int32") => "i32",
+                _ => "i64",
+            }
+            .to_string(),
+            Some("number") => match format {
+                Some("float") => "f32",
+                _ => "f64",
+            }
+            .to_string(),
+            Some("boolean") => "bool".to_string(),
+            Some("array") => match schema.items {
+                Some(ref items) => format!("Vec<{}>", self.rust_type(items, &format!("{}Item", hint))),
+                None => "Vec<serde_json::Value>".to_string(),
+            },
+            _ => match schema.additional_properties {
+                Some(ref values) => format!("std::collections::HashMap<String, {}>", self.map_value_type(values, hint)),
+                None => "serde_json::Value".to_string(),
+            },
+        }
+    }
+
+    fn map_value_type(&mut self, values: &ObjectOrReference<Box<Schema>>, hint: &str) -> String {
+        match *values {
+            ObjectOrReference::Object(ref schema) => self.rust_type(schema, &format!("{}Value", hint)),
+            ObjectOrReference::Ref { ref ref_path } => reference_type(ref_path),
+        }
+    }
+
+    /// Generate the type `name` for `schema`.
+    fn define(&mut self, name: &str, schema: &Schema) {
+        self.names.insert(name.to_string());
+        let mut item = doc_comment(schema.description.as_ref(), "");
+
+        if schema.ref_path.is_some() || !needs_definition(schema) {
+            let rust_type = self.rust_type(schema, name);
+            item.push_str(&format!("pub type {} = {};\n", name, rust_type));
+        } else if schema.one_of.is_some() {
+            item.push_str(&self.one_of_enum(name, schema));
+        } else if let Some(ref values) = schema.enum_values {
+            item.push_str(&string_enum(name, values));
+        } else {
+            let mut fields = String::new();
+            self.struct_fields(name, schema, &mut fields);
+            item.push_str(DERIVES);
+            item.push_str(&format!("pub struct {} {{\n{}}}\n", name, fields));
+        }
+        self.items.push(item);
+    }
+
+    fn struct_fields(&mut self, owner: &str, schema: &Schema, fields: &mut String) {
+        for part in schema.all_of.iter().flatten() {
+            if let Some(ref_path) = part_ref(part) {
+                let rust_type = reference_type(ref_path);
+                fields.push_str("    #[serde(flatten)]\n");
+                fields.push_str(&format!("    pub {}: {},\n", field_name(&rust_type), rust_type));
+            } else if let ObjectOrReference::Object(ref part) = *part {
+                self.struct_fields(owner, part, fields);
+            }
+        }
+
+        let tag = self
+            .tagged
+            .iter()
+            .find(|(variant, _)| variant == owner)
+            .map(|(_, property)| property.clone());
+        let relaxed = self
+            .flattened
+            .iter()
+            .find(|(component, _)| component == owner)
+            .map(|(_, property)| property.clone());
+        let mut required = schema.required.clone().unwrap_or_default();
+        required.retain(|name| relaxed.as_ref() != Some(name));
+        for (name, property) in schema.properties.iter().flatten() {
+            if tag.as_ref() == Some(name) {
+                continue;
+            }
+            let field = field_name(name);
+            let mut rust_type = self.base_type(property, &format!("{}{}", owner, type_name(name)));
+            if rust_type == owner {
+                rust_type = format!("Box<{}>", rust_type);
+            }
+
+            fields.push_str(&doc_comment(property.description.as_ref(), "    "));
+            let mut attributes = Vec::new();
+            if field != *name {
+                attributes.push(format!("rename = {}", string_literal(name)));
+            }
+            // a required key is written even when null
+            let optional = !required.contains(name);
+            if optional {
+                attributes.push("skip_serializing_if = \"Option::is_none\"".to_string());
+            }
+            if optional || property.is_nullable() {
+                rust_type = format!("Option<{}>", rust_type);
+            }
+            if !attributes.is_empty() {
+                fields.push_str(&format!("    #[serde({})]\n", attributes.join(", ")));
+            }
+            fields.push_str(&format!("    pub {}: {},\n", field, rust_type));
+        }
+
+        if schema.properties.is_some() {
+            if let Some(ref values) = schema.additional_properties {
+                let rust_type = self.map_value_type(values, owner);
+                fields.push_str("    #[serde(flatten)]\n");
+                fields.push_str(&format!(
+                    "    pub additional_properties: std::collections::HashMap<String, {}>,\n",
+                    rust_type
+                ));
+            }
+        }
+    }
+
+    fn one_of_enum(&mut self, name: &str, schema: &Schema) -> String {
+        let parts = schema.one_of.as_ref().unwrap();
+        let discriminator = schema.discriminator.as_ref();
+        let mut variants = String::new();
+        let mut variant_names = BTreeSet::new();
+
+        for (index, part) in parts.iter().enumerate() {
+            let (variant, rust_type, tag) = match (part_ref(part), part) {
+                (Some(ref_path), _) => {
+                    let rust_type = reference_type(ref_path);
+                    let tag = discriminator.map(|discriminator| {
+                        let schema_name = ref_path.rsplit('/').next().unwrap_or(ref_path);
+                        discriminator
+                            .mapping
+                            .iter()
+                            .flatten()
+                            .find(|(_, target)| *target == ref_path || *target == schema_name)
+                            .map(|(value, _)| value.clone())
+                            .unwrap_or_else(|| schema_name.to_string())
+                    });
+                    (rust_type.clone(), rust_type, tag)
+                }
+                (None, ObjectOrReference::Object(part)) => {
+                    let variant = match part.title.as_deref().or_else(|| part.type_name()) {
+                        Some(title) => type_name(title),
+                        None => format!("Variant{}", index + 1),
+                    };
+                    let rust_type = self.rust_type(part, &format!("{}{}", name, variant));
+                    let tag = discriminator.map(|_| variant.clone());
+                    (variant, rust_type, tag)
+                }
+                _ => continue,
+            };
+
+            let variant = unique_variant(&mut variant_names, variant);
+            if let Some(tag) = tag {
+                if tag != variant {
+                    variants.push_str(&format!("    #[serde(rename = {})]\n", string_literal(&tag)));
+                }
+            }
+            variants.push_str(&format!("    {}({}),\n", variant, rust_type));
+        }
+
+        let representation = match discriminator {
+            Some(discriminator) => format!("#[serde(tag = {})]\n", string_literal(&discriminator.property_name)),
+            None => "#[serde(untagged)]\n".to_string(),
+        };
+        format!("{}{}pub enum {} {{\n{}}}\n", DERIVES, representation, name, variants)
+    }
+}
+
+/// The reference of a `$ref` schema, which deserializes as either variant.
+fn part_ref(part: &ObjectOrReference<Schema>) -> Option<&str> {
+    match *part {
+        ObjectOrReference::Ref { ref ref_path } => Some(ref_path),
+        ObjectOrReference::Object(ref schema) => schema.ref_path.as_deref(),
+    }
+}
+
+/// Whether `schema` needs a type of its own rather than a built in one.
+fn needs_definition(schema: &Schema) -> bool {
+    let string_enum = schema.enum_values.is_some() && schema.type_name().unwrap_or("string") == "string";
+    string_enum || schema.properties.is_some() || schema.all_of.is_some() || schema.one_of.is_some()
+}
+
+/// The type a `#/components/schemas/..` reference points to.
+fn reference_type(ref_path: &str) -> String {
+    type_name(ref_path.rsplit('/').next().unwrap_or(ref_path))
+}
+
+fn string_enum(name: &str, values: &[String]) -> String {
+    let mut variants = String::new();
+    let mut variant_names = BTreeSet::new();
+    for value in values {
+        let variant = unique_variant(&mut variant_names, type_name(value));
+        if &variant != value {
+            variants.push_str(&format!("    #[serde(rename = {})]\n", string_literal(value)));
+        }
+        variants.push_str(&format!("    {},\n", variant));
+    }
+    format!("{}pub enum {} {{\n{}}}\n", DERIVES, name, variants)
+}
+
+/// `base`, or `base` with a number appended if an earlier variant took it.
+fn unique_variant(names: &mut BTreeSet<String>, base: String) -> String {
+    let mut variant = base.clone();
+    let mut n = 2;
+    while names.contains(&variant) {
+        variant = format!("{}{}", base, n);
+        n += 1;
+    }
+    names.insert(variant.clone());
+    variant
+}
+
+/// Generate the types of the spec at `spec_path` into `out_path`, for use from a `build.rs`.
+/// Cargo is told to rerun the build script when the spec changes.
+pub fn generate_types<P, Q>(spec_path: P, out_path: Q) -> Result<()>
+where
+    P: AsRef<Path>,
+    Q: AsRef<Path>,
+{
+    println!("cargo:rerun-if-changed={}", spec_path.as_ref().display());
+    let spec = crate::from_path(spec_path)?;
+    fs::write(out_path, TypeGenerator::new(&spec).generate())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCHEMAS: &str = r#"openapi: 3.0.3
+info:
+  title: Pets
+  version: 1.0.0
+paths: {}
+components:
+  schemas:
+    Pet:
+      description: A pet.
+      required: [id, petType, owner]
+      properties:
+        id:
+          type: integer
+          format: int64
+        petType:
+          type: string
+        name:
+          type: string
+          nullable: true
+        owner:
+          type: string
+          nullable: true
+        self:
+          type: string
+        status:
+          type: string
+          enum: [available, sold-out]
+        parent:
+          $ref: '#/components/schemas/Pet'
+        labels:
+          type: object
+          additionalProperties:
+            type: string
+    Cat:
+      allOf:
+        - $ref: '#/components/schemas/Pet'
+        - properties:
+            indoor:
+              type: boolean
+    Dog:
+      properties:
+        petType:
+          type: string
+        born:
+          type: string
+          format: date
+    AnyPet:
+      oneOf:
+        - $ref: '#/components/schemas/Cat'
+        - $ref: '#/components/schemas/Dog'
+      discriminator:
+        propertyName: petType
+        mapping:
+          cat: '#/components/schemas/Cat'
+    Ids:
+      type: array
+      items:
+        type: string
+        format: uuid
+"#;
+
+    #[test]
+    fn test_generate() {
+        let spec = crate::from_str(SCHEMAS).unwrap();
+        let code = TypeGenerator::new(&spec).chrono(true).uuid(true).generate();
+
+        assert!(code.contains("/// A pet.\n#[derive("));
+        assert!(code.contains("pub struct Pet {\n    pub id: i64,\n"));
+        assert!(code.contains("    #[serde(skip_serializing_if = \"Option::is_none\")]\n    pub name: Option<String>,\n"));
+        assert!(code.contains("\n    pub owner: Option<String>,\n"));
+        assert!(code.contains("    #[serde(rename = \"self\", skip_serializing_if = \"Option::is_none\")]\n    pub self_: Option<String>,\n"));
+        assert!(code.contains("pub status: Option<PetStatus>,"));
+        assert!(code.contains("pub enum PetStatus {\n    #[serde(rename = \"available\")]\n    Available,\n"));
+        assert!(code.contains("pub parent: Option<Box<Pet>>,"));
+        assert!(code.contains("pub labels: Option<std::collections::HashMap<String, String>>,"));
+        assert!(code.contains("pub struct Cat {\n    #[serde(flatten)]\n    pub pet: Pet,\n"));
+        assert!(code.contains("    #[serde(rename = \"petType\", skip_serializing_if = \"Option::is_none\")]\n    pub pet_type: Option<String>,\n"));
+        assert!(code.contains("pub struct Dog {\n    #[serde(skip_serializing_if = \"Option::is_none\")]\n    pub born: Option<chrono::NaiveDate>,\n}"));
+        assert!(code.contains("#[serde(tag = \"petType\")]\npub enum AnyPet {\n    #[serde(rename = \"cat\")]\n    Cat(Cat),\n    Dog(Dog),\n}"));
+        assert!(code.contains("pub type Ids = Vec<uuid::Uuid>;"));
+    }
+}
@@ -1,11 +1,19 @@
 use super::spec::{
     Callback, Example, Header, Link, RequestBody, Response, Schema, SecurityScheme,
 };
-use super::path::{Parameter};
+use super::path::{Parameter, PathItem};
+use serde::de::value::MapAccessDeserializer;
+use serde::de::{self, DeserializeSeed, Deserializer, IgnoredAny, MapAccess, Visitor};
 use serde::{Deserialize, Serialize};
-use std::collections::BTreeMap;
+use std::fmt;
+use std::marker::PhantomData;
+use crate::Map;
 
-#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+/// An object, or a `$ref` to one.
+///
+/// An object starting with a `$ref` is read as a reference, as the fields next to it are
+/// ignored, unless the object has a `$ref` of its own, see `Referable`.
+#[derive(Clone, Debug, Serialize, PartialEq)]
 #[serde(untagged)]
 pub enum ObjectOrReference<T> {
     Object(T),
@@ -15,6 +23,146 @@ pub enum ObjectOrReference<T> {
     },
 }
 
+/// Objects that can be referred to with an `ObjectOrReference`.
+pub trait Referable {
+    /// Whether the object reads `$ref` itself, as `Schema` and `PathItem` do. Such objects
+    /// keep their references, along with the fields next to them, as an
+    /// `ObjectOrReference::Object`.
+    const HAS_REF: bool = false;
+}
+
+impl<'de, T> Deserialize<'de> for ObjectOrReference<T>
+where
+    T: Deserialize<'de> + Referable,
+{
+    fn deserialize<D>(deserializer: D) -> Result<ObjectOrReference<T>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        if T::HAS_REF {
+            return T::deserialize(deserializer).map(ObjectOrReference::Object);
+        }
+        // objects with a catch-all `extensions` field or only optional fields would read
+        // any reference as an empty object, so a leading `$ref` is looked for first
+        deserializer.deserialize_map(ObjectOrReferenceVisitor(PhantomData))
+    }
+}
+
+struct ObjectOrReferenceVisitor<T>(PhantomData<T>);
+
+impl<'de, T> Visitor<'de> for ObjectOrReferenceVisitor<T>
+where
+    T: Deserialize<'de>,
+{
+    type Value = ObjectOrReference<T>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("an object or a $ref")
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<ObjectOrReference<T>, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let first = map.next_key::<serde_yaml::Value>()?;
+        if first.as_ref().and_then(serde_yaml::Value::as_str) == Some("$ref") {
+            let ref_path = map.next_value::<String>()?;
+            // the fields next to a reference are ignored
+            while map.next_entry::<IgnoredAny, IgnoredAny>()?.is_some() {}
+            return Ok(ObjectOrReference::Ref { ref_path });
+        }
+
+        // read the object from the map itself, so that errors keep their location
+        T::deserialize(MapAccessDeserializer::new(Replay { first, map })).map(ObjectOrReference::Object)
+    }
+}
+
+/// A map whose first key has already been read.
+struct Replay<A> {
+    first: Option<serde_yaml::Value>,
+    map: A,
+}
+
+impl<'de, A> MapAccess<'de> for Replay<A>
+where
+    A: MapAccess<'de>,
+{
+    type Error = A::Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, A::Error>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        match self.first.take() {
+            Some(key) => seed.deserialize(key).map(Some).map_err(de::Error::custom),
+            None => self.map.next_key_seed(seed),
+        }
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, A::Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        self.map.next_value_seed(seed)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        self.map.size_hint().map(|size| size + self.first.is_some() as usize)
+    }
+}
+
+impl Referable for Schema {
+    const HAS_REF: bool = true;
+}
+
+impl Referable for PathItem {
+    const HAS_REF: bool = true;
+}
+
+impl<T: Referable> Referable for Box<T> {
+    const HAS_REF: bool = T::HAS_REF;
+}
+
+impl Referable for Callback {}
+impl Referable for Example {}
+impl Referable for Header {}
+impl Referable for Link {}
+impl Referable for Parameter {}
+impl Referable for RequestBody {}
+impl Referable for Response {}
+impl Referable for SecurityScheme {}
+
+impl<T> ObjectOrReference<T> {
+    /// Resolve a local reference such as `#/components/schemas/Pet` against the component
+    /// map it points into. References are followed until an object is found.
+    pub fn resolve<'a>(
+        &'a self,
+        components: Option<&'a Map<String, ObjectOrReference<T>>>,
+    ) -> Option<&'a T> {
+        let mut current = self;
+        // guard against reference cycles
+        for _ in 0..32 {
+            match current {
+                ObjectOrReference::Object(object) => return Some(object),
+                ObjectOrReference::Ref { ref_path } => {
+                    current = components?.get(ref_name(ref_path)?)?;
+                }
+            }
+        }
+        None
+    }
+}
+
+/// The name of the component a local reference points to, e.g. `Pet` for
+/// `#/components/schemas/Pet`.
+pub fn ref_name(ref_path: &str) -> Option<&str> {
+    if ref_path.starts_with("#/components/") {
+        ref_path.rsplit('/').next()
+    } else {
+        None
+    }
+}
+
 /// Holds a set of reusable objects for different aspects of the OAS.
 /// All objects defined within the components object will have no effect on the API unless
 /// they are explicitly referenced from properties outside the components object.
@@ -24,38 +172,45 @@ pub enum ObjectOrReference<T> {
 pub struct Components {
     /// An object to hold reusable Schema Objects.
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub schemas: Option<BTreeMap<String, ObjectOrReference<Schema>>>,
+    pub schemas: Option<Map<String, ObjectOrReference<Schema>>>,
 
     /// An object to hold reusable Response Objects.
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub responses: Option<BTreeMap<String, ObjectOrReference<Response>>>,
+    pub responses: Option<Map<String, ObjectOrReference<Response>>>,
 
     /// An object to hold reusable Parameter Objects.
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub parameters: Option<BTreeMap<String, ObjectOrReference<Parameter>>>,
+    pub parameters: Option<Map<String, ObjectOrReference<Parameter>>>,
 
     /// An object to hold reusable Example
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub examples: Option<BTreeMap<String, ObjectOrReference<Example>>>,
+    pub examples: Option<Map<String, ObjectOrReference<Example>>>,
 
     /// An object to hold reusable Request Body Objects.
     #[serde(skip_serializing_if = "Option::is_none", rename = "requestBodies")]
-    pub request_bodies: Option<BTreeMap<String, ObjectOrReference<RequestBody>>>,
+    pub request_bodies: Option<Map<String, ObjectOrReference<RequestBody>>>,
 
     /// An object to hold reusable Header Objects.
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub headers: Option<BTreeMap<String, ObjectOrReference<Header>>>,
+    pub headers: Option<Map<String, ObjectOrReference<Header>>>,
 
     /// An object to hold reusable Security Scheme Objects.
     #[serde(skip_serializing_if = "Option::is_none", rename = "securitySchemes")]
-    pub security_schemes: Option<BTreeMap<String, ObjectOrReference<SecurityScheme>>>,
+    pub security_schemes: Option<Map<String, ObjectOrReference<SecurityScheme>>>,
 
     /// An object to hold reusable Link Objects.
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub links: Option<BTreeMap<String, ObjectOrReference<Link>>>,
+    pub links: Option<Map<String, ObjectOrReference<Link>>>,
 
     /// An object to hold reusable Callback Objects.
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub callbacks: Option<BTreeMap<String, ObjectOrReference<Callback>>>,
-    // TODO: Add "Specification Extensions" https://github.com/OAI/OpenAPI-Specification/blob/master/versions/3.0.1.md#specificationExtensions}
+    pub callbacks: Option<Map<String, ObjectOrReference<Callback>>>,
+
+    /// OpenApi 3.1 only. An object to hold reusable Path Item Objects.
+    #[serde(skip_serializing_if = "Option::is_none", rename = "pathItems")]
+    pub path_items: Option<Map<String, ObjectOrReference<PathItem>>>,
+
+    /// `x-` extensions and the fields not described by the model, see `Map`.
+    #[serde(flatten)]
+    pub extensions: Map<String, serde_json::Value>,
 }
\ No newline at end of file
@@ -0,0 +1,18 @@
+//! Results of converting between specification versions.
+
+use crate::spec::Spec;
+
+/// A feature of the source document that could not be represented in the converted `Spec`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LossyConversion {
+    /// JSON pointer of the affected node in the source document.
+    pub path: String,
+    pub message: String,
+}
+
+/// The converted `Spec` together with everything that was lost on the way.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Conversion {
+    pub spec: Spec,
+    pub lossy: Vec<LossyConversion>,
+}
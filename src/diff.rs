@@ -0,0 +1,914 @@
+//! Compare two versions of a spec and classify the changes for the consumers of the API.
+//!
+//! Operations are matched by path and method, parameters by name and location, responses by
+//! status and bodies by media type. Paths are matched by their template, so renaming a path
+//! parameter is not a change. Schemas are compared by direction: what a client sends
+//! may not be narrowed, so new required properties, removed enum values, changed types and
+//! tightened constraints of parameters and request bodies are breaking; what a client
+//! receives may not be widened, so removed properties, properties no longer required, new
+//! enum values and loosened constraints of responses are breaking. Properties of `allOf` parts
+//! are merged into the object, `oneOf` parts are compared in order. Security requirements are
+//! matched by their schemes, and new scopes are breaking as credentials need every one.
+
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::components::ObjectOrReference;
+use crate::handler::path_template_names;
+use crate::operation::Operation;
+use crate::path::{Parameter, PathItem};
+use crate::pointer;
+use crate::spec::{MediaType, Schema, SecurityRequirement, Spec};
+use crate::Map;
+
+/// What changed between the two specs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize)]
+pub enum ChangeKind {
+    PathRemoved,
+    PathAdded,
+    OperationRemoved,
+    OperationAdded,
+    ParameterRemoved,
+    ParameterAdded,
+    ParameterRequired,
+    ParameterOptional,
+    RequestBodyRemoved,
+    RequestBodyAdded,
+    RequestBodyRequired,
+    RequestBodyOptional,
+    MediaTypeRemoved,
+    MediaTypeAdded,
+    ResponseRemoved,
+    ResponseAdded,
+    PropertyRemoved,
+    PropertyAdded,
+    PropertyRequired,
+    PropertyOptional,
+    TypeChanged,
+    EnumValuesRemoved,
+    EnumValuesAdded,
+    NullableChanged,
+    ConstraintTightened,
+    ConstraintLoosened,
+    SecurityRequirementRemoved,
+    SecurityRequirementAdded,
+    SecurityScopesAdded,
+    SecurityScopesRemoved,
+    SecuritySchemeRemoved,
+    SecuritySchemeChanged,
+    SecuritySchemeAdded,
+    ServerRemoved,
+    ServerAdded,
+}
+
+/// A change between two specs.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Change {
+    pub kind: ChangeKind,
+    /// Whether consumers written against the old spec can fail against the new one.
+    pub breaking: bool,
+    /// JSON pointer of the changed element within the new spec, or within the old spec if it
+    /// was removed.
+    pub pointer: String,
+    pub message: String,
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let severity = if self.breaking { "breaking" } else { "non-breaking" };
+        write!(f, "{} ({}): {}", self.pointer, severity, self.message)
+    }
+}
+
+/// Whether a schema describes what clients send or what they receive.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Direction {
+    Request,
+    Response,
+}
+
+/// The pointers of an element within the old and the new spec.
+#[derive(Clone, Debug)]
+struct Location {
+    old: String,
+    new: String,
+}
+
+impl Location {
+    fn push(&self, token: &str) -> Location {
+        Location {
+            old: pointer::push(&self.old, token),
+            new: pointer::push(&self.new, token),
+        }
+    }
+}
+
+/// Every change from `old` to `new`, in document order.
+pub fn diff(old: &Spec, new: &Spec) -> Vec<Change> {
+    let mut differ = Differ {
+        old,
+        new,
+        changes: Vec::new(),
+        comparing: Vec::new(),
+    };
+    differ.servers();
+    differ.paths();
+    differ.security_schemes();
+    differ.changes
+}
+
+/// Whether any of `changes` is breaking.
+pub fn is_breaking(changes: &[Change]) -> bool {
+    changes.iter().any(|change| change.breaking)
+}
+
+struct Differ<'a> {
+    old: &'a Spec,
+    new: &'a Spec,
+    changes: Vec<Change>,
+    /// Pairs of referenced schemas being compared, in a direction, from the outermost to the
+    /// innermost, against cycles.
+    comparing: Vec<(String, String, bool)>,
+}
+
+impl<'a> Differ<'a> {
+    fn change(&mut self, kind: ChangeKind, breaking: bool, pointer: &str, message: String) {
+        self.changes.push(Change {
+            kind,
+            breaking,
+            pointer: pointer.to_string(),
+            message,
+        });
+    }
+
+    fn servers(&mut self) {
+        let urls = |spec: &Spec| -> Vec<String> { spec.servers.iter().flatten().map(|server| server.url.clone()).collect() };
+        let (old, new) = (urls(self.old), urls(self.new));
+        for (index, url) in old.iter().enumerate() {
+            if !new.contains(url) {
+                let pointer = pointer::push("/servers", &index.to_string());
+                self.change(ChangeKind::ServerRemoved, true, &pointer, format!("server {} was removed", url));
+            }
+        }
+        for (index, url) in new.iter().enumerate() {
+            if !old.contains(url) {
+                let pointer = pointer::push("/servers", &index.to_string());
+                self.change(ChangeKind::ServerAdded, false, &pointer, format!("server {} was added", url));
+            }
+        }
+    }
+
+    fn paths(&mut self) {
+        let (old, new) = (self.old, self.new);
+        let find = |paths: &'a Map<String, PathItem>, path: &str| {
+            paths.iter().find(|(candidate, _)| template(candidate) == template(path))
+        };
+        for (path, old_item) in old.paths.iter() {
+            match find(&new.paths, path) {
+                Some((new_path, new_item)) => {
+                    let location = Location {
+                        old: pointer::push("/paths", path),
+                        new: pointer::push("/paths", new_path),
+                    };
+                    self.path_item((path, old_item), (new_path, new_item), &location);
+                }
+                None => {
+                    let pointer = pointer::push("/paths", path);
+                    self.change(ChangeKind::PathRemoved, true, &pointer, format!("path {} was removed", path));
+                }
+            }
+        }
+        for path in new.paths.keys().filter(|path| find(&old.paths, path).is_none()) {
+            let pointer = pointer::push("/paths", path);
+            self.change(ChangeKind::PathAdded, false, &pointer, format!("path {} was added", path));
+        }
+    }
+
+    fn path_item(&mut self, old: (&str, &'a PathItem), new: (&str, &'a PathItem), location: &Location) {
+        let old_operations = old.1.operations();
+        let new_operations = new.1.operations();
+        for (method, old_operation) in old_operations.iter() {
+            let location = location.push(&method.as_str().to_lowercase());
+            match new_operations.iter().find(|(new_method, _)| new_method == method) {
+                Some((_, new_operation)) => {
+                    self.parameters((old.0, old.1, *old_operation), (new.0, new.1, *new_operation), &location);
+                    self.request_body(old_operation, new_operation, &location);
+                    self.responses(old_operation, new_operation, &location);
+                    self.security(old_operation, new_operation, &location);
+                }
+                None => {
+                    let message = format!("{} operation was removed", method);
+                    self.change(ChangeKind::OperationRemoved, true, &location.old, message);
+                }
+            }
+        }
+        for (method, _) in new_operations.iter() {
+            if !old_operations.iter().any(|(old_method, _)| old_method == method) {
+                let pointer = pointer::push(&location.new, &method.as_str().to_lowercase());
+                self.change(ChangeKind::OperationAdded, false, &pointer, format!("{} operation was added", method));
+            }
+        }
+    }
+
+    fn parameters(
+        &mut self,
+        old: (&str, &'a PathItem, &'a Operation),
+        new: (&str, &'a PathItem, &'a Operation),
+        location: &Location,
+    ) {
+        let old_parameters = parameters(self.old, old.1, old.2, &location.old);
+        let new_parameters = parameters(self.new, new.1, new.2, &location.new);
+        // path parameters are matched by their position in the template
+        let renamed: Vec<(&str, &str)> = path_template_names(old.0)
+            .into_iter()
+            .zip(path_template_names(new.0))
+            .collect();
+        let same = |old_parameter: &Parameter, new_parameter: &Parameter| {
+            old_parameter.location == new_parameter.location
+                && match renamed.iter().find(|(old_name, _)| *old_name == old_parameter.name) {
+                    Some((_, new_name)) if old_parameter.location == "path" => *new_name == new_parameter.name,
+                    _ => old_parameter.name == new_parameter.name,
+                }
+        };
+
+        for (old_parameter, old_pointer) in old_parameters.iter() {
+            let name = format!("{} parameter {}", old_parameter.location, old_parameter.name);
+            let (new_parameter, new_pointer) = match new_parameters.iter().find(|(p, _)| same(old_parameter, p)) {
+                Some(found) => found,
+                None => {
+                    self.change(ChangeKind::ParameterRemoved, true, old_pointer, format!("{} was removed", name));
+                    continue;
+                }
+            };
+            match (is_required(old_parameter), is_required(new_parameter)) {
+                (false, true) => {
+                    self.change(ChangeKind::ParameterRequired, true, new_pointer, format!("{} is now required", name))
+                }
+                (true, false) => {
+                    self.change(ChangeKind::ParameterOptional, false, new_pointer, format!("{} is now optional", name))
+                }
+                _ => {}
+            }
+            if let (Some(ref old_schema), Some(ref new_schema)) = (&old_parameter.schema, &new_parameter.schema) {
+                let location = Location {
+                    old: pointer::push(old_pointer, "schema"),
+                    new: pointer::push(new_pointer, "schema"),
+                };
+                self.schema(old_schema, new_schema, &location, Direction::Request);
+            }
+        }
+
+        for (new_parameter, pointer) in new_parameters.iter() {
+            if !old_parameters.iter().any(|(old_parameter, _)| same(old_parameter, new_parameter)) {
+                let required = is_required(new_parameter);
+                let message = format!(
+                    "{} {} parameter {} was added",
+                    if required { "required" } else { "optional" },
+                    new_parameter.location,
+                    new_parameter.name
+                );
+                self.change(ChangeKind::ParameterAdded, required, pointer, message);
+            }
+        }
+    }
+
+    fn request_body(&mut self, old: &'a Operation, new: &'a Operation, location: &Location) {
+        let location = location.push("requestBody");
+        let old_bodies = self.old.components.as_ref().and_then(|c| c.request_bodies.as_ref());
+        let new_bodies = self.new.components.as_ref().and_then(|c| c.request_bodies.as_ref());
+        let old_body = old.request_body.as_ref().and_then(|body| body.resolve(old_bodies));
+        let new_body = new.request_body.as_ref().and_then(|body| body.resolve(new_bodies));
+
+        match (old_body, new_body) {
+            (Some(old_body), Some(new_body)) => {
+                let (old_required, new_required) = (old_body.required == Some(true), new_body.required == Some(true));
+                if !old_required && new_required {
+                    let message = "the request body is now required".to_string();
+                    self.change(ChangeKind::RequestBodyRequired, true, &location.new, message);
+                } else if old_required && !new_required {
+                    let message = "the request body is now optional".to_string();
+                    self.change(ChangeKind::RequestBodyOptional, false, &location.new, message);
+                }
+                self.content(&old_body.content, &new_body.content, &location, Direction::Request);
+            }
+            (Some(_), None) => {
+                let message = "the request body was removed".to_string();
+                self.change(ChangeKind::RequestBodyRemoved, true, &location.old, message);
+            }
+            (None, Some(new_body)) => {
+                let required = new_body.required == Some(true);
+                let message = format!("{} request body was added", if required { "a required" } else { "an optional" });
+                self.change(ChangeKind::RequestBodyAdded, required, &location.new, message);
+            }
+            (None, None) => {}
+        }
+    }
+
+    fn responses(&mut self, old: &'a Operation, new: &'a Operation, location: &Location) {
+        let responses = location.push("responses");
+        for (status, old_response) in old.responses.iter() {
+            let location = responses.push(status);
+            match new.responses.get(status) {
+                Some(new_response) => {
+                    let empty = Map::new();
+                    let old_content = old_response.content.as_ref().unwrap_or(&empty);
+                    let new_content = new_response.content.as_ref().unwrap_or(&empty);
+                    self.content(old_content, new_content, &location, Direction::Response);
+                }
+                None => {
+                    let message = format!("response {} was removed", status);
+                    self.change(ChangeKind::ResponseRemoved, true, &location.old, message);
+                }
+            }
+        }
+        for status in new.responses.keys().filter(|status| !old.responses.contains_key(*status)) {
+            let pointer = pointer::push(&responses.new, status);
+            self.change(ChangeKind::ResponseAdded, false, &pointer, format!("response {} was added", status));
+        }
+    }
+
+    fn content(
+        &mut self,
+        old: &Map<String, MediaType>,
+        new: &Map<String, MediaType>,
+        location: &Location,
+        direction: Direction,
+    ) {
+        let content = location.push("content");
+        for (media_type, old_media) in old.iter() {
+            let location = content.push(media_type);
+            match new.get(media_type) {
+                Some(new_media) => {
+                    if let (Some(old_schema), Some(new_schema)) = (schema(&old_media.schema), schema(&new_media.schema)) {
+                        self.schema(&old_schema, &new_schema, &location.push("schema"), direction);
+                    }
+                }
+                None => {
+                    let message = format!("media type {} was removed", media_type);
+                    self.change(ChangeKind::MediaTypeRemoved, true, &location.old, message);
+                }
+            }
+        }
+        for media_type in new.keys().filter(|media_type| !old.contains_key(*media_type)) {
+            let pointer = pointer::push(&content.new, media_type);
+            self.change(ChangeKind::MediaTypeAdded, false, &pointer, format!("media type {} was added", media_type));
+        }
+    }
+
+    fn schema(&mut self, old: &Schema, new: &Schema, location: &Location, direction: Direction) {
+        // a pair is compared again at each use, but not within itself
+        let key = match (&old.ref_path, &new.ref_path) {
+            (Some(old_ref), Some(new_ref)) => Some((old_ref.clone(), new_ref.clone(), direction == Direction::Request)),
+            _ => None,
+        };
+        if let Some(ref key) = key {
+            if self.comparing.contains(key) {
+                return;
+            }
+            self.comparing.push(key.clone());
+        }
+        self.compare_schemas(old, new, location, direction);
+        if key.is_some() {
+            self.comparing.pop();
+        }
+    }
+
+    fn compare_schemas(&mut self, old: &Schema, new: &Schema, location: &Location, direction: Direction) {
+        let (old_spec, new_spec) = (self.old, self.new);
+        let old = old_spec.resolve_schema(old);
+        let new = new_spec.resolve_schema(new);
+        let request = direction == Direction::Request;
+
+        let (old_type, new_type) = (schema_type(old), schema_type(new));
+        if old_type != new_type || old.format != new.format {
+            let message = format!("the type changed from {} to {}", describe_type(old), describe_type(new));
+            // a type may be added to a value clients send, or removed from one they receive
+            let breaking = match (old_type.is_empty(), new_type.is_empty()) {
+                (true, false) => request,
+                (false, true) => !request,
+                _ => true,
+            };
+            self.change(ChangeKind::TypeChanged, breaking, &location.new, message);
+            return;
+        }
+
+        if old.nullable != new.nullable {
+            let nullable = new.nullable == Some(true);
+            let message = format!("the value is {} nullable", if nullable { "now" } else { "no longer" });
+            // null may no longer be sent, or may now be received
+            self.change(ChangeKind::NullableChanged, request != nullable, &location.new, message);
+        }
+
+        self.enum_values(old, new, &location.new, request);
+        self.constraints(old, new, &location.new, request);
+        self.properties(old, new, location, direction);
+
+        if let (Some(ref old_items), Some(ref new_items)) = (&old.items, &new.items) {
+            self.schema(old_items, new_items, &location.push("items"), direction);
+        }
+        let pairs = old.one_of.iter().flatten().zip(new.one_of.iter().flatten());
+        for (index, (old_part, new_part)) in pairs.enumerate() {
+            if let (Some(old_part), Some(new_part)) = (part(old_part), part(new_part)) {
+                let location = location.push("oneOf").push(&index.to_string());
+                self.schema(&old_part, &new_part, &location, direction);
+            }
+        }
+    }
+    fn enum_values(&mut self, old: &Schema, new: &Schema, path: &str, request: bool) {
+        let pointer = pointer::push(path, "enum");
+        let (removed, added): (Vec<&String>, Vec<&String>) = match (&old.enum_values, &new.enum_values) {
+            (Some(old_values), Some(new_values)) => (
+                old_values.iter().filter(|value| !new_values.contains(value)).collect(),
+                new_values.iter().filter(|value| !old_values.contains(value)).collect(),
+            ),
+            (None, Some(new_values)) => {
+                let message = format!("the values are now restricted to {}", join(new_values.iter()));
+                self.change(ChangeKind::EnumValuesRemoved, request, &pointer, message);
+                return;
+            }
+            (Some(old_values), None) => {
+                let message = format!("the values are no longer restricted to {}", join(old_values.iter()));
+                self.change(ChangeKind::EnumValuesAdded, !request, &pointer, message);
+                return;
+            }
+            (None, None) => return,
+        };
+        if !removed.is_empty() {
+            let message = format!("enum values {} were removed", join(removed.into_iter()));
+            self.change(ChangeKind::EnumValuesRemoved, request, &pointer, message);
+        }
+        if !added.is_empty() {
+            let message = format!("enum values {} were added", join(added.into_iter()));
+            self.change(ChangeKind::EnumValuesAdded, !request, &pointer, message);
+        }
+    }
+
+    fn constraints(&mut self, old: &Schema, new: &Schema, path: &str, request: bool) {
+        let number = |value: &Option<serde_json::Number>| value.as_ref().and_then(|number| number.as_f64());
+        let count = |value: &Option<u64>| value.map(|count| count as f64);
+        // (keyword, old, new, whether a greater value is tighter)
+        let bounds = vec![
+            ("maximum", number(&old.maximum), number(&new.maximum), false),
+            ("minimum", number(&old.minimum), number(&new.minimum), true),
+            ("maxLength", count(&old.max_length), count(&new.max_length), false),
+            ("minLength", count(&old.min_length), count(&new.min_length), true),
+            ("maxItems", count(&old.max_items), count(&new.max_items), false),
+            ("minItems", count(&old.min_items), count(&new.min_items), true),
+        ];
+        for (keyword, old_bound, new_bound, lower) in bounds {
+            let tightened = match (old_bound, new_bound) {
+                (None, Some(_)) => true,
+                (Some(_), None) => false,
+                (Some(old_bound), Some(new_bound)) if old_bound != new_bound => (new_bound > old_bound) == lower,
+                _ => continue,
+            };
+            let describe = |bound: Option<f64>| bound.map_or("none".to_string(), |bound| bound.to_string());
+            let message = format!("{} changed from {} to {}", keyword, describe(old_bound), describe(new_bound));
+            self.constraint(tightened, &pointer::push(path, keyword), message, request);
+        }
+
+        let tightened = match (&old.pattern, &new.pattern) {
+            (old_pattern, Some(new_pattern)) if old_pattern.as_ref() != Some(new_pattern) => Some(true),
+            (Some(_), None) => Some(false),
+            _ => None,
+        };
+        if let Some(tightened) = tightened {
+            let message = format!("the pattern changed from {:?} to {:?}", old.pattern, new.pattern);
+            self.constraint(tightened, &pointer::push(path, "pattern"), message, request);
+        }
+
+        let (old_unique, new_unique) = (old.unique_items == Some(true), new.unique_items == Some(true));
+        if old_unique != new_unique {
+            let message = format!("items are {} required to be unique", if new_unique { "now" } else { "no longer" });
+            self.constraint(new_unique, &pointer::push(path, "uniqueItems"), message, request);
+        }
+    }
+
+    fn constraint(&mut self, tightened: bool, pointer: &str, message: String, request: bool) {
+        if tightened {
+            self.change(ChangeKind::ConstraintTightened, request, pointer, message);
+        } else {
+            self.change(ChangeKind::ConstraintLoosened, !request, pointer, message);
+        }
+    }
+
+    /// Compare the properties of objects, those of `allOf` parts included.
+    fn properties(&mut self, old: &Schema, new: &Schema, location: &Location, direction: Direction) {
+        let request = direction == Direction::Request;
+        let properties = location.push("properties");
+        let (old_properties, old_required) = object_properties(self.old, old);
+        let (new_properties, new_required) = object_properties(self.new, new);
+
+        for (name, old_property) in old_properties.iter() {
+            let location = properties.push(name);
+            let new_property = match new_properties.get(name) {
+                Some(new_property) => new_property,
+                None => {
+                    // a property clients send may be ignored, one they receive may not be missing
+                    let message = format!("property {} was removed", name);
+                    self.change(ChangeKind::PropertyRemoved, !request, &location.old, message);
+                    continue;
+                }
+            };
+            match (old_required.contains(name), new_required.contains(name)) {
+                (false, true) => {
+                    let message = format!("property {} is now required", name);
+                    self.change(ChangeKind::PropertyRequired, request, &location.new, message);
+                }
+                (true, false) => {
+                    let message = format!("property {} is now optional", name);
+                    self.change(ChangeKind::PropertyOptional, !request, &location.new, message);
+                }
+                _ => {}
+            }
+            self.schema(old_property, new_property, &location, direction);
+        }
+
+        for name in new_properties.keys().filter(|name| !old_properties.contains_key(*name)) {
+            let pointer = pointer::push(&properties.new, name);
+            let required = new_required.contains(name);
+            let message = format!("{} property {} was added", if required { "required" } else { "optional" }, name);
+            self.change(ChangeKind::PropertyAdded, request && required, &pointer, message);
+        }
+    }
+
+    fn security(&mut self, old: &'a Operation, new: &'a Operation, location: &Location) {
+        let old_requirements = requirements(old.security.as_ref().or(self.old.security.as_ref()));
+        let new_requirements = requirements(new.security.as_ref().or(self.new.security.as_ref()));
+        let location = location.push("security");
+
+        let same = |old: &Requirement, new: &Requirement| {
+            old.len() == new.len() && old.iter().zip(new.iter()).all(|((old_name, _), (new_name, _))| old_name == new_name)
+        };
+
+        for old_requirement in old_requirements.iter() {
+            let new_requirement = match new_requirements.iter().find(|new_requirement| same(old_requirement, new_requirement)) {
+                Some(new_requirement) => new_requirement,
+                None => {
+                    let message = format!("security requirement {} was removed", describe_requirement(old_requirement));
+                    self.change(ChangeKind::SecurityRequirementRemoved, true, &location.old, message);
+                    continue;
+                }
+            };
+            // credentials need every scope listed, so a new scope may exclude clients
+            for ((name, old_scopes), (_, new_scopes)) in old_requirement.iter().zip(new_requirement.iter()) {
+                let added: Vec<&String> = new_scopes.iter().filter(|scope| !old_scopes.contains(scope)).collect();
+                let removed: Vec<&String> = old_scopes.iter().filter(|scope| !new_scopes.contains(scope)).collect();
+                if !added.is_empty() {
+                    let message = format!("scopes {} of {} were added", join(added.into_iter()), name);
+                    self.change(ChangeKind::SecurityScopesAdded, true, &location.new, message);
+                }
+                if !removed.is_empty() {
+                    let message = format!("scopes {} of {} were removed", join(removed.into_iter()), name);
+                    self.change(ChangeKind::SecurityScopesRemoved, false, &location.new, message);
+                }
+            }
+        }
+        for new_requirement in new_requirements.iter() {
+            if !old_requirements.iter().any(|old_requirement| same(old_requirement, new_requirement)) {
+                let message = format!("security requirement {} was added", describe_requirement(new_requirement));
+                self.change(ChangeKind::SecurityRequirementAdded, false, &location.new, message);
+            }
+        }
+    }
+
+    fn security_schemes(&mut self) {
+        let schemes = |spec: &'a Spec| spec.components.as_ref().and_then(|c| c.security_schemes.as_ref());
+        let (old_schemes, new_schemes) = (schemes(self.old), schemes(self.new));
+        let empty = Map::new();
+
+        for (name, old_scheme) in old_schemes.unwrap_or(&empty).iter() {
+            let pointer = pointer::push("/components/securitySchemes", name);
+            let new_scheme = new_schemes.and_then(|schemes| schemes.get(name));
+            match new_scheme {
+                Some(new_scheme) => {
+                    let old_scheme = old_scheme.resolve(old_schemes);
+                    if old_scheme != new_scheme.resolve(new_schemes) {
+                        let message = format!("security scheme {} changed", name);
+                        self.change(ChangeKind::SecuritySchemeChanged, true, &pointer, message);
+                    }
+                }
+                None => {
+                    let message = format!("security scheme {} was removed", name);
+                    self.change(ChangeKind::SecuritySchemeRemoved, true, &pointer, message);
+                }
+            }
+        }
+        for name in new_schemes.unwrap_or(&empty).keys() {
+            if old_schemes.and_then(|schemes| schemes.get(name)).is_none() {
+                let pointer = pointer::push("/components/securitySchemes", name);
+                let message = format!("security scheme {} was added", name);
+                self.change(ChangeKind::SecuritySchemeAdded, false, &pointer, message);
+            }
+        }
+    }
+}
+
+/// The parameters of an operation, those of the path item overridden by those of the
+/// operation, resolved through `components.parameters`, with the pointer of each.
+fn parameters<'a>(spec: &'a Spec, item: &'a PathItem, operation: &'a Operation, path: &str) -> Vec<(&'a Parameter, String)> {
+    let components = spec.components.as_ref().and_then(|components| components.parameters.as_ref());
+    let item_path = pointer::tokens(path);
+    let item_path: String = item_path[..item_path.len() - 1].iter().fold(String::new(), |pointer, token| {
+        pointer::push(&pointer, token)
+    });
+
+    let mut parameters: Vec<(&Parameter, String)> = Vec::new();
+    let declared = item
+        .parameters
+        .iter()
+        .flatten()
+        .enumerate()
+        .map(|(index, parameter)| (parameter, pointer::push(&pointer::push(&item_path, "parameters"), &index.to_string())))
+        .chain(operation.parameters.iter().flatten().enumerate().map(|(index, parameter)| {
+            (parameter, pointer::push(&pointer::push(path, "parameters"), &index.to_string()))
+        }));
+    for (parameter, pointer) in declared {
+        if let Some(parameter) = parameter.resolve(components) {
+            parameters.retain(|(p, _)| p.name != parameter.name || p.location != parameter.location);
+            parameters.push((parameter, pointer));
+        }
+    }
+    parameters
+}
+
+/// A path with its parameters unnamed, e.g. `/pets/{}`.
+fn template(path: &str) -> String {
+    path.split('/')
+        .map(|segment| if segment.starts_with('{') && segment.ends_with('}') { "{}" } else { segment })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn is_required(parameter: &Parameter) -> bool {
+    parameter.location == "path" || parameter.required == Some(true)
+}
+
+/// The properties of an object schema and the names of the required ones, merged with
+/// those of its `allOf` parts.
+fn object_properties(spec: &Spec, schema: &Schema) -> (Map<String, Schema>, Vec<String>) {
+    let mut properties = Map::new();
+    let mut required = Vec::new();
+    collect_properties(spec, schema, &mut properties, &mut required, 0);
+    (properties, required)
+}
+
+fn collect_properties(
+    spec: &Spec,
+    schema: &Schema,
+    properties: &mut Map<String, Schema>,
+    required: &mut Vec<String>,
+    depth: usize,
+) {
+    // guard against reference cycles
+    if depth > 32 {
+        return;
+    }
+    let schema = spec.resolve_schema(schema);
+    for (name, property) in schema.properties.iter().flatten() {
+        properties.insert(name.clone(), property.clone());
+    }
+    required.extend(schema.required.iter().flatten().cloned());
+    for part in schema.all_of.iter().flatten().filter_map(part) {
+        collect_properties(spec, &part, properties, required, depth + 1);
+    }
+}
+
+/// The scheme names of a security requirement, sorted, with their scopes.
+type Requirement = Vec<(String, Vec<String>)>;
+
+/// The alternatives of a security declaration. No declaration, or an empty one, is the empty
+/// requirement: no credentials needed.
+fn requirements(security: Option<&Vec<SecurityRequirement>>) -> Vec<Requirement> {
+    let requirements: Vec<Requirement> = security
+        .into_iter()
+        .flatten()
+        .map(|requirement| {
+            let mut schemes: Requirement = requirement
+                .iter()
+                .map(|(name, scopes)| (name.clone(), scopes.clone()))
+                .collect();
+            schemes.sort_by(|a, b| a.0.cmp(&b.0));
+            schemes
+        })
+        .collect();
+    if requirements.is_empty() {
+        vec![Vec::new()]
+    } else {
+        requirements
+    }
+}
+
+fn describe_requirement(requirement: &[(String, Vec<String>)]) -> String {
+    if requirement.is_empty() {
+        "without credentials".to_string()
+    } else {
+        requirement.iter().map(|(name, _)| name.as_str()).collect::<Vec<_>>().join(" and ")
+    }
+}
+
+/// The schema of a media type, inline or referenced.
+fn schema(schema: &Option<ObjectOrReference<Schema>>) -> Option<Schema> {
+    schema.as_ref().and_then(part)
+}
+
+/// An `allOf` or `oneOf` part, inline or referenced.
+fn part(part: &ObjectOrReference<Schema>) -> Option<Schema> {
+    match part {
+        ObjectOrReference::Object(schema) => Some(schema.clone()),
+        ObjectOrReference::Ref { ref_path } => Some(Schema {
+            ref_path: Some(ref_path.clone()),
+            ..Default::default()
+        }),
+    }
+}
+
+/// The types of a schema, `null` left out as `nullable` is compared on its own. Schemas
+/// with properties or `allOf` parts and no type are objects.
+fn schema_type(schema: &Schema) -> Vec<&str> {
+    let mut types: Vec<&str> = schema
+        .schema_type
+        .as_ref()
+        .map(|schema_type| schema_type.types())
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|schema_type| *schema_type != "null")
+        .collect();
+    if types.is_empty() && (schema.properties.is_some() || schema.all_of.is_some()) {
+        types.push("object");
+    }
+    types.sort();
+    types
+}
+
+fn describe_type(schema: &Schema) -> String {
+    let types = schema_type(schema);
+    let mut described = if types.is_empty() { "any".to_string() } else { types.join(" or ") };
+    if let Some(ref format) = schema.format {
+        described.push_str(&format!(" ({})", format));
+    }
+    described
+}
+
+fn join<'a, I>(values: I) -> String
+where
+    I: Iterator<Item = &'a String>,
+{
+    values.map(|value| format!("`{}`", value)).collect::<Vec<_>>().join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn changes(old: &str, new: &str) -> Vec<Change> {
+        diff(&crate::from_str(old).unwrap(), &crate::from_str(new).unwrap())
+    }
+
+    const OLD: &str = r#"
+openapi: 3.0.0
+info: {title: Pets, version: "1"}
+servers:
+  - url: https://pets.example.com/v1
+paths:
+  /pets:
+    get:
+      parameters:
+        - {name: limit, in: query, schema: {type: integer, maximum: 100}}
+      responses:
+        '200':
+          description: The pets
+          content:
+            application/json:
+              schema:
+                type: array
+                items: {$ref: '#/components/schemas/Pet'}
+        '404': {description: No pets}
+    post:
+      requestBody:
+        content:
+          application/json:
+            schema: {$ref: '#/components/schemas/Pet'}
+      responses:
+        '201': {description: Created}
+  /owners:
+    get:
+      responses:
+        '200': {description: The owners}
+components:
+  schemas:
+    Pet:
+      type: object
+      required: [name]
+      properties:
+        name: {type: string}
+        kind: {type: string, enum: [cat, dog]}
+        age: {type: integer}
+"#;
+
+    #[test]
+    fn test_diff_identical() {
+        assert_eq!(changes(OLD, OLD), Vec::new());
+    }
+
+    #[test]
+    fn test_diff() {
+        let new = OLD
+            .replace("https://pets.example.com/v1", "https://api.example.com/v2")
+            .replace("maximum: 100}}", "maximum: 50}}\n        - {name: owner, in: query, required: true, schema: {type: string}}")
+            .replace("        '404': {description: No pets}\n", "")
+            .replace("enum: [cat, dog]", "enum: [cat, dog, bird]")
+            .replace("        age: {type: integer}\n", "        age: {type: string}\n")
+            .replace("  /owners:\n    get:\n      responses:\n        '200': {description: The owners}\n", "");
+        let changes = changes(OLD, &new);
+        let found = |kind: ChangeKind, pointer: &str| {
+            changes
+                .iter()
+                .find(|change| change.kind == kind && change.pointer == pointer)
+                .map(|change| change.breaking)
+        };
+
+        assert_eq!(found(ChangeKind::ServerRemoved, "/servers/0"), Some(true));
+        assert_eq!(found(ChangeKind::ServerAdded, "/servers/0"), Some(false));
+        assert_eq!(found(ChangeKind::PathRemoved, "/paths/~1owners"), Some(true));
+        assert_eq!(found(ChangeKind::ParameterAdded, "/paths/~1pets/get/parameters/1"), Some(true));
+        assert_eq!(
+            found(ChangeKind::ConstraintTightened, "/paths/~1pets/get/parameters/0/schema/maximum"),
+            Some(true)
+        );
+        assert_eq!(found(ChangeKind::ResponseRemoved, "/paths/~1pets/get/responses/404"), Some(true));
+        // a new enum value may surprise a client receiving it, but may be sent by one
+        let pet = "/paths/~1pets/get/responses/200/content/application~1json/schema/items/properties";
+        assert_eq!(found(ChangeKind::EnumValuesAdded, &format!("{}/kind/enum", pet)), Some(true));
+        let pet = "/paths/~1pets/post/requestBody/content/application~1json/schema/properties";
+        assert_eq!(found(ChangeKind::EnumValuesAdded, &format!("{}/kind/enum", pet)), Some(false));
+        assert_eq!(found(ChangeKind::TypeChanged, &format!("{}/age", pet)), Some(true));
+        assert!(is_breaking(&changes));
+    }
+
+    #[test]
+    fn test_diff_security() {
+        let secured = OLD.replace(
+            "components:\n",
+            "security:\n  - api_key: []\ncomponents:\n  securitySchemes:\n    api_key: {type: apiKey, name: key, in: header}\n",
+        );
+        let changes = changes(OLD, &secured);
+
+        let removed: Vec<&Change> = changes
+            .iter()
+            .filter(|change| change.kind == ChangeKind::SecurityRequirementRemoved)
+            .collect();
+        assert_eq!(removed.len(), 3);
+        assert!(removed.iter().all(|change| change.breaking));
+        assert!(changes
+            .iter()
+            .any(|change| change.kind == ChangeKind::SecuritySchemeAdded && !change.breaking));
+    }
+
+    #[test]
+    fn test_diff_security_scopes() {
+        let secured = |scopes: &str| {
+            OLD.replace(
+                "components:\n",
+                &format!(
+                    "security:\n  - oauth: [{}]\ncomponents:\n  securitySchemes:\n    oauth: {{type: oauth2, flows: {{}}}}\n",
+                    scopes
+                ),
+            )
+        };
+        let changes = changes(&secured("read"), &secured("write"));
+
+        let added = changes.iter().filter(|change| change.kind == ChangeKind::SecurityScopesAdded);
+        assert_eq!(added.clone().count(), 3);
+        assert!(added.clone().all(|change| change.breaking && change.message.contains("`write`")));
+        let removed = changes.iter().filter(|change| change.kind == ChangeKind::SecurityScopesRemoved);
+        assert!(removed.clone().count() == 3 && removed.clone().all(|change| !change.breaking));
+        assert!(!changes.iter().any(|change| change.kind == ChangeKind::SecurityRequirementRemoved));
+    }
+
+    #[test]
+    fn test_diff_shared_schema() {
+        let old = OLD.replace(
+            "'201': {description: Created}",
+            "'201':\n          description: Created\n          content:\n            application/json:\n              schema: {$ref: '#/components/schemas/Pet'}",
+        );
+        let new = old.replace("        age: {type: integer}\n", "");
+        let changes = changes(&old, &new);
+        let removed: Vec<(&str, bool)> = changes
+            .iter()
+            .filter(|change| change.kind == ChangeKind::PropertyRemoved)
+            .map(|change| (change.pointer.as_str(), change.breaking))
+            .collect();
+
+        // reported at each use, breaking only for the clients receiving it
+        assert_eq!(
+            removed,
+            vec![
+                ("/paths/~1pets/get/responses/200/content/application~1json/schema/items/properties/age", true),
+                ("/paths/~1pets/post/requestBody/content/application~1json/schema/properties/age", false),
+                ("/paths/~1pets/post/responses/201/content/application~1json/schema/properties/age", true),
+            ]
+        );
+    }
+}
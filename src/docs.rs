@@ -0,0 +1,250 @@
+//! Interactive documentation pages for a served `Spec`.
+//!
+//! The Swagger UI assets are vendored under `assets/` and embedded in the binary, so the
+//! pages work offline. They can be loaded from a CDN instead, pinned to the versions below.
+//! ReDoc is not vendored and is always loaded from the CDN, see `assets/README.md`.
+
+use actix_web::{App, HttpRequest, HttpResponse};
+
+const SWAGGER_UI_CDN: &str = "https://unpkg.com/swagger-ui-dist@5.17.14";
+const REDOC_CDN: &str = "https://unpkg.com/redoc@2.0.0-rc.55/bundles";
+
+/// The embedded assets, by the name they are served under.
+const ASSETS: &[(&str, &[u8], &str)] = &[
+    (
+        "swagger-ui-bundle.js",
+        include_bytes!("../assets/swagger-ui/swagger-ui-bundle.js"),
+        "application/javascript",
+    ),
+    ("swagger-ui.css", include_bytes!("../assets/swagger-ui/swagger-ui.css"), "text/css"),
+];
+
+/// Where the documentation page loads the viewer's scripts and stylesheet from.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DocsAssets {
+    /// The assets embedded in the binary, served below `{path}/assets`.
+    Embedded,
+    /// unpkg.com, at the pinned versions of swagger-ui-dist and redoc.
+    Cdn,
+}
+
+/// The documentation viewer rendering the page.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DocsUi {
+    SwaggerUi,
+    ReDoc,
+}
+
+/// Configuration of a documentation page.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DocsConfig {
+    /// The url the spec is served from, e.g. `/openapi.json`.
+    pub spec_url: String,
+    /// The title of the html page.
+    pub title: String,
+    /// The viewer used to render the page.
+    pub ui: DocsUi,
+    /// Tags whose operations are expanded when the page loads. Only honored by Swagger UI.
+    pub expanded_tags: Vec<String>,
+    /// Enables the "Try it out" button of Swagger UI.
+    pub try_it_out: bool,
+    /// Where the viewer's assets are loaded from.
+    pub assets: DocsAssets,
+}
+
+impl DocsConfig {
+    pub fn new(spec_url: &str) -> DocsConfig {
+        DocsConfig {
+            spec_url: spec_url.to_string(),
+            title: "API documentation".to_string(),
+            ui: DocsUi::SwaggerUi,
+            expanded_tags: Vec::new(),
+            try_it_out: true,
+            assets: DocsAssets::Embedded,
+        }
+    }
+
+    pub fn title(mut self, title: &str) -> DocsConfig {
+        self.title = title.to_string();
+        self
+    }
+
+    pub fn ui(mut self, ui: DocsUi) -> DocsConfig {
+        self.ui = ui;
+        self
+    }
+
+    pub fn expand_tag(mut self, tag: &str) -> DocsConfig {
+        self.expanded_tags.push(tag.to_string());
+        self
+    }
+
+    pub fn try_it_out(mut self, enabled: bool) -> DocsConfig {
+        self.try_it_out = enabled;
+        self
+    }
+
+    pub fn assets(mut self, assets: DocsAssets) -> DocsConfig {
+        self.assets = assets;
+        self
+    }
+
+    /// Render the html page. `assets` is the url prefix the embedded assets are served from.
+    pub fn render_page(&self, assets: &str) -> String {
+        let title = escape_html(&self.title);
+        let spec_url = js_string(&self.spec_url);
+        let (swagger_ui, redoc) = match self.assets {
+            DocsAssets::Embedded => (assets, REDOC_CDN),
+            DocsAssets::Cdn => (SWAGGER_UI_CDN, REDOC_CDN),
+        };
+
+        match self.ui {
+            DocsUi::SwaggerUi => format!(
+                r##"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>{title}</title>
+<link rel="stylesheet" href="{assets}/swagger-ui.css">
+</head>
+<body>
+<div id="swagger-ui"></div>
+<script src="{assets}/swagger-ui-bundle.js"></script>
+<script>
+const ui = SwaggerUIBundle({{
+  url: {spec_url},
+  dom_id: "#swagger-ui",
+  docExpansion: "none",
+  tryItOutEnabled: {try_it_out},
+  supportedSubmitMethods: {submit_methods},
+  onComplete: function() {{
+    {expanded_tags}.forEach(function(tag) {{
+      ui.layoutActions.show(["operations-tag", tag], true);
+    }});
+  }}
+}});
+</script>
+</body>
+</html>
+"##,
+                title = title,
+                assets = swagger_ui,
+                spec_url = spec_url,
+                try_it_out = self.try_it_out,
+                submit_methods = if self.try_it_out {
+                    r#"["get", "put", "post", "delete", "options", "head", "patch", "trace"]"#
+                } else {
+                    "[]"
+                },
+                expanded_tags = serde_json::to_string(&self.expanded_tags).unwrap_or_else(|_| "[]".to_string()),
+            ),
+            DocsUi::ReDoc => format!(
+                r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>{title}</title>
+</head>
+<body>
+<div id="redoc"></div>
+<script src="{assets}/redoc.standalone.js"></script>
+<script>
+Redoc.init({spec_url}, {{}}, document.getElementById("redoc"));
+</script>
+</body>
+</html>
+"#,
+                title = title,
+                assets = redoc,
+                spec_url = spec_url,
+            ),
+        }
+    }
+}
+
+/// Mount the documentation page on `path`, and with `DocsAssets::Embedded` its assets below
+/// `{path}/assets`.
+pub fn mount<S: 'static>(app: App<S>, path: &str, config: DocsConfig) -> App<S> {
+    let assets = format!("{}/assets", path.trim_end_matches('/'));
+    let page = config.render_page(&assets);
+
+    let app = app.resource(path, move |r| {
+        r.get().f(move |_req| {
+            HttpResponse::Ok()
+                .content_type("text/html; charset=utf-8")
+                .body(page.clone())
+        })
+    });
+    match config.assets {
+        DocsAssets::Embedded => app.resource(&format!("{}/{{file}}", assets), |r| r.get().f(asset)),
+        DocsAssets::Cdn => app,
+    }
+}
+
+fn asset<S>(req: &HttpRequest<S>) -> HttpResponse {
+    let file = req.match_info().get("file");
+    match ASSETS.iter().find(|(name, _, _)| Some(*name) == file) {
+        Some((_, body, content_type)) => HttpResponse::Ok()
+            .content_type(*content_type)
+            .header("cache-control", "public, max-age=86400")
+            .body(*body),
+        None => HttpResponse::NotFound().finish(),
+    }
+}
+
+fn escape_html(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Quote a value as a javascript string literal that is safe to embed in a `<script>` block.
+fn js_string(value: &str) -> String {
+    serde_json::to_string(value)
+        .unwrap_or_else(|_| "\"\"".to_string())
+        .replace("</", "<\\/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_swagger_ui() {
+        let page = DocsConfig::new("/openapi.json")
+            .title("Petstore <v1>")
+            .expand_tag("pets")
+            .try_it_out(false)
+            .render_page("/docs/assets");
+
+        assert!(page.contains("<title>Petstore &lt;v1&gt;</title>"));
+        assert!(page.contains(r#"url: "/openapi.json""#));
+        assert!(page.contains(r#"["pets"]"#));
+        assert!(page.contains("supportedSubmitMethods: []"));
+        assert!(page.contains("/docs/assets/swagger-ui-bundle.js"));
+
+        let page = DocsConfig::new("/openapi.json").assets(DocsAssets::Cdn).render_page("/docs/assets");
+        assert!(page.contains(&format!("{}/swagger-ui.css", SWAGGER_UI_CDN)));
+        assert!(!page.contains("/docs/assets"));
+    }
+
+    #[test]
+    fn test_embedded_assets() {
+        for (name, body, _) in ASSETS.iter() {
+            assert!(!body.is_empty(), "{} is empty", name);
+        }
+    }
+
+    #[test]
+    fn test_render_redoc() {
+        let page = DocsConfig::new("/openapi.json")
+            .ui(DocsUi::ReDoc)
+            .render_page("/docs/assets");
+
+        assert!(page.contains(r#"Redoc.init("/openapi.json""#));
+        assert!(page.contains(&format!(r#"<script src="{}/redoc.standalone.js">"#, REDOC_CDN)));
+        assert!(!page.contains("/docs/assets"));
+    }
+}
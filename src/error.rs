@@ -5,6 +5,9 @@ use semver::{SemVerError, Version};
 use serde_json::Error as JsonError;
 use serde_yaml::Error as YamlError;
 use std::io::Error as IoError;
+use toml::de::Error as TomlError;
+
+use crate::parse_error::ParseError;
 
 /// errors that openapi functions may return
 #[derive(Fail, Debug)]
@@ -16,9 +19,29 @@ pub enum Error {
     #[fail(display = "{}", _0)]
     Serialize(JsonError),
     #[fail(display = "{}", _0)]
+    Toml(TomlError),
+    #[fail(display = "{}", _0)]
     SemVerError(SemVerError),
+    #[fail(display = "{}", _0)]
+    Parse(Box<ParseError>),
     #[fail(display = "Unsupported spec file version ({})", _0)]
     UnsupportedSpecFileVersion(Version),
+    #[fail(display = "Spec file declares neither an `openapi` nor a `swagger` version")]
+    MissingSpecFileVersion,
+    #[fail(display = "Invalid spec: {}", _0)]
+    InvalidSpec(String),
+    #[fail(display = "Request failed: {}", _0)]
+    Request(String),
+    #[fail(display = "Unexpected response status ({})", _0)]
+    UnexpectedStatus(u16),
+    #[fail(display = "Invalid parameter {}: {}", _0, _1)]
+    InvalidParameter(String, String),
+    #[fail(display = "Merge conflict: {}", _0)]
+    MergeConflict(String),
+    #[fail(display = "Invalid lint configuration: {}", _0)]
+    LintConfig(String),
+    #[fail(display = "Unresolved reference {}", _0)]
+    UnresolvedReference(String),
 }
 
 impl From<IoError> for Error {
@@ -39,6 +62,18 @@ impl From<JsonError> for Error {
     }
 }
 
+impl From<TomlError> for Error {
+    fn from(e: TomlError) -> Self {
+        Error::Toml(e)
+    }
+}
+
+impl From<ParseError> for Error {
+    fn from(e: ParseError) -> Self {
+        Error::Parse(Box::new(e))
+    }
+}
+
 impl From<SemVerError> for Error {
     fn from(e: SemVerError) -> Self {
         Error::SemVerError(e)
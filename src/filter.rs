@@ -0,0 +1,266 @@
+//! Slice a spec down to some of its operations, e.g. to publish the public part of an
+//! internal API.
+//!
+//! ```ignore
+//! let public = Filter::new()
+//!     .path("/pets/**")
+//!     .without_extension("x-internal", true)
+//!     .apply(&spec)?;
+//! ```
+//!
+//! The criteria of a `Filter` must all hold for an operation to be kept, any of the values
+//! given for one of them matching. Paths and webhooks left without operations are removed,
+//! as are the components and tags nothing refers to anymore, so the result is a valid spec
+//! on its own. Path items that refer to a component are kept or removed by their path only.
+
+use std::collections::HashSet;
+
+use actix_web::http::Method;
+use serde_json::Value;
+
+use crate::cleanup;
+use crate::operation::Operation;
+use crate::path::PathItem;
+use crate::spec::Spec;
+use crate::{Map, Result};
+
+/// Decides whether to keep an operation, given its path and method.
+type Predicate = Box<dyn Fn(&str, &Method, &Operation) -> bool>;
+
+/// Keeps the operations of a spec that match all its criteria.
+#[derive(Default)]
+pub struct Filter {
+    tags: Vec<String>,
+    paths: Vec<String>,
+    methods: Vec<Method>,
+    excluded: Vec<(String, Value)>,
+    predicates: Vec<Predicate>,
+}
+
+impl Filter {
+    pub fn new() -> Filter {
+        Filter::default()
+    }
+
+    /// Keep operations tagged `tag`.
+    pub fn tag(mut self, tag: &str) -> Filter {
+        self.tags.push(tag.to_string());
+        self
+    }
+
+    /// Keep operations on paths matching `glob`, where `*` matches within a segment and `**`
+    /// across segments, e.g. `/pets/*` or `/admin/**`. Webhooks are matched by name.
+    pub fn path(mut self, glob: &str) -> Filter {
+        self.paths.push(glob.to_string());
+        self
+    }
+
+    /// Keep operations of `method`.
+    pub fn method(mut self, method: Method) -> Filter {
+        self.methods.push(method);
+        self
+    }
+
+    /// Remove operations, or whole path items, whose extension `name` is `value`, e.g.
+    /// `x-internal: true`.
+    pub fn without_extension<V>(mut self, name: &str, value: V) -> Filter
+    where
+        V: Into<Value>,
+    {
+        self.excluded.push((name.to_string(), value.into()));
+        self
+    }
+
+    /// Keep operations for which `predicate`, given their path and method, is true.
+    pub fn predicate<F>(mut self, predicate: F) -> Filter
+    where
+        F: Fn(&str, &Method, &Operation) -> bool + 'static,
+    {
+        self.predicates.push(Box::new(predicate));
+        self
+    }
+
+    /// The spec with only the matching operations, and the components and tags they use.
+    pub fn apply(&self, spec: &Spec) -> Result<Spec> {
+        let mut spec = spec.clone();
+        spec.paths = self.items(&spec.paths);
+        if let Some(ref webhooks) = spec.webhooks {
+            spec.webhooks = Some(self.items(webhooks));
+        }
+
+        let used: HashSet<&String> = spec
+            .paths
+            .values()
+            .chain(spec.webhooks.iter().flat_map(|webhooks| webhooks.values()))
+            .flat_map(|item| item.operations())
+            .flat_map(|(_, operation)| operation.tags.iter().flatten())
+            .collect();
+        let tags = spec.tags.take().map(|tags| {
+            tags.into_iter()
+                .filter(|tag| used.contains(&tag.name))
+                .collect::<Vec<_>>()
+        });
+        spec.tags = tags.filter(|tags| !tags.is_empty());
+
+        let mut document = serde_json::to_value(&spec)?;
+        cleanup::remove_unused_components(&mut document);
+        crate::from_value(document)
+    }
+
+    fn items(&self, items: &Map<String, PathItem>) -> Map<String, PathItem> {
+        let mut kept = Map::new();
+        for (path, item) in items.iter() {
+            if !self.paths.is_empty() && !self.paths.iter().any(|glob| glob_matches(glob, path)) {
+                continue;
+            }
+            if self.is_excluded(&item.extensions) {
+                continue;
+            }
+            if item.reference.is_some() {
+                kept.insert(path.clone(), item.clone());
+                continue;
+            }
+            let mut item = item.clone();
+            item.retain_operations(|method, operation| self.keeps(path, method, operation));
+            if !item.operations().is_empty() {
+                kept.insert(path.clone(), item);
+            }
+        }
+        kept
+    }
+
+    fn keeps(&self, path: &str, method: &Method, operation: &Operation) -> bool {
+        let tagged = || operation.tags.iter().flatten().any(|tag| self.tags.contains(tag));
+        (self.tags.is_empty() || tagged())
+            && (self.methods.is_empty() || self.methods.contains(method))
+            && !self.is_excluded(&operation.extensions)
+            && self.predicates.iter().all(|predicate| predicate(path, method, operation))
+    }
+
+    fn is_excluded(&self, extensions: &Map<String, Value>) -> bool {
+        self.excluded
+            .iter()
+            .any(|(name, value)| extensions.get(name) == Some(value))
+    }
+}
+
+/// Whether `path` matches `glob`, where `*` matches any characters but `/` and `**` any
+/// characters.
+fn glob_matches(glob: &str, path: &str) -> bool {
+    if let Some(rest) = glob.strip_prefix("**") {
+        return (0..=path.len())
+            .filter(|&i| path.is_char_boundary(i))
+            .any(|i| glob_matches(rest, &path[i..]));
+    }
+    if let Some(rest) = glob.strip_prefix('*') {
+        let segment = path.find('/').unwrap_or(path.len());
+        return (0..=segment)
+            .filter(|&i| path.is_char_boundary(i))
+            .any(|i| glob_matches(rest, &path[i..]));
+    }
+    match (glob.chars().next(), path.chars().next()) {
+        (None, None) => true,
+        (Some(g), Some(p)) if g == p => glob_matches(&glob[g.len_utf8()..], &path[p.len_utf8()..]),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SPEC: &str = r#"
+openapi: 3.0.0
+info: {title: Pets, version: "1"}
+tags:
+  - name: pets
+  - name: admin
+paths:
+  /pets:
+    get:
+      tags: [pets]
+      responses:
+        '200':
+          description: The pets
+          content:
+            application/json:
+              schema:
+                type: array
+                items: {$ref: '#/components/schemas/Pet'}
+    post:
+      tags: [pets]
+      x-internal: true
+      requestBody:
+        $ref: '#/components/requestBodies/NewPet'
+      responses:
+        '201': {description: Created}
+  /admin/users/{id}:
+    delete:
+      tags: [admin]
+      security:
+        - admin_key: []
+      parameters:
+        - $ref: '#/components/parameters/Id'
+      responses:
+        '204': {description: Deleted}
+components:
+  schemas:
+    Pet:
+      type: object
+      properties:
+        owner: {$ref: '#/components/schemas/Owner'}
+    Owner:
+      type: object
+    NewPet:
+      type: object
+  requestBodies:
+    NewPet:
+      content:
+        application/json:
+          schema: {$ref: '#/components/schemas/NewPet'}
+  parameters:
+    Id: {name: id, in: path, required: true, schema: {type: string}}
+  securitySchemes:
+    admin_key: {type: apiKey, name: key, in: header}
+"#;
+
+    #[test]
+    fn test_glob_matches() {
+        assert!(glob_matches("/pets", "/pets"));
+        assert!(glob_matches("/pets/*", "/pets/{id}"));
+        assert!(!glob_matches("/pets/*", "/pets/{id}/toys"));
+        assert!(glob_matches("/pets/**", "/pets/{id}/toys"));
+        assert!(glob_matches("/*/users/*", "/admin/users/{id}"));
+        assert!(!glob_matches("/pets/*", "/owners/1"));
+    }
+
+    #[test]
+    fn test_filter() {
+        let spec = crate::from_str(SPEC).unwrap();
+
+        let public = Filter::new().without_extension("x-internal", true).tag("pets").apply(&spec).unwrap();
+        assert_eq!(public.paths.keys().collect::<Vec<_>>(), vec!["/pets"]);
+        assert!(public.paths["/pets"].post.is_none());
+        let components = public.components.unwrap();
+        let mut schemas: Vec<&String> = components.schemas.as_ref().unwrap().keys().collect();
+        schemas.sort();
+        assert_eq!(schemas, vec!["Owner", "Pet"]);
+        assert!(components.request_bodies.is_none());
+        assert!(components.security_schemes.is_none());
+        assert_eq!(public.tags.unwrap().len(), 1);
+
+        let admin = Filter::new().path("/admin/**").method(Method::DELETE).apply(&spec).unwrap();
+        let components = admin.components.unwrap();
+        assert!(components.schemas.is_none());
+        assert!(components.parameters.unwrap().contains_key("Id"));
+        assert!(components.security_schemes.unwrap().contains_key("admin_key"));
+
+        let posts = Filter::new()
+            .predicate(|_, _, operation| operation.request_body.is_some())
+            .apply(&spec)
+            .unwrap();
+        let components = posts.components.unwrap();
+        assert!(components.request_bodies.unwrap().contains_key("NewPet"));
+        assert_eq!(components.schemas.unwrap().keys().collect::<Vec<_>>(), vec!["NewPet"]);
+    }
+}
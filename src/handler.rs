@@ -0,0 +1,162 @@
+//! Describe actix-web handlers as `Operation`s.
+//!
+//! `#[openapi_operation]` (with the `derive` feature) implements `OpenApiOperation` for an
+//! annotated handler, inferring parameters and the request body from its `Path`, `Query`,
+//! `Json` and `Form` extractors and a response from its return type. The functions of this
+//! module are what the generated code is made of. `SpecBuilder::operation` collects the
+//! described handlers into a `Spec`.
+
+use crate::components::ObjectOrReference;
+use crate::operation::Operation;
+use crate::path::Parameter;
+use crate::schema::Schemas;
+use crate::spec::{MediaType, RequestBody, Response, Schema};
+use crate::Map;
+
+/// A handler described as an operation.
+pub trait OpenApiOperation {
+    /// The lower case HTTP method, e.g. `get`.
+    fn method() -> &'static str;
+
+    /// The templated path, e.g. `/pets/{id}`.
+    fn path() -> &'static str;
+
+    /// The operation. Schemas of named types are registered in `schemas`.
+    fn operation(schemas: &mut Schemas) -> Operation;
+}
+
+/// The names of the `{templated}` segments of a path, in order.
+pub fn path_template_names(path: &str) -> Vec<&str> {
+    path.split('/')
+        .filter(|segment| segment.starts_with('{') && segment.ends_with('}'))
+        .map(|segment| &segment[1..segment.len() - 1])
+        .collect()
+}
+
+fn add_parameter(operation: &mut Operation, name: &str, location: &str, required: bool, schema: Schema) {
+    let parameters = operation.parameters.get_or_insert_with(Vec::new);
+    let exists = parameters.iter().any(|parameter| match *parameter {
+        ObjectOrReference::Object(ref parameter) => parameter.name == name && parameter.location == location,
+        _ => false,
+    });
+    if !exists {
+        parameters.push(ObjectOrReference::Object(Parameter {
+            name: name.to_string(),
+            location: location.to_string(),
+            required: Some(required),
+            schema: Some(schema),
+            ..Default::default()
+        }));
+    }
+}
+
+/// Path parameters from a `Path<(A, B, ..)>` extractor: one per template segment, in order.
+pub fn path_parameters(operation: &mut Operation, path: &str, schemas: Vec<Schema>) {
+    for (name, schema) in path_template_names(path).into_iter().zip(schemas) {
+        add_parameter(operation, name, "path", true, schema);
+    }
+}
+
+/// Path parameters from a `Path<T>` extractor. The properties of an object become one
+/// parameter each; any other schema is the first template segment.
+pub fn path_object_parameters(operation: &mut Operation, path: &str, schema: Schema) {
+    match schema.properties {
+        Some(properties) => {
+            for (name, property) in properties {
+                add_parameter(operation, &name, "path", true, property);
+            }
+        }
+        None => path_parameters(operation, path, vec![schema]),
+    }
+}
+
+/// Query parameters from a `Query<T>` extractor, one per property of the object.
+pub fn query_parameters(operation: &mut Operation, schema: Schema) {
+    let required = schema.required.unwrap_or_default();
+    for (name, property) in schema.properties.into_iter().flatten() {
+        let is_required = required.contains(&name);
+        add_parameter(operation, &name, "query", is_required, property);
+    }
+}
+
+/// A required request body of `media_type`, from a `Json<T>` or `Form<T>` extractor.
+pub fn request_body(operation: &mut Operation, media_type: &str, schema: Schema) {
+    let mut content = Map::new();
+    content.insert(
+        media_type.to_string(),
+        MediaType {
+            schema: Some(ObjectOrReference::Object(schema)),
+            ..Default::default()
+        },
+    );
+    operation.request_body = Some(ObjectOrReference::Object(RequestBody {
+        description: None,
+        content,
+        required: Some(true),
+        extensions: Map::new(),
+    }));
+}
+
+/// A response of `status`, with a body of `media_type` when a schema is given.
+pub fn response(operation: &mut Operation, status: &str, description: &str, body: Option<(&str, Schema)>) {
+    let content = body.map(|(media_type, schema)| {
+        let mut content = Map::new();
+        content.insert(
+            media_type.to_string(),
+            MediaType {
+                schema: Some(ObjectOrReference::Object(schema)),
+                ..Default::default()
+            },
+        );
+        content
+    });
+    operation.responses.insert(
+        status.to_string(),
+        Response {
+            description: Some(description.to_string()),
+            content,
+            ..Default::default()
+        },
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::{self, OpenApiSchema};
+
+    #[test]
+    fn test_parameters() {
+        let mut operation = Operation::default();
+        let mut schemas = Schemas::new();
+        path_parameters(
+            &mut operation,
+            "/owners/{owner}/pets/{id}",
+            vec![String::schema_ref(&mut schemas), u64::schema_ref(&mut schemas)],
+        );
+
+        let mut query = schema::object();
+        schema::property(&mut query, "limit", u32::schema_ref(&mut schemas), true);
+        schema::property(&mut query, "q", String::schema_ref(&mut schemas), false);
+        query_parameters(&mut operation, query);
+
+        let parameters: Vec<(String, String, Option<bool>)> = operation
+            .parameters
+            .unwrap()
+            .into_iter()
+            .filter_map(|parameter| match parameter {
+                ObjectOrReference::Object(p) => Some((p.name, p.location, p.required)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            parameters,
+            vec![
+                ("owner".to_string(), "path".to_string(), Some(true)),
+                ("id".to_string(), "path".to_string(), Some(true)),
+                ("limit".to_string(), "query".to_string(), Some(false)),
+                ("q".to_string(), "query".to_string(), Some(true)),
+            ]
+        );
+    }
+}
@@ -7,28 +7,133 @@
 #[macro_use] extern crate log;
 extern crate actix_web;
 
-use std::{fs::File, io::Read, path::Path, result::Result as StdResult};
+use std::{fs, io::{Read, Write}, path::Path, result::Result as StdResult};
+
+use crate::parse_error::ParseError;
 
 pub mod error;
+pub mod builder;
+pub mod bundle;
+pub mod cleanup;
+pub mod client;
+pub mod codegen;
 pub mod components;
+pub mod conversion;
+pub mod diff;
+#[cfg(feature = "docs")]
+pub mod docs;
 pub mod server;
 pub mod external_doc;
+pub mod filter;
+pub mod handler;
+pub mod lint;
+pub mod merge;
+pub mod mock;
+pub mod openapi31;
 pub mod operation;
+pub mod parse_error;
 pub mod path;
+pub mod pointer;
+pub mod sample;
+pub mod schema;
+pub mod serve;
+pub mod service;
+pub mod skeleton;
 pub mod spec;
+pub mod swagger2;
+pub mod validation;
+pub mod version;
+pub mod visit;
 
 pub use error::Error;
+#[cfg(feature = "derive")]
+pub use actix_web_openapi_derive::{openapi_operation, OpenApiSchema};
 
-const MINIMUM_OPENAPI30_VERSION: &str = ">= 3.0";
+const OPENAPI30_VERSION: &str = ">= 3.0.0, < 3.1.0";
+const OPENAPI31_VERSION: &str = ">= 3.1.0, < 3.2.0";
 
 pub type Result<T> = StdResult<T, Error>;
 
+/// The map type of the model. Maps keep the key order of the loaded document, so paths,
+/// properties, responses, components and extensions are written back in the order they were
+/// read. The fields of an object, such as `info` or `paths`, are always written in the order
+/// of the model. The `preserve_order` feature keeps the order of the JSON values held by the
+/// model as well, such as examples and extensions.
+///
+/// The objects of the model keep the fields they do not describe in an `extensions` map,
+/// `x-` extensions as well as fields of the specification the model leaves out, so a loaded
+/// document is written back without losing them. The `unknown-fields` lint rule reports the
+/// ones that are neither.
+pub type Map<K, V> = indexmap::IndexMap<K, V>;
+
+/// The serialization formats an open api spec can be written in.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Format {
+    Json,
+    Yaml,
+}
+
+impl Format {
+    /// The format implied by a `.json`, `.yaml` or `.yml` file extension.
+    pub fn from_path<P>(path: P) -> Option<Format>
+    where
+        P: AsRef<Path>,
+    {
+        let extension = path.as_ref().extension()?.to_str()?.to_lowercase();
+        match extension.as_str() {
+            "json" => Some(Format::Json),
+            "yaml" | "yml" => Some(Format::Yaml),
+            _ => None,
+        }
+    }
+
+    /// Guess the format from the content: JSON documents start with `{`.
+    pub fn detect(bytes: &[u8]) -> Format {
+        let bytes = if bytes.starts_with(b"\xEF\xBB\xBF") { &bytes[3..] } else { bytes };
+        match bytes.iter().find(|b| !b.is_ascii_whitespace()) {
+            Some(b'{') => Format::Json,
+            _ => Format::Yaml,
+        }
+    }
+}
+
+/// Options controlling how a spec is loaded.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct LoadOptions {
+    /// Reject documents whose `openapi` version is not supported instead of loading them
+    /// on a best effort basis.
+    pub strict: bool,
+}
+
 /// deserialize an open api spec from a path
 pub fn from_path<P>(path: P) -> Result<spec::Spec>
 where
     P: AsRef<Path>,
 {
-    from_reader(File::open(path)?)
+    from_path_with(path, LoadOptions::default())
+}
+
+/// deserialize an open api spec from a path using the given options.
+/// The format is taken from the file extension, or detected from the content.
+pub fn from_path_with<P>(path: P, options: LoadOptions) -> Result<spec::Spec>
+where
+    P: AsRef<Path>,
+{
+    from_path_with_report(path, options).map(log_lossy)
+}
+
+/// deserialize an open api spec from a path using the given options, together with what
+/// converting a Swagger 2.0 document lost, rather than logging it.
+pub fn from_path_with_report<P>(path: P, options: LoadOptions) -> Result<conversion::Conversion>
+where
+    P: AsRef<Path>,
+{
+    let bytes = fs::read(&path)?;
+    let format = Format::from_path(&path).unwrap_or_else(|| Format::detect(&bytes));
+    match from_bytes_with_report(&bytes, format, options) {
+        Err(Error::Parse(err)) => Err(Error::Parse(Box::new(err.with_file(path)))),
+        result => result,
+    }
 }
 
 /// deserialize an open api spec from type which implements Read
@@ -36,7 +141,103 @@ pub fn from_reader<R>(read: R) -> Result<spec::Spec>
 where
     R: Read,
 {
-    Ok(serde_yaml::from_reader::<R, spec::Spec>(read)?)
+    from_reader_with(read, LoadOptions::default())
+}
+
+/// deserialize an open api spec from type which implements Read using the given options.
+///
+/// The `openapi` or `swagger` field decides how the document is parsed: OpenApi 3.x
+/// documents are deserialized directly and Swagger 2.0 documents are converted.
+pub fn from_reader_with<R>(mut read: R, options: LoadOptions) -> Result<spec::Spec>
+where
+    R: Read,
+{
+    let mut bytes = Vec::new();
+    read.read_to_end(&mut bytes)?;
+    from_slice_with(&bytes, options)
+}
+
+/// deserialize an open api spec from a JSON or YAML string
+pub fn from_str(s: &str) -> Result<spec::Spec> {
+    from_slice(s.as_bytes())
+}
+
+/// deserialize an open api spec from JSON or YAML bytes
+pub fn from_slice(bytes: &[u8]) -> Result<spec::Spec> {
+    from_slice_with(bytes, LoadOptions::default())
+}
+
+/// deserialize an open api spec from JSON or YAML bytes using the given options
+pub fn from_slice_with(bytes: &[u8], options: LoadOptions) -> Result<spec::Spec> {
+    from_bytes(bytes, Format::detect(bytes), options)
+}
+
+/// deserialize an open api spec from a JSON string
+pub fn from_json(s: &str) -> Result<spec::Spec> {
+    from_bytes(s.as_bytes(), Format::Json, LoadOptions::default())
+}
+
+/// deserialize an open api spec from a `serde_json::Value`
+pub fn from_value(value: serde_json::Value) -> Result<spec::Spec> {
+    from_document(serde_yaml::to_value(value)?, LoadOptions::default())
+}
+
+/// Parse `bytes`, reporting syntax errors and invalid documents as located `ParseError`s.
+fn from_bytes(bytes: &[u8], format: Format, options: LoadOptions) -> Result<spec::Spec> {
+    from_bytes_with_report(bytes, format, options).map(log_lossy)
+}
+
+fn from_bytes_with_report(bytes: &[u8], format: Format, options: LoadOptions) -> Result<conversion::Conversion> {
+    let document = match format {
+        Format::Json => {
+            let value = serde_json::from_slice::<serde_json::Value>(bytes)
+                .map_err(|err| ParseError::from_json_syntax(&err, bytes))?;
+            serde_yaml::to_value(value)?
+        }
+        Format::Yaml => serde_yaml::from_slice::<serde_yaml::Value>(bytes)
+            .map_err(|err| ParseError::from_yaml_syntax(&err, bytes))?,
+    };
+    let swagger = document.get("swagger").is_some();
+    match from_document_with_report(document, options) {
+        Err(Error::Yaml(err)) if swagger => {
+            Err(ParseError::locate::<swagger2::Swagger>(bytes, format, &err.to_string()).into())
+        }
+        Err(Error::Yaml(err)) => Err(ParseError::locate::<spec::Spec>(bytes, format, &err.to_string()).into()),
+        result => result,
+    }
+}
+
+fn from_document(document: serde_yaml::Value, options: LoadOptions) -> Result<spec::Spec> {
+    from_document_with_report(document, options).map(log_lossy)
+}
+
+fn from_document_with_report(mut document: serde_yaml::Value, options: LoadOptions) -> Result<conversion::Conversion> {
+    version::quote(&mut document);
+    match version::detect(&document)? {
+        version::SpecVersion::Swagger(ref version) if version.major == 2 && version.minor == 0 => {
+            let swagger = serde_yaml::from_value::<swagger2::Swagger>(document)?;
+            Ok(swagger.convert())
+        }
+        version::SpecVersion::OpenApi(ref version) if version.major == 3 => {
+            let spec = serde_yaml::from_value::<spec::Spec>(document)?;
+            match spec.validate_version() {
+                Err(err) if options.strict => return Err(err),
+                Err(err) => warn!("Loading on a best effort basis: {}", err),
+                Ok(_) => {}
+            }
+            Ok(conversion::Conversion { spec, lossy: Vec::new() })
+        }
+        version::SpecVersion::Swagger(version) | version::SpecVersion::OpenApi(version) => {
+            Err(Error::UnsupportedSpecFileVersion(version))
+        }
+    }
+}
+
+fn log_lossy(conversion: conversion::Conversion) -> spec::Spec {
+    for lossy in conversion.lossy.iter() {
+        warn!("Swagger 2.0 conversion at {}: {}", lossy.path, lossy.message);
+    }
+    conversion.spec
 }
 
 /// serialize to a yaml string
@@ -49,9 +250,26 @@ pub fn to_json(spec: &spec::Spec) -> Result<String> {
     Ok(serde_json::to_string_pretty(spec)?)
 }
 
+/// serialize as yaml to a type which implements Write
+pub fn to_writer_yaml<W>(writer: W, spec: &spec::Spec) -> Result<()>
+where
+    W: Write,
+{
+    Ok(serde_yaml::to_writer(writer, spec)?)
+}
+
+/// serialize as json to a type which implements Write
+pub fn to_writer_json<W>(writer: W, spec: &spec::Spec) -> Result<()>
+where
+    W: Write,
+{
+    Ok(serde_json::to_writer_pretty(writer, spec)?)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::components::ObjectOrReference;
     use pretty_assertions::assert_eq;
     use std::{
         fs::{self, read_to_string, File},
@@ -77,7 +295,21 @@ mod tests {
     fn convert_yaml_str_to_json(yaml_str: &str) -> String {
         let yaml: serde_yaml::Value = serde_yaml::from_str(yaml_str).unwrap();
         let json: serde_json::Value = serde_yaml::from_value(yaml).unwrap();
-        serde_json::to_string_pretty(&json).unwrap()
+        serde_json::to_string_pretty(&sort_keys(json)).unwrap()
+    }
+
+    /// Sort the keys of the objects in `value`. Only needed with `preserve_order`, where the
+    /// fields of the model's objects are written in their own order rather than the file's.
+    fn sort_keys(value: serde_json::Value) -> serde_json::Value {
+        match value {
+            serde_json::Value::Object(object) => {
+                let mut entries: Vec<_> = object.into_iter().collect();
+                entries.sort_by(|a, b| a.0.cmp(&b.0));
+                serde_json::Value::Object(entries.into_iter().map(|(key, value)| (key, sort_keys(value))).collect())
+            }
+            serde_json::Value::Array(items) => serde_json::Value::Array(items.into_iter().map(sort_keys).collect()),
+            value => value,
+        }
     }
 
     /// Deserialize and re-serialize the input file to a JSON string through two different
@@ -110,7 +342,7 @@ mod tests {
         // Parse the input file
         let parsed_spec = from_path(&input_file).unwrap();
         // Convert to serde_json::Value
-        let parsed_spec_json = serde_json::to_value(parsed_spec).unwrap();
+        let parsed_spec_json = sort_keys(serde_json::to_value(parsed_spec).unwrap());
         // Convert to a JSON string
         let parsed_spec_json_str: String = serde_json::to_string_pretty(&parsed_spec_json).unwrap();
 
@@ -145,6 +377,252 @@ mod tests {
         }
     }
 
+    #[test]
+    fn can_load_json_and_yaml() {
+        let yaml = read_to_string("data/v3.0/petstore.yaml").unwrap();
+        let spec = from_str(&yaml).unwrap();
+
+        let mut json = Vec::new();
+        to_writer_json(&mut json, &spec).unwrap();
+        assert_eq!(Format::detect(&json), Format::Json);
+        assert_eq!(from_slice(&json).unwrap(), spec);
+        assert_eq!(from_json(&to_json(&spec).unwrap()).unwrap(), spec);
+        assert_eq!(from_value(serde_json::to_value(&spec).unwrap()).unwrap(), spec);
+
+        let mut yaml = Vec::new();
+        to_writer_yaml(&mut yaml, &spec).unwrap();
+        assert_eq!(Format::detect(&yaml), Format::Yaml);
+        assert_eq!(from_slice(&yaml).unwrap(), spec);
+
+        assert_eq!(Format::from_path("spec.JSON"), Some(Format::Json));
+        assert_eq!(Format::from_path("spec.yml"), Some(Format::Yaml));
+        assert_eq!(Format::from_path("spec"), None);
+    }
+
+    #[test]
+    fn reports_parse_error_locations() {
+        let dir = std::env::temp_dir().join("openapi-parse-error");
+        write_to_file(
+            &dir,
+            "broken.yaml",
+            "openapi: 3.0.0\ninfo:\n  title: Broken\n  version: 1.0.0\npaths:\n  /pets: [1]\n",
+        );
+        let path = dir.join("broken.yaml");
+        match from_path(&path) {
+            Err(Error::Parse(err)) => {
+                assert_eq!(err.file, Some(path.clone()));
+                assert_eq!(err.line, Some(6));
+                assert_eq!(err.pointer, "/paths/~1pets");
+            }
+            other => panic!("expected a parse error, got {:?}", other),
+        }
+
+        match from_str("{\"openapi\": \"3.0.0\",\n  \"info\": }") {
+            Err(Error::Parse(err)) => assert_eq!(err.line, Some(2)),
+            other => panic!("expected a parse error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn reports_nested_parse_error_locations() {
+        let parameter = "openapi: 3.0.0\ninfo: {title: Broken, version: 1.0.0}\npaths:\n  /pets:\n    get:\n      parameters:\n        - name: limit\n          in: query\n          required: [1]\n      responses: {}\n";
+        match from_str(parameter) {
+            Err(Error::Parse(err)) => {
+                assert_eq!(err.pointer, "/paths/~1pets/get/parameters/0/required");
+                assert_eq!(err.line, Some(9));
+            }
+            other => panic!("expected a parse error, got {:?}", other),
+        }
+
+        let schema = "openapi: 3.0.0\ninfo: {title: Broken, version: 1.0.0}\npaths: {}\ncomponents:\n  schemas:\n    Pet:\n      type: string\n      minLength: abc\n";
+        match from_str(schema) {
+            Err(Error::Parse(err)) => {
+                assert_eq!(err.pointer, "/components/schemas/Pet/minLength");
+                assert_eq!(err.line, Some(8));
+            }
+            other => panic!("expected a parse error, got {:?}", other),
+        }
+    }
+
+    const EXTENDED: &str = r##"openapi: 3.0.0
+x-generator: hand written
+info:
+  title: Extended
+  version: 1.0.0
+  x-audience: internal
+  contact: {name: Zoo, x-team: keepers}
+  license: {name: MIT, x-spdx: MIT}
+servers:
+  - url: "https://{host}/v1"
+    x-region: eu
+    variables:
+      host: {default: zoo.example.com, x-env: prod}
+tags:
+  - name: zebras
+    x-display-name: Zebras
+    externalDocs: {url: "https://zoo.example.com/zebras"}
+paths:
+  /zebras:
+    get:
+      x-rate-limit: 10
+      responses:
+        "200":
+          description: ok
+          headers:
+            X-Count: {schema: {type: integer}, x-unit: zebras}
+          content:
+            application/json:
+              x-sample: true
+              example: [stripes]
+        "404":
+          $ref: "#/components/responses/NotFound"
+    post:
+      requestBody:
+        x-body: form
+        content:
+          multipart/form-data:
+            encoding:
+              photo: {contentType: image/png, x-max-size: 1024}
+      responses:
+        "201":
+          description: created
+  /apes:
+    get:
+      responses:
+        "200":
+          description: ok
+components:
+  responses:
+    NotFound:
+      description: not found
+    Missing:
+      $ref: "#/components/responses/NotFound"
+  examples:
+    Zebra: {value: stripes, x-source: photo}
+  securitySchemes:
+    api_key: {type: apiKey, name: key, in: header, x-rotation: daily}
+"##;
+
+    #[test]
+    fn keeps_unknown_fields() {
+        let spec = from_str(EXTENDED).unwrap();
+        assert_eq!(spec.extensions["x-generator"], "hand written");
+        assert_eq!(spec.info.extensions["x-audience"], "internal");
+        assert_eq!(spec.info.contact.as_ref().unwrap().extensions["x-team"], "keepers");
+        assert_eq!(spec.info.license.as_ref().unwrap().extensions["x-spdx"], "MIT");
+        let server = &spec.servers.as_ref().unwrap()[0];
+        assert_eq!(server.extensions["x-region"], "eu");
+        assert_eq!(server.variables.as_ref().unwrap()["host"].extensions["x-env"], "prod");
+        let tag = &spec.tags.as_ref().unwrap()[0];
+        assert_eq!(tag.extensions["x-display-name"], "Zebras");
+        assert!(tag.extensions.contains_key("externalDocs"));
+
+        let get = spec.paths["/zebras"].get.as_ref().unwrap();
+        assert_eq!(get.extensions["x-rate-limit"], 10);
+        let response = &get.responses["200"];
+        match response.headers.as_ref().unwrap()["X-Count"] {
+            ObjectOrReference::Object(ref header) => assert_eq!(header.extensions["x-unit"], "zebras"),
+            ref other => panic!("expected an inline header, got {:?}", other),
+        }
+        let media_type = &response.content.as_ref().unwrap()["application/json"];
+        assert_eq!(media_type.extensions.keys().collect::<Vec<_>>(), vec!["x-sample"]);
+
+        let request_body = match get_request_body(&spec) {
+            ObjectOrReference::Object(request_body) => request_body,
+            other => panic!("expected an inline request body, got {:?}", other),
+        };
+        assert_eq!(request_body.extensions["x-body"], "form");
+        let encoding = &request_body.content["multipart/form-data"].encoding.as_ref().unwrap()["photo"];
+        assert_eq!(encoding.extensions["x-max-size"], 1024);
+
+        let components = spec.components.as_ref().unwrap();
+        match components.examples.as_ref().unwrap()["Zebra"] {
+            ObjectOrReference::Object(ref example) => assert_eq!(example.extensions["x-source"], "photo"),
+            ref other => panic!("expected an inline example, got {:?}", other),
+        }
+        match components.security_schemes.as_ref().unwrap()["api_key"] {
+            ObjectOrReference::Object(spec::SecurityScheme::ApiKey { ref extensions, .. }) => {
+                assert_eq!(extensions["x-rotation"], "daily")
+            }
+            ref other => panic!("expected an api key scheme, got {:?}", other),
+        }
+
+        let yaml = to_yaml(&spec).unwrap();
+        assert!(yaml.contains("x-rate-limit: 10"));
+        assert_eq!(yaml.matches("example:").count(), 1);
+        assert_eq!(from_str(&yaml).unwrap(), spec);
+    }
+
+    fn get_request_body(spec: &spec::Spec) -> &ObjectOrReference<spec::RequestBody> {
+        spec.paths["/zebras"].post.as_ref().unwrap().request_body.as_ref().unwrap()
+    }
+
+    #[test]
+    fn reads_referenced_responses() {
+        let spec = from_str(EXTENDED).unwrap();
+        let responses = spec.components.as_ref().and_then(|c| c.responses.as_ref());
+        let missing = &responses.unwrap()["Missing"];
+        assert_eq!(
+            *missing,
+            ObjectOrReference::Ref {
+                ref_path: "#/components/responses/NotFound".to_string()
+            }
+        );
+        assert_eq!(missing.resolve(responses).unwrap().description, Some("not found".to_string()));
+    }
+
+    #[test]
+    fn keeps_key_order() {
+        let spec = from_str(EXTENDED).unwrap();
+        assert_eq!(spec.paths.keys().collect::<Vec<_>>(), vec!["/zebras", "/apes"]);
+
+        let yaml = to_yaml(&spec).unwrap();
+        assert!(yaml.find("/zebras").unwrap() < yaml.find("/apes").unwrap());
+    }
+
+    #[test]
+    fn can_convert_swagger2() {
+        let spec = from_path("data/v2.0/petstore.yaml").unwrap();
+        assert_eq!(spec.openapi, "3.0.1");
+        assert_eq!(spec.paths.len(), 2);
+    }
+
+    #[test]
+    fn rejects_unsupported_versions() {
+        match from_reader("swagger: '1.2'\ninfo: {title: t, version: v}".as_bytes()) {
+            Err(Error::UnsupportedSpecFileVersion(version)) => assert_eq!(version.to_string(), "1.2.0"),
+            other => panic!("unexpected result {:?}", other),
+        }
+
+        let future = "openapi: 3.2.0\ninfo: {title: t, version: v}\npaths: {}";
+        assert!(from_reader(future.as_bytes()).is_ok());
+        match from_reader_with(future.as_bytes(), LoadOptions { strict: true }) {
+            Err(Error::UnsupportedSpecFileVersion(_)) => {}
+            other => panic!("unexpected result {:?}", other),
+        }
+    }
+
+    #[test]
+    fn reads_unquoted_versions() {
+        let spec = from_str("openapi: 3.0\ninfo: {title: t, version: v}\npaths: {}").unwrap();
+        assert_eq!(spec.openapi, "3.0");
+
+        let swagger = read_to_string("data/v2.0/petstore.yaml").unwrap().replace("swagger: \"2.0\"", "swagger: 2.0");
+        assert!(swagger.starts_with("swagger: 2.0\n"));
+        assert_eq!(from_str(&swagger).unwrap().paths.len(), 2);
+    }
+
+    #[test]
+    fn examples_match_schemas() {
+        for entry in fs::read_dir("data/v3.0").unwrap() {
+            let path = entry.unwrap().path();
+            println!("Testing if the examples of {:?} match their schemas", path);
+            let spec = from_path(&path).unwrap();
+            let errors = validation::validate_examples(&spec);
+            assert!(errors.is_empty(), "{:?}: {:?}", path, errors);
+        }
+    }
+
     #[test]
     fn can_deserialize_and_reserialize_v3() {
         let save_path_base: std::path::PathBuf =
@@ -0,0 +1,912 @@
+//! Check a spec against style rules, beyond what makes it valid.
+//!
+//! ```ignore
+//! let config = Config::from_path("openapi-lint.yaml")?;
+//! let problems = Linter::new().configure(&config)?.lint(&spec);
+//! print!("{}", to_text(&problems));
+//! ```
+//!
+//! A configuration sets the severity of rules by name, and the options of those that have
+//! some, in YAML or TOML:
+//!
+//! ```yaml
+//! rules:
+//!   operation-id-case:
+//!     severity: error
+//!     case: snake
+//!   schema-description: off
+//! ```
+//!
+//! The built-in rules are:
+//!
+//! - `operation-id-case`: operationIds follow a `case` of `camel` (the default), `pascal`,
+//!   `snake` or `kebab`.
+//! - `operation-summary`: operations have a summary.
+//! - `operation-tags`: operations have tags.
+//! - `tags-declared`: the tags of operations are declared in the root `tags`.
+//! - `path-kebab-case`: path segments other than templates are kebab-case.
+//! - `no-trailing-slash`: paths do not end with a slash.
+//! - `error-responses`: operations document a `4xx` or `5xx` response, or a default one.
+//! - `schema-description`: component schemas have a description.
+//! - `no-inline-response-schema`: response bodies refer to component schemas rather than
+//!   describing objects inline.
+//! - `unknown-fields`: objects have no fields other than `x-` extensions and those of the
+//!   specification, e.g. misspelled ones.
+//!
+//! Other rules implement `Rule` and are added with `Linter::rule`.
+
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use actix_web::http::Method;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::components::ObjectOrReference;
+use crate::operation::Operation;
+use crate::path::{Parameter, PathItem};
+use crate::pointer;
+use crate::server::Server;
+use crate::spec::{Header, MediaType, RequestBody, Response, Schema, SecurityScheme, Spec};
+use crate::visit::{
+    walk_header, walk_media_type, walk_operation, walk_parameter, walk_path_item, walk_request_body, walk_response,
+    walk_schema, walk_spec, Visit,
+};
+use crate::{Error, Map, Result};
+
+/// How much a problem matters, `Off` disabling a rule.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Off,
+    Info,
+    Warning,
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Severity::Off => "off",
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+        f.write_str(name)
+    }
+}
+
+/// A node of the spec breaking a rule.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Problem {
+    /// The name of the rule.
+    pub rule: String,
+    pub severity: Severity,
+    /// JSON pointer of the node within the spec.
+    pub pointer: String,
+    pub message: String,
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}: {} ({})", self.severity, self.pointer, self.message, self.rule)
+    }
+}
+
+/// A style rule.
+pub trait Rule {
+    /// The name the rule is configured by, e.g. `operation-summary`.
+    fn name(&self) -> &str;
+
+    /// The severity of the rule unless configured.
+    fn severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    /// Apply the options of the rule from a configuration, failing on those it does not know.
+    fn configure(&mut self, options: &Map<String, Value>) -> Result<()> {
+        match options.keys().next() {
+            Some(option) => Err(Error::LintConfig(format!("{} has no option {}", self.name(), option))),
+            None => Ok(()),
+        }
+    }
+
+    /// The pointers of the nodes of `spec` breaking the rule, with what is wrong with each.
+    fn check(&self, spec: &Spec) -> Vec<(String, String)>;
+}
+
+/// The configuration of the rules of a `Linter`, by name.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+pub struct Config {
+    #[serde(default)]
+    pub rules: Map<String, RuleConfig>,
+}
+
+/// The configuration of a rule, its severity alone or with options.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(untagged)]
+pub enum RuleConfig {
+    Severity(Severity),
+    Options {
+        severity: Option<Severity>,
+        #[serde(flatten)]
+        options: Map<String, Value>,
+    },
+}
+
+impl Config {
+    pub fn from_yaml(yaml: &str) -> Result<Config> {
+        Ok(serde_yaml::from_str(yaml)?)
+    }
+
+    pub fn from_toml(toml: &str) -> Result<Config> {
+        Ok(toml::from_str(toml)?)
+    }
+
+    /// Read a configuration, in TOML if the file name ends with `.toml` and YAML otherwise.
+    pub fn from_path<P>(path: P) -> Result<Config>
+    where
+        P: AsRef<Path>,
+    {
+        let contents = fs::read_to_string(path.as_ref())?;
+        match path.as_ref().extension().and_then(|extension| extension.to_str()) {
+            Some("toml") => Config::from_toml(&contents),
+            _ => Config::from_yaml(&contents),
+        }
+    }
+}
+
+/// Checks specs against a set of rules.
+pub struct Linter {
+    rules: Vec<(Box<dyn Rule>, Severity)>,
+}
+
+impl Default for Linter {
+    fn default() -> Linter {
+        let rules: Vec<Box<dyn Rule>> = vec![
+            Box::new(OperationIdCase { case: Case::Camel }),
+            Box::new(OperationSummary),
+            Box::new(OperationTags),
+            Box::new(TagsDeclared),
+            Box::new(PathKebabCase),
+            Box::new(NoTrailingSlash),
+            Box::new(ErrorResponses),
+            Box::new(SchemaDescription),
+            Box::new(NoInlineResponseSchema),
+            Box::new(UnknownFields),
+        ];
+        Linter {
+            rules: rules
+                .into_iter()
+                .map(|rule| {
+                    let severity = rule.severity();
+                    (rule, severity)
+                })
+                .collect(),
+        }
+    }
+}
+
+impl Linter {
+    /// A linter with the built-in rules.
+    pub fn new() -> Linter {
+        Linter::default()
+    }
+
+    /// A linter without rules.
+    pub fn empty() -> Linter {
+        Linter { rules: Vec::new() }
+    }
+
+    /// Add a rule, at its own severity.
+    pub fn rule<R>(mut self, rule: R) -> Linter
+    where
+        R: Rule + 'static,
+    {
+        let severity = rule.severity();
+        self.rules.push((Box::new(rule), severity));
+        self
+    }
+
+    /// Apply a configuration, failing on rules and options that do not exist.
+    pub fn configure(mut self, config: &Config) -> Result<Linter> {
+        for (name, rule_config) in config.rules.iter() {
+            let (rule, severity) = match self.rules.iter_mut().find(|(rule, _)| rule.name() == name) {
+                Some((rule, severity)) => (rule, severity),
+                None => return Err(Error::LintConfig(format!("there is no rule {}", name))),
+            };
+            match rule_config {
+                RuleConfig::Severity(configured) => *severity = *configured,
+                RuleConfig::Options {
+                    severity: configured,
+                    options,
+                } => {
+                    rule.configure(options)?;
+                    if let Some(configured) = configured {
+                        *severity = *configured;
+                    }
+                }
+            }
+        }
+        Ok(self)
+    }
+
+    /// The problems of `spec`, rule by rule.
+    pub fn lint(&self, spec: &Spec) -> Vec<Problem> {
+        let mut problems = Vec::new();
+        for (rule, severity) in self.rules.iter().filter(|(_, severity)| *severity != Severity::Off) {
+            for (pointer, message) in rule.check(spec) {
+                problems.push(Problem {
+                    rule: rule.name().to_string(),
+                    severity: *severity,
+                    pointer,
+                    message,
+                });
+            }
+        }
+        problems
+    }
+}
+
+/// Whether any of `problems` is an error, e.g. to fail a build.
+pub fn has_errors(problems: &[Problem]) -> bool {
+    problems.iter().any(|problem| problem.severity == Severity::Error)
+}
+
+/// The problems one per line, followed by their count by severity.
+pub fn to_text(problems: &[Problem]) -> String {
+    let mut text: String = problems.iter().map(|problem| format!("{}\n", problem)).collect();
+    let count = |severity| problems.iter().filter(|problem| problem.severity == severity).count();
+    text.push_str(&format!(
+        "{} errors, {} warnings, {} infos\n",
+        count(Severity::Error),
+        count(Severity::Warning),
+        count(Severity::Info)
+    ));
+    text
+}
+
+/// The problems as a JSON array.
+pub fn to_json(problems: &[Problem]) -> Result<String> {
+    Ok(serde_json::to_string_pretty(problems)?)
+}
+
+/// Calls `check` with the path, method and pointer of each operation of a spec.
+struct Operations<F> {
+    path: String,
+    check: F,
+}
+
+impl<F> Visit for Operations<F>
+where
+    F: FnMut(&str, &Method, &Operation, &str),
+{
+    fn visit_path_item(&mut self, path: &str, item: &PathItem, pointer: &str) {
+        self.path = path.to_string();
+        walk_path_item(self, path, item, pointer);
+    }
+
+    fn visit_operation(&mut self, method: &Method, operation: &Operation, pointer: &str) {
+        (self.check)(&self.path, method, operation, pointer);
+    }
+}
+
+fn operations<F>(spec: &Spec, check: F)
+where
+    F: FnMut(&str, &Method, &Operation, &str),
+{
+    Operations {
+        path: String::new(),
+        check,
+    }
+    .visit_spec(spec, "");
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Case {
+    Camel,
+    Pascal,
+    Snake,
+    Kebab,
+}
+
+impl Case {
+    fn name(self) -> &'static str {
+        match self {
+            Case::Camel => "camel",
+            Case::Pascal => "pascal",
+            Case::Snake => "snake",
+            Case::Kebab => "kebab",
+        }
+    }
+
+    fn matches(self, name: &str) -> bool {
+        let first = match name.chars().next() {
+            Some(first) => first,
+            None => return false,
+        };
+        let lower = |ch: char| ch.is_ascii_lowercase() || ch.is_ascii_digit();
+        match self {
+            Case::Camel => first.is_ascii_lowercase() && name.chars().all(|ch| ch.is_ascii_alphanumeric()),
+            Case::Pascal => first.is_ascii_uppercase() && name.chars().all(|ch| ch.is_ascii_alphanumeric()),
+            Case::Snake => first.is_ascii_lowercase() && name.chars().all(|ch| lower(ch) || ch == '_'),
+            Case::Kebab => first.is_ascii_lowercase() && name.chars().all(|ch| lower(ch) || ch == '-'),
+        }
+    }
+}
+
+struct OperationIdCase {
+    case: Case,
+}
+
+impl Rule for OperationIdCase {
+    fn name(&self) -> &str {
+        "operation-id-case"
+    }
+
+    fn configure(&mut self, options: &Map<String, Value>) -> Result<()> {
+        for (option, value) in options.iter() {
+            let cases = [Case::Camel, Case::Pascal, Case::Snake, Case::Kebab];
+            let case = cases.iter().find(|case| Some(case.name()) == value.as_str());
+            self.case = match (option.as_str(), case) {
+                ("case", Some(case)) => *case,
+                _ => {
+                    let message = format!("{} has no option {} = {}", self.name(), option, value);
+                    return Err(Error::LintConfig(message));
+                }
+            };
+        }
+        Ok(())
+    }
+
+    fn check(&self, spec: &Spec) -> Vec<(String, String)> {
+        let mut found = Vec::new();
+        operations(spec, |_, _, operation, pointer| {
+            if let Some(ref operation_id) = operation.operation_id {
+                if !self.case.matches(operation_id) {
+                    let message = format!("operationId {} is not {} case", operation_id, self.case.name());
+                    found.push((pointer::push(pointer, "operationId"), message));
+                }
+            }
+        });
+        found
+    }
+}
+
+struct OperationSummary;
+
+impl Rule for OperationSummary {
+    fn name(&self) -> &str {
+        "operation-summary"
+    }
+
+    fn check(&self, spec: &Spec) -> Vec<(String, String)> {
+        let mut found = Vec::new();
+        operations(spec, |_, _, operation, pointer| {
+            if operation.summary.as_ref().filter(|summary| !summary.trim().is_empty()).is_none() {
+                found.push((pointer.to_string(), "the operation has no summary".to_string()));
+            }
+        });
+        found
+    }
+}
+
+struct OperationTags;
+
+impl Rule for OperationTags {
+    fn name(&self) -> &str {
+        "operation-tags"
+    }
+
+    fn check(&self, spec: &Spec) -> Vec<(String, String)> {
+        let mut found = Vec::new();
+        operations(spec, |_, _, operation, pointer| {
+            if operation.tags.as_ref().filter(|tags| !tags.is_empty()).is_none() {
+                found.push((pointer.to_string(), "the operation has no tags".to_string()));
+            }
+        });
+        found
+    }
+}
+
+struct TagsDeclared;
+
+impl Rule for TagsDeclared {
+    fn name(&self) -> &str {
+        "tags-declared"
+    }
+
+    fn check(&self, spec: &Spec) -> Vec<(String, String)> {
+        let declared: Vec<&String> = spec.tags.iter().flatten().map(|tag| &tag.name).collect();
+        let mut found = Vec::new();
+        operations(spec, |_, _, operation, pointer| {
+            let tags = pointer::push(pointer, "tags");
+            for (i, tag) in operation.tags.iter().flatten().enumerate() {
+                if !declared.contains(&tag) {
+                    let message = format!("the tag {} is not declared in the root tags", tag);
+                    found.push((pointer::push(&tags, &i.to_string()), message));
+                }
+            }
+        });
+        found
+    }
+}
+
+struct PathKebabCase;
+
+impl Rule for PathKebabCase {
+    fn name(&self) -> &str {
+        "path-kebab-case"
+    }
+
+    fn check(&self, spec: &Spec) -> Vec<(String, String)> {
+        let mut found = Vec::new();
+        for path in spec.paths.keys() {
+            let segments = path.split('/').filter(|segment| !segment.is_empty() && !segment.starts_with('{'));
+            for segment in segments {
+                if !Case::Kebab.matches(segment) {
+                    let message = format!("the path segment {} is not kebab case", segment);
+                    found.push((pointer::push("/paths", path), message));
+                }
+            }
+        }
+        found
+    }
+}
+
+struct NoTrailingSlash;
+
+impl Rule for NoTrailingSlash {
+    fn name(&self) -> &str {
+        "no-trailing-slash"
+    }
+
+    fn check(&self, spec: &Spec) -> Vec<(String, String)> {
+        spec.paths
+            .keys()
+            .filter(|path| path.len() > 1 && path.ends_with('/'))
+            .map(|path| (pointer::push("/paths", path), "the path ends with a slash".to_string()))
+            .collect()
+    }
+}
+
+struct ErrorResponses;
+
+impl Rule for ErrorResponses {
+    fn name(&self) -> &str {
+        "error-responses"
+    }
+
+    fn check(&self, spec: &Spec) -> Vec<(String, String)> {
+        let mut found = Vec::new();
+        operations(spec, |_, _, operation, pointer| {
+            let documented = operation
+                .responses
+                .keys()
+                .any(|status| status == "default" || status.starts_with('4') || status.starts_with('5'));
+            if !documented {
+                let message = "the operation documents no error response".to_string();
+                found.push((pointer::push(pointer, "responses"), message));
+            }
+        });
+        found
+    }
+}
+
+struct SchemaDescription;
+
+impl Rule for SchemaDescription {
+    fn name(&self) -> &str {
+        "schema-description"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Info
+    }
+
+    fn check(&self, spec: &Spec) -> Vec<(String, String)> {
+        let schemas = spec.components.iter().flat_map(|components| components.schemas.iter().flatten());
+        schemas
+            .filter(|(_, schema)| match schema {
+                ObjectOrReference::Object(schema) => schema.ref_path.is_none() && schema.description.is_none(),
+                ObjectOrReference::Ref { .. } => false,
+            })
+            .map(|(name, _)| {
+                let pointer = pointer::push("/components/schemas", name);
+                (pointer, format!("the schema {} has no description", name))
+            })
+            .collect()
+    }
+}
+
+struct NoInlineResponseSchema;
+
+/// Collects the response bodies describing objects inline.
+#[derive(Default)]
+struct InlineResponseSchemas(Vec<(String, String)>);
+
+impl Visit for InlineResponseSchemas {
+    fn visit_response(&mut self, response: &Response, pointer: &str) {
+        let content = pointer::push(pointer, "content");
+        for (name, media_type) in response.content.iter().flatten() {
+            let mut schema = match media_type.schema {
+                Some(ObjectOrReference::Object(ref schema)) => schema,
+                _ => continue,
+            };
+            let mut pointer = pointer::push(&pointer::push(&content, name), "schema");
+            if let Some(ref items) = schema.items {
+                schema = items;
+                pointer = pointer::push(&pointer, "items");
+            }
+            if is_inline_object(schema) {
+                self.0.push((pointer, "the response describes an object inline".to_string()));
+            }
+        }
+        walk_response(self, response, pointer);
+    }
+}
+
+fn is_inline_object(schema: &Schema) -> bool {
+    schema.ref_path.is_none() && (schema.properties.is_some() || schema.all_of.is_some() || schema.one_of.is_some())
+}
+
+impl Rule for NoInlineResponseSchema {
+    fn name(&self) -> &str {
+        "no-inline-response-schema"
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Info
+    }
+
+    fn check(&self, spec: &Spec) -> Vec<(String, String)> {
+        let mut found = InlineResponseSchemas::default();
+        found.visit_spec(spec, "");
+        found
+            .0
+            .into_iter()
+            .filter(|(pointer, _)| !pointer.starts_with("/components/responses/"))
+            .collect()
+    }
+}
+
+struct UnknownFields;
+
+/// The fields of the specification the model keeps in `extensions`, by object.
+const PARAMETER_FIELDS: &[&str] = &["deprecated", "allowEmptyValue", "allowReserved", "content"];
+const HEADER_FIELDS: &[&str] = &[
+    "deprecated",
+    "allowEmptyValue",
+    "style",
+    "explode",
+    "allowReserved",
+    "example",
+    "examples",
+    "content",
+];
+const RESPONSE_FIELDS: &[&str] = &["$ref"];
+const TAG_FIELDS: &[&str] = &["externalDocs"];
+const SECURITY_SCHEME_FIELDS: &[&str] = &["description"];
+const SCHEMA_FIELDS: &[&str] = &[
+    "anyOf",
+    "not",
+    "writeOnly",
+    "deprecated",
+    "xml",
+    "externalDocs",
+    "maxProperties",
+    "minProperties",
+    "patternProperties",
+    "propertyNames",
+    "unevaluatedProperties",
+    "dependentSchemas",
+    "dependentRequired",
+    "prefixItems",
+    "contains",
+    "minContains",
+    "maxContains",
+    "unevaluatedItems",
+    "if",
+    "then",
+    "else",
+    "contentEncoding",
+    "contentMediaType",
+    "contentSchema",
+    "$schema",
+    "$id",
+    "$anchor",
+    "$dynamicRef",
+    "$dynamicAnchor",
+    "$defs",
+    "$comment",
+];
+
+/// Collects the fields of objects that are neither `x-` extensions nor known.
+#[derive(Default)]
+struct UnknownFieldsFound(Vec<(String, String)>);
+
+impl UnknownFieldsFound {
+    fn check(&mut self, extensions: &Map<String, Value>, known: &[&str], pointer: &str) {
+        for name in extensions.keys() {
+            if !name.starts_with("x-") && !known.contains(&name.as_str()) {
+                let message = format!("{} is not a field of the specification nor an x- extension", name);
+                self.0.push((pointer::push(pointer, name), message));
+            }
+        }
+    }
+
+    fn check_servers(&mut self, servers: &[Server], pointer: &str) {
+        for (i, server) in servers.iter().enumerate() {
+            let pointer = pointer::push(&pointer::push(pointer, "servers"), &i.to_string());
+            self.check(&server.extensions, &[], &pointer);
+            let variables = pointer::push(&pointer, "variables");
+            for (name, variable) in server.variables.iter().flatten() {
+                self.check(&variable.extensions, &[], &pointer::push(&variables, name));
+            }
+        }
+    }
+}
+
+impl Visit for UnknownFieldsFound {
+    fn visit_spec(&mut self, spec: &Spec, pointer: &str) {
+        self.check(&spec.extensions, &[], pointer);
+        let info = pointer::push(pointer, "info");
+        self.check(&spec.info.extensions, &[], &info);
+        if let Some(ref contact) = spec.info.contact {
+            self.check(&contact.extensions, &[], &pointer::push(&info, "contact"));
+        }
+        if let Some(ref license) = spec.info.license {
+            self.check(&license.extensions, &[], &pointer::push(&info, "license"));
+        }
+        self.check_servers(spec.servers.as_ref().map_or(&[], Vec::as_slice), pointer);
+        let tags = pointer::push(pointer, "tags");
+        for (i, tag) in spec.tags.iter().flatten().enumerate() {
+            self.check(&tag.extensions, TAG_FIELDS, &pointer::push(&tags, &i.to_string()));
+        }
+        if let Some(ref components) = spec.components {
+            let pointer = pointer::push(pointer, "components");
+            self.check(&components.extensions, &[], &pointer);
+            let examples = pointer::push(&pointer, "examples");
+            for (name, example) in components.examples.iter().flatten() {
+                if let ObjectOrReference::Object(example) = example {
+                    self.check(&example.extensions, &[], &pointer::push(&examples, name));
+                }
+            }
+            let schemes = pointer::push(&pointer, "securitySchemes");
+            for (name, scheme) in components.security_schemes.iter().flatten() {
+                let extensions = match scheme {
+                    ObjectOrReference::Object(SecurityScheme::ApiKey { extensions, .. })
+                    | ObjectOrReference::Object(SecurityScheme::Http { extensions, .. })
+                    | ObjectOrReference::Object(SecurityScheme::OAuth2 { extensions, .. })
+                    | ObjectOrReference::Object(SecurityScheme::OpenIdConnect { extensions, .. }) => extensions,
+                    ObjectOrReference::Ref { .. } => continue,
+                };
+                self.check(extensions, SECURITY_SCHEME_FIELDS, &pointer::push(&schemes, name));
+            }
+        }
+        walk_spec(self, spec, pointer);
+    }
+
+    fn visit_path_item(&mut self, path: &str, item: &PathItem, pointer: &str) {
+        self.check(&item.extensions, &[], pointer);
+        self.check_servers(item.servers.as_ref().map_or(&[], Vec::as_slice), pointer);
+        walk_path_item(self, path, item, pointer);
+    }
+
+    fn visit_operation(&mut self, method: &Method, operation: &Operation, pointer: &str) {
+        self.check(&operation.extensions, &[], pointer);
+        self.check_servers(operation.servers.as_ref().map_or(&[], Vec::as_slice), pointer);
+        walk_operation(self, method, operation, pointer);
+    }
+
+    fn visit_parameter(&mut self, parameter: &Parameter, pointer: &str) {
+        self.check(&parameter.extensions, PARAMETER_FIELDS, pointer);
+        walk_parameter(self, parameter, pointer);
+    }
+
+    fn visit_request_body(&mut self, body: &RequestBody, pointer: &str) {
+        self.check(&body.extensions, &[], pointer);
+        walk_request_body(self, body, pointer);
+    }
+
+    fn visit_response(&mut self, response: &Response, pointer: &str) {
+        self.check(&response.extensions, RESPONSE_FIELDS, pointer);
+        walk_response(self, response, pointer);
+    }
+
+    fn visit_header(&mut self, header: &Header, pointer: &str) {
+        self.check(&header.extensions, HEADER_FIELDS, pointer);
+        walk_header(self, header, pointer);
+    }
+
+    fn visit_media_type(&mut self, media_type: &MediaType, pointer: &str) {
+        self.check(&media_type.extensions, &[], pointer);
+        let encodings = pointer::push(pointer, "encoding");
+        for (property, encoding) in media_type.encoding.iter().flatten() {
+            self.check(&encoding.extensions, &[], &pointer::push(&encodings, property));
+        }
+        walk_media_type(self, media_type, pointer);
+    }
+
+    fn visit_schema(&mut self, schema: &Schema, pointer: &str) {
+        self.check(&schema.extensions, SCHEMA_FIELDS, pointer);
+        walk_schema(self, schema, pointer);
+    }
+}
+
+impl Rule for UnknownFields {
+    fn name(&self) -> &str {
+        "unknown-fields"
+    }
+
+    fn check(&self, spec: &Spec) -> Vec<(String, String)> {
+        let mut found = UnknownFieldsFound::default();
+        found.visit_spec(spec, "");
+        found.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SPEC: &str = r#"
+openapi: 3.0.0
+info: {title: Pets, version: "1"}
+tags:
+  - name: pets
+paths:
+  /pets:
+    get:
+      operationId: listPets
+      summary: List the pets
+      tags: [pets]
+      responses:
+        '200':
+          description: The pets
+          content:
+            application/json:
+              schema:
+                type: array
+                items: {$ref: '#/components/schemas/Pet'}
+        default:
+          description: An error
+          content:
+            application/json:
+              schema: {$ref: '#/components/schemas/Error'}
+  /petOwners/:
+    get:
+      operationId: list_owners
+      tags: [owners]
+      responses:
+        '200':
+          description: The owners
+          content:
+            application/json:
+              schema:
+                type: array
+                items:
+                  type: object
+                  properties:
+                    name: {type: string}
+components:
+  schemas:
+    Pet:
+      description: A pet
+      type: object
+    Error:
+      type: object
+"#;
+
+    fn rules(problems: &[Problem]) -> Vec<(&str, &str)> {
+        problems
+            .iter()
+            .map(|problem| (problem.rule.as_str(), problem.pointer.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn test_lint() {
+        let spec = crate::from_str(SPEC).unwrap();
+        let problems = Linter::new().lint(&spec);
+
+        let owners = "/paths/~1petOwners~1/get";
+        assert_eq!(
+            rules(&problems),
+            vec![
+                ("operation-id-case", "/paths/~1petOwners~1/get/operationId"),
+                ("operation-summary", owners),
+                ("tags-declared", "/paths/~1petOwners~1/get/tags/0"),
+                ("path-kebab-case", "/paths/~1petOwners~1"),
+                ("no-trailing-slash", "/paths/~1petOwners~1"),
+                ("error-responses", "/paths/~1petOwners~1/get/responses"),
+                ("schema-description", "/components/schemas/Error"),
+                (
+                    "no-inline-response-schema",
+                    "/paths/~1petOwners~1/get/responses/200/content/application~1json/schema/items"
+                ),
+            ]
+        );
+        assert!(!has_errors(&problems));
+        assert!(to_text(&problems).ends_with("0 errors, 6 warnings, 2 infos\n"));
+    }
+
+    #[test]
+    fn test_configure() {
+        let spec = crate::from_str(SPEC).unwrap();
+        let config = Config::from_yaml(
+            "
+rules:
+  operation-id-case:
+    severity: error
+    case: snake
+  path-kebab-case: off
+",
+        )
+        .unwrap();
+        let toml = Config::from_toml(
+            "
+[rules]
+path-kebab-case = \"off\"
+
+[rules.operation-id-case]
+severity = \"error\"
+case = \"snake\"
+",
+        )
+        .unwrap();
+        assert_eq!(config, toml);
+
+        let problems = Linter::new().configure(&config).unwrap().lint(&spec);
+        let case = problems.iter().find(|problem| problem.rule == "operation-id-case").unwrap();
+        assert_eq!(case.pointer, "/paths/~1pets/get/operationId");
+        assert_eq!(case.severity, Severity::Error);
+        assert!(problems.iter().all(|problem| problem.rule != "path-kebab-case"));
+        assert!(has_errors(&problems));
+
+        let unknown = Config::from_yaml("rules: {camel-case: error}").unwrap();
+        assert!(Linter::new().configure(&unknown).is_err());
+        let option = Config::from_yaml("rules: {operation-summary: {length: 10}}").unwrap();
+        assert!(Linter::new().configure(&option).is_err());
+    }
+
+    #[test]
+    fn test_unknown_fields() {
+        let spec = crate::from_str(
+            r#"
+openapi: 3.0.0
+info: {title: Pets, version: "1", x-audience: internal}
+tags:
+  - name: pets
+    externalDocs: {url: "https://example.com"}
+paths:
+  /pets:
+    get:
+      summery: List the pets
+      responses:
+        '200':
+          description: The pets
+          content:
+            application/json:
+              schema:
+                type: object
+                writeOnly: true
+                properties:
+                  name: {type: string, maxLenght: 10}
+"#,
+        )
+        .unwrap();
+        let problems = Linter::empty().rule(UnknownFields).lint(&spec);
+        assert_eq!(
+            rules(&problems),
+            vec![
+                ("unknown-fields", "/paths/~1pets/get/summery"),
+                (
+                    "unknown-fields",
+                    "/paths/~1pets/get/responses/200/content/application~1json/schema/properties/name/maxLenght"
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,659 @@
+//! Combine the specs of several services into one document, e.g. for an API gateway.
+//!
+//! ```ignore
+//! let gateway = Merger::new()
+//!     .info(Info { title: "Gateway".to_string(), version: "1".to_string(), ..Default::default() })
+//!     .prefixed_source("store", "/store", store)
+//!     .prefixed_source("users", "/users", users)
+//!     .merge()?;
+//! ```
+//!
+//! Paths, webhooks, components, tags and servers are combined in the order the sources
+//! were added. Components a later source defines identically are kept once. Components and
+//! operationIds it defines differently are resolved as `Conflict` says, and every `$ref`,
+//! security requirement and discriminator mapping of the source is rewritten to the renamed
+//! components. A path two sources share is merged when their methods differ. The security
+//! declared for a whole source is moved to its operations, so that it keeps applying only
+//! to them.
+//!
+//! The servers of a prefixed source serve its paths without the prefix, so only those whose
+//! URL ends with the prefix are kept, the prefix moved from their URL to the paths. The
+//! servers of a gateway serving all the paths are set with `Merger::servers`.
+
+use std::collections::{HashMap, HashSet};
+
+use serde_json::{Map as JsonMap, Value};
+
+use crate::pointer;
+use crate::server::Server;
+use crate::spec::{Info, Spec};
+use crate::{Error, LoadOptions, Map, Result};
+
+const METHODS: &[&str] = &["get", "put", "post", "delete", "options", "head", "patch", "trace"];
+
+/// How to resolve a name that a later source defines differently.
+#[derive(Clone, Copy, Debug)]
+pub enum Conflict {
+    /// Fail the merge.
+    Fail,
+    /// Keep the definition merged first, the later source referring to it instead of its own.
+    /// Operations cannot be dropped this way, so colliding operationIds fail the merge.
+    KeepFirst,
+    /// Name the later definition `rename(source, name)`, e.g. with `prefix_source`.
+    Rename(fn(&str, &str) -> String),
+}
+
+impl Default for Conflict {
+    fn default() -> Conflict {
+        Conflict::Rename(prefix_source)
+    }
+}
+
+/// Rename a conflicting `name` of the source `source` to `{source}_{name}`.
+pub fn prefix_source(source: &str, name: &str) -> String {
+    format!("{}_{}", source, name)
+}
+
+/// A spec to merge, named for conflict resolution.
+#[derive(Clone, Debug)]
+struct Source {
+    name: String,
+    prefix: Option<String>,
+    spec: Spec,
+}
+
+/// Merges specs. See the module documentation.
+#[derive(Clone, Debug, Default)]
+pub struct Merger {
+    sources: Vec<Source>,
+    conflict: Conflict,
+    info: Option<Info>,
+    servers: Option<Vec<Server>>,
+}
+
+impl Merger {
+    pub fn new() -> Merger {
+        Merger::default()
+    }
+
+    /// The info of the merged document, that of the first source unless set.
+    pub fn info(mut self, info: Info) -> Merger {
+        self.info = Some(info);
+        self
+    }
+
+    /// The servers of the merged document, those of the sources unless set.
+    pub fn servers(mut self, servers: Vec<Server>) -> Merger {
+        self.servers = Some(servers);
+        self
+    }
+
+    /// How to resolve conflicting names, renaming with `prefix_source` unless set.
+    pub fn conflict(mut self, conflict: Conflict) -> Merger {
+        self.conflict = conflict;
+        self
+    }
+
+    /// Add a spec, its paths kept as they are.
+    pub fn source(mut self, name: &str, spec: Spec) -> Merger {
+        self.sources.push(Source {
+            name: name.to_string(),
+            prefix: None,
+            spec,
+        });
+        self
+    }
+
+    /// Add a spec, its paths prefixed with `prefix`, e.g. `/store`.
+    pub fn prefixed_source(mut self, name: &str, prefix: &str, spec: Spec) -> Merger {
+        self.sources.push(Source {
+            name: name.to_string(),
+            prefix: Some(prefix.trim_end_matches('/').to_string()),
+            spec,
+        });
+        self
+    }
+
+    /// The merged spec.
+    pub fn merge(self) -> Result<Spec> {
+        let first = match self.sources.first() {
+            Some(first) => first,
+            None => return Err(Error::InvalidSpec("there are no specs to merge".to_string())),
+        };
+        let mut merged = Merged {
+            openapi: first.spec.openapi.clone(),
+            info: self.info.clone().unwrap_or_else(|| first.spec.info.clone()),
+            ..Default::default()
+        };
+
+        for source in self.sources.iter() {
+            let mut document = serde_json::to_value(&source.spec)?;
+            move_security_to_operations(&mut document);
+
+            let components = document.get("components").and_then(Value::as_object).cloned().unwrap_or_default();
+            let renames = self.renames(&source.name, &components, &merged.components)?;
+            rewrite(&mut document, None, &renames);
+            let order = serde_yaml::to_value(&source.spec.components)?;
+            merged.components(&document, &order, &renames);
+
+            // the paths are taken in the order of the source, which its JSON form may not keep
+            let empty = Map::new();
+            let webhooks = source.spec.webhooks.as_ref().unwrap_or(&empty);
+            for &(key, paths, order) in &[("paths", true, &source.spec.paths), ("webhooks", false, webhooks)] {
+                for path in order.keys() {
+                    let item = match document.get(key).and_then(|items| items.get(path)) {
+                        Some(item) => item.clone(),
+                        None => continue,
+                    };
+                    let path = match (paths, &source.prefix) {
+                        (true, Some(prefix)) => format!("{}{}", prefix, path),
+                        _ => path.clone(),
+                    };
+                    self.path_item(&mut merged, source, key, path, item)?;
+                }
+            }
+
+            for server in document.get("servers").and_then(Value::as_array).into_iter().flatten() {
+                let mut server = server.clone();
+                if let (Some(prefix), Some(Value::String(url))) = (&source.prefix, server.get_mut("url")) {
+                    match url.trim_end_matches('/').strip_suffix(prefix.as_str()) {
+                        Some(stripped) => *url = stripped.to_string(),
+                        None => continue,
+                    }
+                }
+                if !merged.servers.iter().any(|merged| merged.get("url") == server.get("url")) {
+                    merged.servers.push(server);
+                }
+            }
+            for tag in document.get("tags").and_then(Value::as_array).into_iter().flatten() {
+                if !merged.tags.iter().any(|merged| merged.get("name") == tag.get("name")) {
+                    merged.tags.push(tag.clone());
+                }
+            }
+        }
+
+        if let Some(ref servers) = self.servers {
+            merged.servers = servers.iter().map(serde_json::to_value).collect::<serde_json::Result<_>>()?;
+        }
+        merged.into_spec()
+    }
+
+    /// The new names of the components of a source that conflict with those merged so far,
+    /// by kind and name. Renaming a component changes those referring to it, so conflicts
+    /// are looked for until no more are found.
+    fn renames(
+        &self,
+        source: &str,
+        components: &JsonMap<String, Value>,
+        merged: &Map<String, Map<String, Value>>,
+    ) -> Result<HashMap<(String, String), String>> {
+        let mut renames: HashMap<(String, String), String> = HashMap::new();
+        loop {
+            let mut changed = false;
+            for (kind, definitions) in components.iter().filter(|(kind, _)| !kind.starts_with("x-")) {
+                for (name, definition) in definitions.as_object().into_iter().flatten() {
+                    let key = (kind.clone(), name.clone());
+                    if renames.contains_key(&key) {
+                        continue;
+                    }
+                    let existing = match merged.get(kind).and_then(|definitions| definitions.get(name)) {
+                        Some(existing) => existing,
+                        None => continue,
+                    };
+                    let mut definition = definition.clone();
+                    rewrite(&mut definition, None, &renames);
+                    if *existing == definition {
+                        continue;
+                    }
+
+                    let renamed = match self.conflict {
+                        Conflict::Fail => {
+                            let message = format!("{} defines {} {} differently", source, kind, name);
+                            return Err(Error::MergeConflict(message));
+                        }
+                        Conflict::KeepFirst => name.clone(),
+                        Conflict::Rename(rename) => {
+                            let taken = |candidate: &str| {
+                                merged.get(kind).and_then(|definitions| definitions.get(candidate)).is_some()
+                                    || definitions.get(candidate).is_some()
+                                    || renames.iter().any(|((k, _), renamed)| k == kind && renamed == candidate)
+                            };
+                            unique(&rename(source, name), taken)
+                        }
+                    };
+                    renames.insert(key, renamed);
+                    changed = true;
+                }
+            }
+            if !changed {
+                return Ok(renames);
+            }
+        }
+    }
+
+    fn path_item(&self, merged: &mut Merged, source: &Source, key: &str, path: String, item: Value) -> Result<()> {
+        let mut item = match item {
+            Value::Object(item) => item,
+            _ => return Ok(()),
+        };
+
+        for method in METHODS {
+            let operation_id = item
+                .get(*method)
+                .and_then(|operation| operation.get("operationId"))
+                .and_then(Value::as_str)
+                .map(str::to_string);
+            let operation_id = match operation_id {
+                Some(operation_id) => operation_id,
+                None => continue,
+            };
+            let kept = merged.operation(key, &path, method).is_some();
+            if kept || !merged.operation_ids.contains(&operation_id) {
+                continue;
+            }
+            let renamed = match self.conflict {
+                Conflict::Rename(rename) => unique(&rename(&source.name, &operation_id), |candidate| {
+                    merged.operation_ids.contains(candidate)
+                }),
+                _ => {
+                    let message = format!("{} reuses the operationId {}", source.name, operation_id);
+                    return Err(Error::MergeConflict(message));
+                }
+            };
+            item[*method]["operationId"] = Value::String(renamed);
+        }
+
+        let items = merged.items.entry(key.to_string()).or_default();
+        let existing = match items.get_mut(&path) {
+            Some(existing) => existing,
+            None => {
+                merged.operation_ids.extend(operation_ids(&item));
+                items.insert(path, item);
+                return Ok(());
+            }
+        };
+
+        // the items now share path level fields, so the parameters move to the operations
+        for item in [&mut *existing, &mut item] {
+            if let Some(Value::Array(parameters)) = item.remove("parameters") {
+                for method in METHODS {
+                    if let Some(Value::Object(operation)) = item.get_mut(*method) {
+                        add_parameters(operation, &parameters);
+                    }
+                }
+            }
+        }
+        for method in METHODS {
+            let operation = match item.remove(*method) {
+                Some(operation) => operation,
+                None => continue,
+            };
+            if existing.contains_key(*method) {
+                if let Conflict::KeepFirst = self.conflict {
+                    continue;
+                }
+                let message = format!("{} also defines {} {}", source.name, method.to_uppercase(), path);
+                return Err(Error::MergeConflict(message));
+            }
+            if let Some(operation_id) = operation.get("operationId").and_then(Value::as_str) {
+                merged.operation_ids.insert(operation_id.to_string());
+            }
+            existing.insert(method.to_string(), operation);
+        }
+        Ok(())
+    }
+}
+
+/// The document being merged.
+#[derive(Debug, Default)]
+struct Merged {
+    openapi: String,
+    info: Info,
+    servers: Vec<Value>,
+    tags: Vec<Value>,
+    /// Path items by `paths` or `webhooks`.
+    items: Map<String, Map<String, JsonMap<String, Value>>>,
+    /// Components by kind and name.
+    components: Map<String, Map<String, Value>>,
+    operation_ids: HashSet<String>,
+}
+
+impl Merged {
+    fn operation(&self, key: &str, path: &str, method: &str) -> Option<&Value> {
+        self.items.get(key)?.get(path)?.get(method)
+    }
+
+    /// Add the components of a source, its references already rewritten to `renames`, in the
+    /// order of `order`, the YAML form of its components.
+    fn components(&mut self, document: &Value, order: &serde_yaml::Value, renames: &HashMap<(String, String), String>) {
+        let keys = |mapping: &serde_yaml::Value| -> Vec<String> {
+            let mapping = mapping.as_mapping().into_iter().flatten();
+            mapping.filter_map(|(key, _)| key.as_str().map(str::to_string)).collect()
+        };
+        for kind in keys(order).into_iter().filter(|kind| !kind.starts_with("x-")) {
+            let definitions = match document.get("components").and_then(|components| components.get(&kind)) {
+                Some(definitions) => definitions,
+                None => continue,
+            };
+            for name in keys(&order[kind.as_str()]) {
+                let definition = match definitions.get(&name) {
+                    Some(definition) => definition,
+                    None => continue,
+                };
+                let name = renames.get(&(kind.clone(), name.clone())).cloned().unwrap_or(name);
+                let merged = self.components.entry(kind.clone()).or_default();
+                if !merged.contains_key(&name) {
+                    merged.insert(name, definition.clone());
+                }
+            }
+        }
+    }
+
+    /// The merged spec, read from a YAML document as its mappings keep the order of the paths.
+    fn into_spec(self) -> Result<Spec> {
+        let mut document = serde_yaml::Mapping::new();
+        let mut insert = |key: &str, value: serde_yaml::Value| {
+            document.insert(serde_yaml::Value::String(key.to_string()), value);
+        };
+        insert("openapi", serde_yaml::Value::String(self.openapi));
+        insert("info", serde_yaml::to_value(&self.info)?);
+        if !self.servers.is_empty() {
+            insert("servers", serde_yaml::to_value(&self.servers)?);
+        }
+        let mut items = self.items;
+        insert("paths", serde_yaml::to_value(&items.shift_remove("paths").unwrap_or_default())?);
+        if let Some(webhooks) = items.shift_remove("webhooks") {
+            insert("webhooks", serde_yaml::to_value(&webhooks)?);
+        }
+        if !self.components.is_empty() {
+            insert("components", serde_yaml::to_value(&self.components)?);
+        }
+        if !self.tags.is_empty() {
+            insert("tags", serde_yaml::to_value(&self.tags)?);
+        }
+        crate::from_document(serde_yaml::Value::Mapping(document), LoadOptions::default())
+    }
+}
+
+/// `name`, or `name` followed by the first number making it free.
+fn unique<F>(name: &str, taken: F) -> String
+where
+    F: Fn(&str) -> bool,
+{
+    let mut candidate = name.to_string();
+    let mut number = 2;
+    while taken(&candidate) {
+        candidate = format!("{}{}", name, number);
+        number += 1;
+    }
+    candidate
+}
+
+fn operation_ids(item: &JsonMap<String, Value>) -> Vec<String> {
+    METHODS
+        .iter()
+        .filter_map(|method| item.get(*method)?.get("operationId")?.as_str())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Add path level `parameters` to an operation, unless it overrides them.
+fn add_parameters(operation: &mut JsonMap<String, Value>, parameters: &[Value]) {
+    let own = operation.entry("parameters").or_insert_with(|| Value::Array(Vec::new()));
+    if let Value::Array(own) = own {
+        let key = |parameter: &Value| (parameter.get("name").cloned(), parameter.get("in").cloned(), parameter.get("$ref").cloned());
+        let mut added: Vec<Value> = parameters
+            .iter()
+            .filter(|parameter| !own.iter().any(|own| key(own) == key(parameter)))
+            .cloned()
+            .collect();
+        added.append(own);
+        *own = added;
+    }
+}
+
+/// Give the operations of a document without their own `security` the document's, and
+/// remove the document's.
+fn move_security_to_operations(document: &mut Value) {
+    let security = match document.as_object_mut().and_then(|document| document.remove("security")) {
+        Some(security) => security,
+        None => return,
+    };
+    for key in &["paths", "webhooks"] {
+        let items = document.get_mut(*key).and_then(Value::as_object_mut).into_iter().flatten();
+        for (_, item) in items {
+            for method in METHODS {
+                if let Some(Value::Object(operation)) = item.get_mut(*method) {
+                    operation.entry("security").or_insert_with(|| security.clone());
+                }
+            }
+        }
+    }
+}
+
+/// Point the local references, security requirements and discriminator mappings of `value`
+/// at renamed components. `key` is the key `value` is found under.
+fn rewrite(value: &mut Value, key: Option<&str>, renames: &HashMap<(String, String), String>) {
+    if renames.is_empty() {
+        return;
+    }
+    let renamed_ref = |reference: &str| -> Option<String> {
+        let tokens = pointer::tokens(reference.strip_prefix('#')?);
+        match tokens.as_slice() {
+            [components, kind, name] if components == "components" => {
+                let renamed = renames.get(&(kind.clone(), name.clone()))?;
+                Some(format!("#{}", pointer::push(&pointer::push("/components", kind), renamed)))
+            }
+            _ => None,
+        }
+    };
+
+    match value {
+        Value::Object(object) => {
+            if let Some(Value::String(reference)) = object.get_mut("$ref") {
+                if let Some(renamed) = renamed_ref(reference) {
+                    *reference = renamed;
+                }
+            }
+            if key == Some("discriminator") {
+                if let Some(Value::Object(mapping)) = object.get_mut("mapping") {
+                    for (_, target) in mapping.iter_mut() {
+                        if let Value::String(target) = target {
+                            let renamed = match renamed_ref(target) {
+                                Some(renamed) => Some(renamed),
+                                None => renames.get(&("schemas".to_string(), target.clone())).cloned(),
+                            };
+                            if let Some(renamed) = renamed {
+                                *target = renamed;
+                            }
+                        }
+                    }
+                }
+            }
+            for (key, value) in object.iter_mut() {
+                rewrite(value, Some(key), renames);
+            }
+        }
+        Value::Array(items) => {
+            if key == Some("security") {
+                for requirement in items.iter_mut() {
+                    if let Value::Object(requirement) = requirement {
+                        let names: Vec<(String, Value)> = std::mem::take(requirement).into_iter().collect();
+                        for (name, scopes) in names {
+                            let name = renames
+                                .get(&("securitySchemes".to_string(), name.clone()))
+                                .cloned()
+                                .unwrap_or(name);
+                            requirement.insert(name, scopes);
+                        }
+                    }
+                }
+            }
+            for item in items.iter_mut() {
+                rewrite(item, None, renames);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STORE: &str = r#"
+openapi: 3.0.0
+info: {title: Store, version: "1"}
+servers:
+  - url: https://store.example.com
+security:
+  - api_key: []
+paths:
+  /pets:
+    get:
+      operationId: listPets
+      responses:
+        '200':
+          description: The pets
+          content:
+            application/json:
+              schema:
+                type: array
+                items: {$ref: '#/components/schemas/Pet'}
+        default:
+          description: An error
+          content:
+            application/json:
+              schema: {$ref: '#/components/schemas/Error'}
+components:
+  schemas:
+    Pet:
+      type: object
+      properties:
+        name: {type: string}
+    Error:
+      type: object
+      properties:
+        message: {type: string}
+  securitySchemes:
+    api_key: {type: apiKey, name: key, in: header}
+"#;
+
+    const SHELTER: &str = r#"
+openapi: 3.0.0
+info: {title: Shelter, version: "1"}
+paths:
+  /pets:
+    get:
+      operationId: listPets
+      responses:
+        '200':
+          description: The pets
+          content:
+            application/json:
+              schema:
+                type: array
+                items: {$ref: '#/components/schemas/Pet'}
+        default:
+          description: An error
+          content:
+            application/json:
+              schema: {$ref: '#/components/schemas/Error'}
+components:
+  schemas:
+    Pet:
+      type: object
+      properties:
+        name: {type: string}
+        shelter: {type: string}
+    Error:
+      type: object
+      properties:
+        message: {type: string}
+"#;
+
+    #[test]
+    fn test_merge() {
+        let spec = Merger::new()
+            .prefixed_source("store", "/store", crate::from_str(STORE).unwrap())
+            .prefixed_source("shelter", "/shelter/", crate::from_str(SHELTER).unwrap())
+            .merge()
+            .unwrap();
+
+        assert_eq!(spec.info.title, "Store");
+        assert!(spec.security.is_none());
+        let store = spec.paths["/store/pets"].get.as_ref().unwrap();
+        assert_eq!(store.operation_id, Some("listPets".to_string()));
+        assert!(store.security.is_some());
+
+        let shelter = spec.paths["/shelter/pets"].get.as_ref().unwrap();
+        assert_eq!(shelter.operation_id, Some("shelter_listPets".to_string()));
+        assert!(shelter.security.is_none());
+        let json = serde_json::to_value(shelter).unwrap();
+        let pets = &json["responses"]["200"]["content"]["application/json"]["schema"]["items"]["$ref"];
+        assert_eq!(pets, "#/components/schemas/shelter_Pet");
+        assert_eq!(json["responses"]["default"]["content"]["application/json"]["schema"]["$ref"], "#/components/schemas/Error");
+
+        let schemas = spec.components.unwrap().schemas.unwrap();
+        let mut names: Vec<&String> = schemas.keys().collect();
+        names.sort();
+        assert_eq!(names, vec!["Error", "Pet", "shelter_Pet"]);
+    }
+
+    #[test]
+    fn test_merge_conflicts() {
+        let store = crate::from_str(STORE).unwrap();
+        let shelter = crate::from_str(SHELTER).unwrap();
+
+        let failed = Merger::new()
+            .conflict(Conflict::Fail)
+            .source("store", store.clone())
+            .prefixed_source("shelter", "/shelter", shelter.clone())
+            .merge();
+        assert!(failed.is_err());
+
+        // the paths collide as well as the operationIds
+        let kept = Merger::new()
+            .conflict(Conflict::KeepFirst)
+            .source("store", store)
+            .source("shelter", shelter)
+            .merge()
+            .unwrap();
+        assert_eq!(kept.paths.len(), 1);
+        assert_eq!(kept.components.unwrap().schemas.unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_merge_order_and_servers() {
+        let store = STORE
+            .replace("https://store.example.com", "https://api.example.com/store/")
+            .replace("  /pets:\n", "  /zoo: {}\n  /pets:\n")
+            .replace("    Pet:\n", "    Zebra: {type: object}\n    Pet:\n");
+        let shelter = format!("{}servers:\n  - url: https://shelter.example.com\n", SHELTER);
+        let spec = Merger::new()
+            .prefixed_source("store", "/store", crate::from_str(&store).unwrap())
+            .prefixed_source("shelter", "/shelter", crate::from_str(&shelter).unwrap())
+            .merge()
+            .unwrap();
+
+        let paths: Vec<&String> = spec.paths.keys().collect();
+        assert_eq!(paths, vec!["/store/zoo", "/store/pets", "/shelter/pets"]);
+        let schemas = spec.components.unwrap().schemas.unwrap();
+        let names: Vec<&String> = schemas.keys().collect();
+        assert_eq!(names, vec!["Zebra", "Pet", "Error", "shelter_Pet"]);
+        // the shelter's server does not serve /shelter/pets
+        let urls: Vec<String> = spec.servers.unwrap().into_iter().map(|server| server.url).collect();
+        assert_eq!(urls, vec!["https://api.example.com"]);
+
+        let gateway = Server {
+            url: "https://gateway.example.com".to_string(),
+            ..Default::default()
+        };
+        let spec = Merger::new()
+            .servers(vec![gateway.clone()])
+            .prefixed_source("shelter", "/shelter", crate::from_str(&shelter).unwrap())
+            .merge()
+            .unwrap();
+        assert_eq!(spec.servers, Some(vec![gateway]));
+    }
+}
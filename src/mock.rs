@@ -0,0 +1,571 @@
+//! A mock actix-web server answering every operation of a `Spec` with its examples.
+//!
+//! Responses are picked from the examples of the response media type, the examples held in
+//! `components.examples` or the schema's own example, and are otherwise generated from the
+//! response schema by `sample::Generator`. Clients can choose the response with a `Prefer` header, e.g.
+//! `Prefer: code=404, example=notFound`, the example being named by the media type or in
+//! `components.examples`. Incoming requests are validated against the declared parameters
+//! and request body before a response is produced. Only `application/json` and
+//! `application/x-www-form-urlencoded` bodies are checked, other media types are accepted as
+//! they are.
+
+use crate::Map;
+use std::io;
+use std::sync::Arc;
+use actix_web::{server, App, HttpMessage, HttpRequest, HttpResponse};
+use actix_web::http::{Method, StatusCode};
+use serde_json::{json, Value};
+use url::form_urlencoded;
+use url::percent_encoding::percent_decode;
+
+use crate::components::ObjectOrReference;
+use crate::operation::Operation;
+use crate::path::{Parameter, PathItem};
+use crate::spec::{Example, MediaType, MediaTypeExample, Response, Schema, Spec};
+use crate::sample::Generator;
+use crate::validation;
+
+const PREFER: &str = "prefer";
+const JSON_CONTENT_TYPE: &str = "application/json";
+const FORM_CONTENT_TYPE: &str = "application/x-www-form-urlencoded";
+const EXAMPLES_PREFIX: &str = "#/components/examples/";
+
+/// Serves canned responses for the operations of a spec.
+#[derive(Clone, Debug)]
+pub struct MockServer {
+    spec: Arc<Spec>,
+    base_paths: Vec<String>,
+}
+
+/// The choices a client expressed through the `Prefer` header.
+#[derive(Clone, Debug, Default, PartialEq)]
+struct Preference {
+    code: Option<String>,
+    example: Option<String>,
+}
+
+impl MockServer {
+    pub fn new(spec: Spec) -> MockServer {
+        let mut base_paths: Vec<String> = spec
+            .servers
+            .iter()
+            .flatten()
+            .map(|server| base_path(&server.url))
+            .filter(|path| !path.is_empty())
+            .collect();
+        // try the most specific base path first
+        base_paths.sort_by(|a, b| b.len().cmp(&a.len()));
+
+        MockServer {
+            spec: Arc::new(spec),
+            base_paths,
+        }
+    }
+
+    /// An `App` routing every request to the mock.
+    pub fn app(self) -> App {
+        let mock = Arc::new(self);
+
+        App::new().default_resource(move |r| {
+            r.route()
+                .with(move |(req, body): (HttpRequest, String)| mock.handle(&req, &body))
+        })
+    }
+
+    /// Serve the mock on `addr`, e.g. `127.0.0.1:8080`, until the process is stopped.
+    pub fn run(self, addr: &str) -> io::Result<()> {
+        info!("Serving mock of {} on {}", self.spec.info.title, addr);
+        server::new(move || self.clone().app()).bind(addr)?.run();
+        Ok(())
+    }
+
+    /// Answer a request whose body has already been read.
+    pub fn handle<S>(&self, req: &HttpRequest<S>, body: &str) -> HttpResponse {
+        let path = self.strip_base_path(req.path());
+        let (template, path_item, operation) = match self.find_operation(req.method(), path) {
+            Some(found) => found,
+            None => return HttpResponse::NotFound().finish(),
+        };
+
+        let errors = self.validate_request(req, &path_values(template, path), path_item, operation, body);
+        if !errors.is_empty() {
+            return HttpResponse::BadRequest().json(json!({ "errors": errors }));
+        }
+
+        let preference = req
+            .headers()
+            .get(PREFER)
+            .and_then(|value| value.to_str().ok())
+            .map(parse_preference)
+            .unwrap_or_default();
+
+        let (code, response) = match select_response(operation, preference.code.as_ref().map(String::as_str)) {
+            Some(found) => found,
+            None => return HttpResponse::NotImplemented().finish(),
+        };
+        let status = preference
+            .code
+            .as_ref()
+            .and_then(|code| code.parse::<u16>().ok())
+            .or_else(|| status_code(code))
+            .and_then(|code| StatusCode::from_u16(code).ok())
+            .unwrap_or(StatusCode::OK);
+
+        let mut builder = HttpResponse::build(status);
+        let (content_type, media_type) = match response.content.as_ref().and_then(select_media_type) {
+            Some(found) => found,
+            None => return builder.finish(),
+        };
+
+        match self.example(media_type, preference.example.as_ref().map(String::as_str)) {
+            Some(Value::String(ref text)) if content_type != JSON_CONTENT_TYPE => {
+                builder.content_type(content_type.as_str()).body(text.clone())
+            }
+            Some(value) => builder
+                .content_type(content_type.as_str())
+                .body(serde_json::to_string(&value).unwrap_or_default()),
+            None => builder.finish(),
+        }
+    }
+
+    fn strip_base_path<'a>(&self, path: &'a str) -> &'a str {
+        self.base_paths
+            .iter()
+            .find(|base| path.starts_with(base.as_str()))
+            .map(|base| &path[base.len()..])
+            .unwrap_or(path)
+    }
+
+    /// Find the path template and operation for a request, preferring templates with the
+    /// most literal segments.
+    fn find_operation(&self, method: &Method, path: &str) -> Option<(&str, &PathItem, &Operation)> {
+        self.spec
+            .paths
+            .iter()
+            .filter_map(|(template, path_item)| match_template(template, path).map(|score| (score, template, path_item)))
+            .filter_map(|(score, template, path_item)| {
+                path_item
+                    .operations()
+                    .into_iter()
+                    .find(|(op_method, _)| op_method == method)
+                    .map(|(_, operation)| (score, template, path_item, operation))
+            })
+            .max_by_key(|(score, _, _, _)| *score)
+            .map(|(_, template, path_item, operation)| (template.as_str(), path_item, operation))
+    }
+
+    fn validate_request<S>(
+        &self,
+        req: &HttpRequest<S>,
+        path_values: &[(String, String)],
+        path_item: &PathItem,
+        operation: &Operation,
+        body: &str,
+    ) -> Vec<String> {
+        let mut errors = Vec::new();
+        let components = self.spec.components.as_ref();
+
+        let parameters = path_item
+            .parameters
+            .iter()
+            .flatten()
+            .chain(operation.parameters.iter().flatten())
+            .filter_map(|parameter| parameter.resolve(components.and_then(|c| c.parameters.as_ref())));
+
+        for parameter in parameters {
+            let value = match parameter.location.as_str() {
+                "query" => req.query().get(&parameter.name).cloned(),
+                "header" => req
+                    .headers()
+                    .get(parameter.name.as_str())
+                    .and_then(|value| value.to_str().ok())
+                    .map(String::from),
+                "cookie" => req.cookie(&parameter.name).map(|cookie| cookie.value().to_string()),
+                "path" => path_values
+                    .iter()
+                    .find(|(name, _)| *name == parameter.name)
+                    .map(|(_, value)| value.clone()),
+                _ => continue,
+            };
+
+            match value {
+                Some(value) => errors.extend(self.validate_parameter(parameter, &value)),
+                None if parameter.required == Some(true) => errors.push(format!(
+                    "missing required {} parameter `{}`",
+                    parameter.location, parameter.name
+                )),
+                None => {}
+            }
+        }
+
+        let request_body = operation
+            .request_body
+            .as_ref()
+            .and_then(|body| body.resolve(components.and_then(|c| c.request_bodies.as_ref())));
+        if let Some(request_body) = request_body {
+            if body.trim().is_empty() {
+                if request_body.required == Some(true) {
+                    errors.push("missing required request body".to_string());
+                }
+                return errors;
+            }
+            let content_type = req.content_type();
+            let content_type = if content_type.is_empty() { JSON_CONTENT_TYPE } else { content_type };
+            let schema = request_body
+                .content
+                .get(content_type)
+                .and_then(|media_type| self.media_type_schema(media_type));
+            let value = match (content_type, schema) {
+                (JSON_CONTENT_TYPE, Some(_)) => serde_json::from_str::<Value>(body)
+                    .map_err(|err| format!("request body is not valid JSON: {}", err)),
+                (FORM_CONTENT_TYPE, Some(schema)) => Ok(self.form_value(schema, body)),
+                // other media types are not checked
+                _ => return errors,
+            };
+            match (value, schema) {
+                (Ok(value), Some(schema)) => errors.extend(
+                    validation::validate(&self.spec, schema, "", &value)
+                        .into_iter()
+                        .map(|error| format!("body{}: {}", error.instance_path, error.message)),
+                ),
+                (Err(err), _) => errors.push(err),
+                _ => {}
+            }
+        }
+
+        errors
+    }
+
+    /// Read a form body as an object, typing its fields by the schemas of the properties.
+    fn form_value(&self, schema: &Schema, body: &str) -> Value {
+        let mut object = serde_json::Map::new();
+        for (name, raw) in form_urlencoded::parse(body.as_bytes()).into_owned() {
+            let property = schema
+                .properties
+                .as_ref()
+                .and_then(|properties| properties.get(&name))
+                .map(|property| self.spec.resolve_schema(property));
+            match property {
+                Some(property) if property.type_name() == Some("array") => {
+                    let items = property.items.as_ref().map(|items| self.spec.resolve_schema(items));
+                    let item = items.map_or_else(|| Value::from(raw.as_str()), |items| typed(items, &raw));
+                    if let Value::Array(values) = object.entry(name).or_insert_with(|| Value::Array(Vec::new())) {
+                        values.push(item);
+                    }
+                }
+                Some(property) => {
+                    object.insert(name, typed(property, &raw));
+                }
+                None => {
+                    object.insert(name, Value::from(raw));
+                }
+            }
+        }
+        Value::Object(object)
+    }
+
+    fn validate_parameter(&self, parameter: &Parameter, raw: &str) -> Vec<String> {
+        let schema = match parameter.schema {
+            Some(ref schema) => self.spec.resolve_schema(schema),
+            None => return Vec::new(),
+        };
+        validation::validate(&self.spec, schema, "", &typed(schema, raw))
+            .into_iter()
+            .map(|error| format!("{} parameter `{}`: {}", parameter.location, parameter.name, error.message))
+            .collect()
+    }
+
+    fn media_type_schema<'a>(&'a self, media_type: &'a MediaType) -> Option<&'a Schema> {
+        let schemas = self.spec.components.as_ref().and_then(|c| c.schemas.as_ref());
+        media_type
+            .schema
+            .as_ref()
+            .and_then(|schema| schema.resolve(schemas))
+            .map(|schema| self.spec.resolve_schema(schema))
+    }
+
+    /// The example for a media type, by name when one was requested. A name is looked up in
+    /// the examples of the media type, then in `components.examples`, and may be given as a
+    /// `#/components/examples/` reference.
+    fn example(&self, media_type: &MediaType, name: Option<&str>) -> Option<Value> {
+        let components = self.spec.components.as_ref().and_then(|c| c.examples.as_ref());
+        let value = |example: &ObjectOrReference<Example>| {
+            example.resolve(components).and_then(|example| example.value.clone())
+        };
+
+        let examples = match media_type.examples {
+            Some(MediaTypeExample::Examples { ref examples }) => Some(examples),
+            _ => None,
+        };
+        let named = name.and_then(|name| {
+            let component = name.trim_start_matches(EXAMPLES_PREFIX);
+            let reference = format!("{}{}", EXAMPLES_PREFIX, component);
+            examples
+                .and_then(|examples| {
+                    examples.get(name).or_else(|| {
+                        examples.values().find(|example| match example {
+                            ObjectOrReference::Ref { ref_path } => *ref_path == reference,
+                            ObjectOrReference::Object(_) => false,
+                        })
+                    })
+                })
+                .and_then(value)
+                .or_else(|| components?.get(component).and_then(value))
+        });
+
+        named
+            .or_else(|| match media_type.examples {
+                Some(MediaTypeExample::Example { ref example }) => Some(example.clone()),
+                Some(MediaTypeExample::Examples { ref examples }) => examples.values().next().and_then(value),
+                None => None,
+            })
+            .or_else(|| {
+                self.media_type_schema(media_type).map(|schema| {
+                    Generator::new(&self.spec)
+                        .optional_probability(1.0)
+                        .generate(schema)
+                })
+            })
+    }
+}
+
+/// Read a raw parameter or form value as the type of its schema, keeping it a string when it
+/// does not parse so that validation reports it.
+fn typed(schema: &Schema, raw: &str) -> Value {
+    match schema.type_name() {
+        Some("integer") => raw.parse::<i64>().map(Value::from).unwrap_or_else(|_| Value::from(raw)),
+        Some("number") => raw.parse::<f64>().map(Value::from).unwrap_or_else(|_| Value::from(raw)),
+        Some("boolean") => raw.parse::<bool>().map(Value::from).unwrap_or_else(|_| Value::from(raw)),
+        _ => Value::from(raw),
+    }
+}
+
+/// The values of the path parameters of a request matching `template`, decoded. The mock
+/// routes every request through one default resource, so actix's `match_info()` holds none.
+fn path_values(template: &str, path: &str) -> Vec<(String, String)> {
+    template
+        .trim_matches('/')
+        .split('/')
+        .zip(path.trim_matches('/').split('/'))
+        .filter(|(expected, _)| expected.starts_with('{') && expected.ends_with('}'))
+        .map(|(expected, actual)| {
+            let name = expected[1..expected.len() - 1].to_string();
+            (name, percent_decode(actual.as_bytes()).decode_utf8_lossy().into_owned())
+        })
+        .collect()
+}
+
+/// Score how well a path template such as `/pets/{petId}` matches a request path.
+/// Returns the number of literal segments, or `None` if the path does not match.
+fn match_template(template: &str, path: &str) -> Option<usize> {
+    let template: Vec<&str> = template.trim_matches('/').split('/').collect();
+    let path: Vec<&str> = path.trim_matches('/').split('/').collect();
+    if template.len() != path.len() {
+        return None;
+    }
+
+    let mut literals = 0;
+    for (expected, actual) in template.iter().zip(path.iter()) {
+        if expected.starts_with('{') && expected.ends_with('}') {
+            if actual.is_empty() {
+                return None;
+            }
+        } else if expected == actual {
+            literals += 1;
+        } else {
+            return None;
+        }
+    }
+
+    Some(literals)
+}
+
+/// The path component of a server url, without a trailing slash.
+fn base_path(url: &str) -> String {
+    let path = match url.find("://") {
+        Some(pos) => {
+            let rest = &url[pos + 3..];
+            rest.find('/').map(|slash| &rest[slash..]).unwrap_or("")
+        }
+        None => url,
+    };
+
+    path.trim_end_matches('/').to_string()
+}
+
+fn parse_preference(header: &str) -> Preference {
+    let mut preference = Preference::default();
+
+    for pair in header.split(|c| c == ',' || c == ';') {
+        let mut parts = pair.trim().splitn(2, '=');
+        let key = parts.next().unwrap_or("").trim();
+        let value = parts.next().map(|value| value.trim().trim_matches('"').to_string());
+        match key {
+            "code" => preference.code = value,
+            "example" => preference.example = value,
+            _ => {}
+        }
+    }
+
+    preference
+}
+
+/// Pick the requested response, or the first successful one, falling back to `default`.
+fn select_response<'a>(operation: &'a Operation, code: Option<&str>) -> Option<(&'a str, &'a Response)> {
+    let responses = &operation.responses;
+    let find = |key: &str| responses.get_key_value(key).map(|(key, response)| (key.as_str(), response));
+
+    match code {
+        Some(code) => find(code)
+            .or_else(|| {
+                code.get(..1)
+                    .and_then(|class| find(&format!("{}XX", class)))
+            })
+            .or_else(|| find("default")),
+        None => responses
+            .iter()
+            .find(|(key, _)| key.starts_with('2'))
+            .map(|(key, response)| (key.as_str(), response))
+            .or_else(|| find("default"))
+            .or_else(|| responses.iter().next().map(|(key, response)| (key.as_str(), response))),
+    }
+}
+
+/// The status code of a response key such as `200` or `2XX`.
+fn status_code(key: &str) -> Option<u16> {
+    key.parse::<u16>().ok().or_else(|| {
+        if key.len() == 3 && key.ends_with("XX") {
+            key[..1].parse::<u16>().ok().map(|class| class * 100)
+        } else {
+            None
+        }
+    })
+}
+
+/// Prefer `application/json`, otherwise the first declared media type.
+fn select_media_type(content: &Map<String, MediaType>) -> Option<(&String, &MediaType)> {
+    content
+        .get_key_value(JSON_CONTENT_TYPE)
+        .or_else(|| content.iter().next())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::test::TestRequest;
+
+    fn mock() -> MockServer {
+        MockServer::new(crate::from_path("./data/v3.0/petstore.yaml").unwrap())
+    }
+
+    #[test]
+    fn test_match_template() {
+        assert_eq!(match_template("/pets/{petId}", "/pets/1"), Some(1));
+        assert_eq!(match_template("/pets", "/pets"), Some(1));
+        assert_eq!(match_template("/pets/{petId}", "/pets"), None);
+    }
+
+    #[test]
+    fn test_parse_preference() {
+        let preference = parse_preference("code=404, example=notFound");
+        assert_eq!(preference.code, Some("404".to_string()));
+        assert_eq!(preference.example, Some("notFound".to_string()));
+    }
+
+    #[test]
+    fn test_handle() {
+        let req = TestRequest::with_uri("/v1/pets/1").finish();
+        assert_eq!(mock().handle(&req, "").status(), StatusCode::OK);
+
+        let req = TestRequest::with_header(PREFER, "code=500").uri("/v1/pets/1").finish();
+        assert_eq!(mock().handle(&req, "").status(), StatusCode::INTERNAL_SERVER_ERROR);
+
+        let req = TestRequest::with_uri("/v1/pets?limit=ten").finish();
+        assert_eq!(mock().handle(&req, "").status(), StatusCode::BAD_REQUEST);
+
+        let req = TestRequest::with_uri("/v1/owners").finish();
+        assert_eq!(mock().handle(&req, "").status(), StatusCode::NOT_FOUND);
+    }
+
+    const ORDERS: &str = r##"
+openapi: 3.0.0
+info: {title: Orders, version: "1"}
+paths:
+  /orders/{orderId}:
+    parameters:
+      - {name: orderId, in: path, required: true, schema: {type: integer}}
+    get:
+      responses:
+        '200':
+          description: An order
+          content:
+            application/json:
+              examples:
+                first: {value: {id: 1}}
+                big: {$ref: '#/components/examples/large'}
+    post:
+      requestBody:
+        content:
+          application/x-www-form-urlencoded:
+            schema:
+              type: object
+              required: [quantity]
+              properties:
+                quantity: {type: integer}
+                tags: {type: array, items: {type: string}}
+      responses:
+        '204': {description: Updated}
+components:
+  examples:
+    large: {value: {id: 1000}}
+"##;
+
+    fn orders() -> MockServer {
+        MockServer::new(crate::from_str(ORDERS).unwrap())
+    }
+
+    fn body(response: &HttpResponse) -> Value {
+        match response.body() {
+            actix_web::Body::Binary(binary) => serde_json::from_slice(binary.as_ref()).unwrap(),
+            _ => panic!("expected a binary body"),
+        }
+    }
+
+    #[test]
+    fn test_path_parameters() {
+        let req = TestRequest::with_uri("/orders/12").finish();
+        assert_eq!(orders().handle(&req, "").status(), StatusCode::OK);
+
+        let req = TestRequest::with_uri("/orders/twelve").finish();
+        let response = orders().handle(&req, "");
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert!(body(&response)["errors"][0].as_str().unwrap().contains("path parameter `orderId`"));
+    }
+
+    #[test]
+    fn test_form_body() {
+        let form = |body: &str| {
+            let req = TestRequest::with_header("content-type", FORM_CONTENT_TYPE)
+                .method(Method::POST)
+                .uri("/orders/12")
+                .finish();
+            orders().handle(&req, body).status()
+        };
+        assert_eq!(form("quantity=2&tags=a&tags=b"), StatusCode::NO_CONTENT);
+        assert_eq!(form("quantity=two"), StatusCode::BAD_REQUEST);
+        assert_eq!(form("tags=a"), StatusCode::BAD_REQUEST);
+    }
+
+    #[test]
+    fn test_preferred_example() {
+        let example = |prefer: &str| {
+            let req = TestRequest::with_header(PREFER, prefer).uri("/orders/12").finish();
+            body(&orders().handle(&req, ""))
+        };
+        assert_eq!(example("example=first"), json!({"id": 1}));
+        assert_eq!(example("example=big"), json!({"id": 1000}));
+        assert_eq!(example("example=large"), json!({"id": 1000}));
+        assert_eq!(example("example=#/components/examples/large"), json!({"id": 1000}));
+        assert_eq!(example("example=missing"), json!({"id": 1}));
+    }
+}
@@ -0,0 +1,334 @@
+//! Support for [OpenApi 3.1](https://github.com/OAI/OpenAPI-Specification/blob/main/versions/3.1.0.md)
+//! documents.
+//!
+//! OpenApi 3.1 documents load into the same `Spec` model as OpenApi 3.0 documents, whose
+//! fields cover the additions of 3.1 (type lists, `const`, schema `examples`, numeric
+//! exclusive bounds, `webhooks`, `jsonSchemaDialect` and `license.identifier`).
+//! `downgrade` converts such a `Spec` into one that only uses OpenApi 3.0 features.
+
+use crate::Map;
+use serde_json::Value;
+
+use crate::components::ObjectOrReference;
+use crate::conversion::{Conversion, LossyConversion};
+use crate::operation::Operation;
+use crate::path::{Parameter, PathItem};
+use crate::pointer;
+use crate::spec::{ExclusiveBound, Header, MediaType, Schema, SchemaType, Spec};
+
+/// The OpenApi version of a downgraded document.
+const DOWNGRADED_VERSION: &str = "3.0.3";
+
+/// Convert an OpenApi 3.1 `Spec` to OpenApi 3.0, reporting what could not be expressed.
+pub fn downgrade(spec: &Spec) -> Conversion {
+    let mut spec = spec.clone();
+    let mut downgrader = Downgrader { lossy: Vec::new() };
+
+    spec.openapi = DOWNGRADED_VERSION.to_string();
+
+    if spec.info.summary.take().is_some() {
+        downgrader.lossy("/info/summary", "info summary is not supported");
+    }
+    if let Some(ref mut license) = spec.info.license {
+        if license.identifier.take().is_some() {
+            downgrader.lossy("/info/license/identifier", "license identifier is not supported");
+        }
+    }
+    if spec.webhooks.take().is_some() {
+        downgrader.lossy("/webhooks", "webhooks are not supported");
+    }
+    if spec.json_schema_dialect.take().is_some() {
+        downgrader.lossy("/jsonSchemaDialect", "jsonSchemaDialect is not supported");
+    }
+
+    for (path, path_item) in spec.paths.iter_mut() {
+        downgrader.path_item(path_item, &pointer::push("/paths", path));
+    }
+
+    if let Some(ref mut components) = spec.components {
+        if components.path_items.take().is_some() {
+            downgrader.lossy("/components/pathItems", "path item components are not supported");
+        }
+        for (name, schema) in components.schemas.iter_mut().flatten() {
+            if let ObjectOrReference::Object(ref mut schema) = *schema {
+                downgrader.schema(schema, &pointer::push("/components/schemas", name));
+            }
+        }
+        for (name, parameter) in components.parameters.iter_mut().flatten() {
+            if let ObjectOrReference::Object(ref mut parameter) = *parameter {
+                downgrader.parameter(parameter, &pointer::push("/components/parameters", name));
+            }
+        }
+        for (name, request_body) in components.request_bodies.iter_mut().flatten() {
+            if let ObjectOrReference::Object(ref mut request_body) = *request_body {
+                let path = pointer::push("/components/requestBodies", name);
+                downgrader.content(Some(&mut request_body.content), &path);
+            }
+        }
+        for (name, response) in components.responses.iter_mut().flatten() {
+            if let ObjectOrReference::Object(ref mut response) = *response {
+                let path = pointer::push("/components/responses", name);
+                downgrader.headers(response.headers.as_mut(), &path);
+                downgrader.content(response.content.as_mut(), &path);
+            }
+        }
+        for (name, header) in components.headers.iter_mut().flatten() {
+            if let ObjectOrReference::Object(ref mut header) = *header {
+                downgrader.header(header, &pointer::push("/components/headers", name));
+            }
+        }
+    }
+
+    Conversion {
+        spec,
+        lossy: downgrader.lossy,
+    }
+}
+
+struct Downgrader {
+    lossy: Vec<LossyConversion>,
+}
+
+impl Downgrader {
+    fn lossy(&mut self, path: &str, message: &str) {
+        self.lossy.push(LossyConversion {
+            path: path.to_string(),
+            message: message.to_string(),
+        });
+    }
+
+    fn path_item(&mut self, path_item: &mut PathItem, path: &str) {
+        self.parameters(path_item.parameters.as_mut(), path);
+
+        let operations = vec![
+            ("get", &mut path_item.get),
+            ("put", &mut path_item.put),
+            ("post", &mut path_item.post),
+            ("delete", &mut path_item.delete),
+            ("options", &mut path_item.options),
+            ("head", &mut path_item.head),
+            ("patch", &mut path_item.patch),
+            ("trace", &mut path_item.trace),
+        ];
+        for (method, operation) in operations {
+            if let Some(operation) = operation {
+                self.operation(operation, &pointer::push(path, method));
+            }
+        }
+    }
+
+    fn operation(&mut self, operation: &mut Operation, path: &str) {
+        self.parameters(operation.parameters.as_mut(), path);
+
+        if let Some(ObjectOrReference::Object(ref mut request_body)) = operation.request_body {
+            self.content(Some(&mut request_body.content), &pointer::push(path, "requestBody"));
+        }
+
+        let responses = pointer::push(path, "responses");
+        for (code, response) in operation.responses.iter_mut() {
+            let path = pointer::push(&responses, code);
+            self.headers(response.headers.as_mut(), &path);
+            self.content(response.content.as_mut(), &path);
+        }
+    }
+
+    fn parameters(&mut self, parameters: Option<&mut Vec<ObjectOrReference<Parameter>>>, path: &str) {
+        let path = pointer::push(path, "parameters");
+        for (index, parameter) in parameters.into_iter().flatten().enumerate() {
+            if let ObjectOrReference::Object(ref mut parameter) = *parameter {
+                self.parameter(parameter, &pointer::push(&path, &index.to_string()));
+            }
+        }
+    }
+
+    fn parameter(&mut self, parameter: &mut Parameter, path: &str) {
+        if let Some(ref mut schema) = parameter.schema {
+            self.schema(schema, &pointer::push(path, "schema"));
+        }
+    }
+
+    fn headers(&mut self, headers: Option<&mut Map<String, ObjectOrReference<Header>>>, path: &str) {
+        let path = pointer::push(path, "headers");
+        for (name, header) in headers.into_iter().flatten() {
+            if let ObjectOrReference::Object(ref mut header) = *header {
+                self.header(header, &pointer::push(&path, name));
+            }
+        }
+    }
+
+    fn header(&mut self, header: &mut Header, path: &str) {
+        if let Some(ref mut schema) = header.schema {
+            self.schema(schema, &pointer::push(path, "schema"));
+        }
+    }
+
+    fn content(&mut self, content: Option<&mut Map<String, MediaType>>, path: &str) {
+        let path = pointer::push(path, "content");
+        for (name, media_type) in content.into_iter().flatten() {
+            if let Some(ObjectOrReference::Object(ref mut schema)) = media_type.schema {
+                self.schema(schema, &pointer::push(&pointer::push(&path, name), "schema"));
+            }
+        }
+    }
+
+    /// Rewrite the OpenApi 3.1 keywords of a schema and of its nested schemas.
+    fn schema(&mut self, schema: &mut Schema, path: &str) {
+        if let Some(SchemaType::Multiple(types)) = schema.schema_type.clone() {
+            let nullable = types.iter().any(|t| t == "null");
+            let types: Vec<String> = types.into_iter().filter(|t| t != "null").collect();
+            if types.len() > 1 {
+                self.lossy(&pointer::push(path, "type"), "only the first of several types is kept");
+            }
+            schema.schema_type = types.into_iter().next().map(SchemaType::Single);
+            if schema.schema_type.is_none() {
+                self.lossy(&pointer::push(path, "type"), "the null type is not supported");
+            }
+            if nullable {
+                schema.nullable = Some(true);
+            }
+        }
+
+        // the model's enum values are strings, so other constants cannot keep their type
+        match schema.const_value.take() {
+            Some(Value::String(value)) => schema.enum_values = Some(vec![value]),
+            Some(value) => {
+                let message = format!("the constant {} is not a string and cannot be expressed as an enum", value);
+                self.lossy(&pointer::push(path, "const"), &message);
+            }
+            None => {}
+        }
+
+        if let Some(examples) = schema.examples.take() {
+            if examples.len() > 1 {
+                self.lossy(&pointer::push(path, "examples"), "only the first example is kept");
+            }
+            if schema.example.is_none() {
+                schema.example = examples.into_iter().next();
+            }
+        }
+
+        if let Some(ExclusiveBound::Limit(limit)) = schema.exclusive_minimum.clone() {
+            let exclusive = schema.minimum_bound().map_or(true, |(_, exclusive)| exclusive);
+            if exclusive {
+                schema.minimum = Some(limit);
+                schema.exclusive_minimum = Some(ExclusiveBound::Flag(true));
+            } else {
+                schema.exclusive_minimum = None;
+            }
+        }
+        if let Some(ExclusiveBound::Limit(limit)) = schema.exclusive_maximum.clone() {
+            let exclusive = schema.maximum_bound().map_or(true, |(_, exclusive)| exclusive);
+            if exclusive {
+                schema.maximum = Some(limit);
+                schema.exclusive_maximum = Some(ExclusiveBound::Flag(true));
+            } else {
+                schema.exclusive_maximum = None;
+            }
+        }
+
+        // OpenApi 3.0 ignores the siblings of `$ref`, so wrap the reference in `allOf`
+        if let Some(ref_path) = schema.ref_path.clone() {
+            let mut siblings = schema.clone();
+            siblings.ref_path = None;
+            if siblings != Schema::default() {
+                schema.ref_path = None;
+                let reference = ObjectOrReference::Object(Schema {
+                    ref_path: Some(ref_path),
+                    ..Default::default()
+                });
+                schema.all_of.get_or_insert_with(Vec::new).insert(0, reference);
+            }
+        }
+
+        for (name, property) in schema.properties.iter_mut().flatten() {
+            self.schema(property, &pointer::push(&pointer::push(path, "properties"), name));
+        }
+        if let Some(ref mut items) = schema.items {
+            self.schema(items, &pointer::push(path, "items"));
+        }
+        if let Some(ObjectOrReference::Object(ref mut additional)) = schema.additional_properties {
+            self.schema(additional, &pointer::push(path, "additionalProperties"));
+        }
+        for (index, part) in schema.all_of.iter_mut().flatten().enumerate() {
+            if let ObjectOrReference::Object(ref mut part) = *part {
+                self.schema(part, &pointer::push(&pointer::push(path, "allOf"), &index.to_string()));
+            }
+        }
+        for (index, part) in schema.one_of.iter_mut().flatten().enumerate() {
+            if let ObjectOrReference::Object(ref mut part) = *part {
+                self.schema(part, &pointer::push(&pointer::push(path, "oneOf"), &index.to_string()));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SPEC: &str = r##"
+openapi: 3.1.0
+info:
+  title: Webhook Example
+  version: 1.0.0
+  license:
+    name: Apache 2.0
+    identifier: Apache-2.0
+webhooks:
+  newPet:
+    post:
+      responses:
+        "200":
+          description: Return a 200 status to indicate that the data was received successfully
+components:
+  schemas:
+    Pet:
+      type: object
+      properties:
+        name:
+          type: [string, "null"]
+        kind:
+          const: dog
+        legs:
+          type: integer
+          const: 4
+        age:
+          type: integer
+          exclusiveMinimum: 0
+        owner:
+          $ref: "#/components/schemas/Owner"
+          description: The owner of the pet
+    Owner:
+      type: object
+"##;
+
+    #[test]
+    fn test_parse_and_downgrade() {
+        let spec: Spec = serde_yaml::from_str(SPEC).unwrap();
+        assert!(spec.is_openapi31());
+        assert!(spec.validate_version().is_ok());
+
+        let conversion = downgrade(&spec);
+        assert_eq!(conversion.spec.openapi, "3.0.3");
+        assert!(!conversion.spec.is_openapi31());
+        assert!(conversion.spec.webhooks.is_none());
+        assert_eq!(
+            conversion.lossy.iter().map(|l| l.path.as_str()).collect::<Vec<_>>(),
+            vec!["/info/license/identifier", "/webhooks", "/components/schemas/Pet/properties/legs/const"]
+        );
+
+        let schemas = conversion.spec.components.unwrap().schemas.unwrap();
+        let properties = match schemas["Pet"] {
+            ObjectOrReference::Object(ref pet) => pet.properties.clone().unwrap(),
+            _ => panic!("expected an inline schema"),
+        };
+        assert_eq!(properties["name"].schema_type, Some("string".into()));
+        assert_eq!(properties["name"].nullable, Some(true));
+        assert_eq!(properties["kind"].enum_values, Some(vec!["dog".to_string()]));
+        assert_eq!(properties["legs"].enum_values, None);
+        assert_eq!(properties["age"].minimum, Some(0.into()));
+        assert_eq!(properties["age"].exclusive_minimum, Some(ExclusiveBound::Flag(true)));
+        assert_eq!(properties["owner"].ref_path, None);
+        assert_eq!(properties["owner"].all_of.as_ref().unwrap().len(), 1);
+    }
+}
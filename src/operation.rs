@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
-use std::collections::BTreeMap;
-use crate::spec::{Callback, RequestBody, Response};
+use crate::Map;
+use crate::spec::{Callback, RequestBody, Response, SecurityRequirement};
 use crate::server::Server;
 use crate::components::ObjectOrReference;
 use crate::path::Parameter;
@@ -69,7 +69,7 @@ pub struct Operation {
     ///
     /// See [link]
     /// [link][https://github.com/OAI/OpenAPI-Specification/blob/master/versions/3.0.1.md#responsesObject]
-    pub responses: BTreeMap<String, Response>,
+    pub responses: Map<String, Response>,
 
     /// A map of possible out-of band callbacks related to the parent operation. The key is
     /// a unique identifier for the Callback Object. Each value in the map is a
@@ -79,26 +79,31 @@ pub struct Operation {
     /// an expression, evaluated at runtime, that identifies a URL to use for the
     /// callback operation.
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub callbacks: Option<BTreeMap<String, Callback>>,
+    pub callbacks: Option<Map<String, Callback>>,
 
     /// Declares this operation to be deprecated. Consumers SHOULD refrain from usage
     /// of the declared operation. Default value is `false`.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub deprecated: Option<bool>,
 
-    // FIXME: Implement
-    // /// A declaration of which security mechanisms can be used for this operation. The list of
-    // /// values includes alternative security requirement objects that can be used. Only one
-    // /// of the security requirement objects need to be satisfied to authorize a request.
-    // /// This definition overrides any declared top-level
-    // /// [`security`](https://github.com/OAI/OpenAPI-Specification/blob/master/versions/3.0.1.md#oasSecurity).
-    // /// To remove a top-level security declaration, an empty array can be used.
-    // pub security: Option<SecurityRequirement>,
+    /// A declaration of which security mechanisms can be used for this operation. The list of
+    /// values includes alternative security requirement objects that can be used. Only one
+    /// of the security requirement objects need to be satisfied to authorize a request.
+    /// This definition overrides any declared top-level
+    /// [`security`](https://github.com/OAI/OpenAPI-Specification/blob/master/versions/3.0.1.md#oasSecurity).
+    /// To remove a top-level security declaration, an empty array can be used.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub security: Option<Vec<SecurityRequirement>>,
+
     /// An alternative `server` array to service this operation. If an alternative `server`
     /// object is specified at the Path Item Object or Root level, it will be overridden by
     /// this value.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub servers: Option<Vec<Server>>,
+
+    /// `x-` extensions and the fields not described by the model, see `Map`.
+    #[serde(flatten)]
+    pub extensions: Map<String, serde_json::Value>,
 }
 
 impl Operation {
@@ -115,7 +120,7 @@ mod tests {
     #[test]
     fn test_to_client_request() {
         let mut builder = ClientRequest::build();
-        let respns: BTreeMap<String, Response> = BTreeMap::new();
+        let respns: Map<String, Response> = Map::new();
 
         let operation = Operation {
             tags: None,
@@ -128,7 +133,9 @@ mod tests {
             responses: respns,
             callbacks: None,
             deprecated: Some(false),
+            security: None,
             servers: None,
+            extensions: Map::new(),
         };
 
         match operation.to_client_request(builder).finish() {
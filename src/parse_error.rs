@@ -0,0 +1,219 @@
+//! Parse errors that point into the source document.
+//!
+//! A `ParseError` records the file, line, column and JSON pointer of the node that could
+//! not be parsed, and `render` prints the offending lines of the source for CI logs.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use serde::de::DeserializeOwned;
+use serde_path_to_error::Segment;
+
+use crate::pointer;
+use crate::Format;
+
+/// The number of lines shown above the failing line by `ParseError::render`.
+const CONTEXT_LINES: usize = 2;
+
+/// An error located in the source of a spec document.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParseError {
+    /// The file the document was loaded from, if any.
+    pub file: Option<PathBuf>,
+    /// One-based line of the failing node.
+    pub line: Option<usize>,
+    /// One-based column of the failing node.
+    pub column: Option<usize>,
+    /// JSON pointer to the failing node, e.g. `/paths/~1pets/get/responses/200`.
+    pub pointer: String,
+    /// The parser's message, without location information.
+    pub message: String,
+    source: Option<String>,
+}
+
+impl ParseError {
+    /// An error from a document that is not well-formed JSON.
+    pub fn from_json_syntax(error: &serde_json::Error, source: &[u8]) -> ParseError {
+        ParseError::new(json_location(error), "", &error.to_string(), source)
+    }
+
+    /// An error from a document that is not well-formed YAML.
+    pub fn from_yaml_syntax(error: &serde_yaml::Error, source: &[u8]) -> ParseError {
+        ParseError::new(yaml_location(error), "", &error.to_string(), source)
+    }
+
+    /// Deserialize `source` into `T` once more, tracking the path of the failing node.
+    ///
+    /// Used after deserializing the parsed document failed, as the parsed document no
+    /// longer knows where its nodes came from. `message` is reported if the source
+    /// unexpectedly deserializes.
+    pub fn locate<T>(source: &[u8], format: Format, message: &str) -> ParseError
+    where
+        T: DeserializeOwned,
+    {
+        match format {
+            Format::Json => {
+                let mut deserializer = serde_json::Deserializer::from_slice(source);
+                match serde_path_to_error::deserialize::<_, T>(&mut deserializer) {
+                    Ok(_) => ParseError::new(None, "", message, source),
+                    Err(err) => {
+                        let path = to_pointer(err.path());
+                        ParseError::new(json_location(err.inner()), &path, &err.inner().to_string(), source)
+                    }
+                }
+            }
+            Format::Yaml => {
+                let deserializer = serde_yaml::Deserializer::from_slice(source);
+                match serde_path_to_error::deserialize::<_, T>(deserializer) {
+                    Ok(_) => ParseError::new(None, "", message, source),
+                    Err(err) => {
+                        let path = to_pointer(err.path());
+                        ParseError::new(yaml_location(err.inner()), &path, &err.inner().to_string(), source)
+                    }
+                }
+            }
+        }
+    }
+
+    fn new(location: Option<(usize, usize)>, pointer: &str, message: &str, source: &[u8]) -> ParseError {
+        ParseError {
+            file: None,
+            line: location.map(|(line, _)| line),
+            column: location.map(|(_, column)| column),
+            pointer: pointer.to_string(),
+            message: strip_location(message).to_string(),
+            source: Some(String::from_utf8_lossy(source).into_owned()),
+        }
+    }
+
+    /// The same error, attributed to `file`.
+    pub fn with_file<P>(mut self, file: P) -> ParseError
+    where
+        P: AsRef<Path>,
+    {
+        self.file = Some(file.as_ref().to_path_buf());
+        self
+    }
+
+    /// A multi-line report with the offending source lines and a caret under the column.
+    pub fn render(&self) -> String {
+        let mut out = format!("error: {}\n", self.message);
+        out.push_str(&format!(" --> {}\n", self.location()));
+        if !self.pointer.is_empty() {
+            out.push_str(&format!("  = at {}\n", self.pointer));
+        }
+
+        let (source, line) = match (self.source.as_ref(), self.line) {
+            (Some(source), Some(line)) if line > 0 => (source, line),
+            _ => return out,
+        };
+        let lines: Vec<&str> = source.lines().collect();
+        if line > lines.len() {
+            return out;
+        }
+
+        let first = line.saturating_sub(CONTEXT_LINES).max(1);
+        let width = line.to_string().len();
+        out.push_str(&format!("{:width$} |\n", "", width = width));
+        for number in first..=line {
+            out.push_str(&format!("{:width$} | {}\n", number, lines[number - 1], width = width));
+        }
+        if let Some(column) = self.column.filter(|column| *column > 0) {
+            out.push_str(&format!("{:width$} | {:>column$}\n", "", "^", width = width, column = column));
+        }
+        out
+    }
+
+    /// `file:line:column`, leaving out whatever is unknown.
+    fn location(&self) -> String {
+        let mut location = match self.file {
+            Some(ref file) => file.display().to_string(),
+            None => "<input>".to_string(),
+        };
+        if let Some(line) = self.line {
+            location.push_str(&format!(":{}", line));
+            if let Some(column) = self.column {
+                location.push_str(&format!(":{}", column));
+            }
+        }
+        location
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.location(), self.message)?;
+        if !self.pointer.is_empty() {
+            write!(f, " (at {})", self.pointer)?;
+        }
+        Ok(())
+    }
+}
+
+fn json_location(error: &serde_json::Error) -> Option<(usize, usize)> {
+    if error.line() == 0 {
+        None
+    } else {
+        Some((error.line(), error.column()))
+    }
+}
+
+fn yaml_location(error: &serde_yaml::Error) -> Option<(usize, usize)> {
+    error.location().map(|location| (location.line(), location.column()))
+}
+
+fn to_pointer(path: &serde_path_to_error::Path) -> String {
+    path.iter().fold(String::new(), |path, segment| match *segment {
+        Segment::Seq { index } => pointer::push(&path, &index.to_string()),
+        Segment::Map { ref key } => pointer::push(&path, key),
+        Segment::Enum { ref variant } => pointer::push(&path, variant),
+        Segment::Unknown => pointer::push(&path, "?"),
+    })
+}
+
+/// Remove the ` at line X column Y` suffix that serde_json and serde_yaml append.
+fn strip_location(message: &str) -> &str {
+    match message.rfind(" at line ") {
+        Some(index) => &message[..index],
+        None => message,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spec::Spec;
+
+    const SPEC: &str = r#"openapi: 3.0.0
+info:
+  title: Broken
+  version: 1.0.0
+paths:
+  /pets:
+    get:
+      responses:
+        "200":
+          description: [1, 2]
+"#;
+
+    #[test]
+    fn test_locate_yaml() {
+        let error = ParseError::locate::<Spec>(SPEC.as_bytes(), Format::Yaml, "").with_file("broken.yaml");
+        assert_eq!(error.pointer, "/paths/~1pets/get/responses/200/description");
+        assert_eq!(error.line, Some(10));
+        assert!(error.to_string().starts_with("broken.yaml:10:"));
+
+        let rendered = error.render();
+        assert!(rendered.contains("10 |           description: [1, 2]"));
+        assert!(rendered.contains("= at /paths/~1pets/get/responses/200/description"));
+    }
+
+    #[test]
+    fn test_locate_json() {
+        let json = r#"{"openapi": "3.0.0", "info": {"title": 1, "version": "1.0.0"}, "paths": {}}"#;
+        let error = ParseError::locate::<Spec>(json.as_bytes(), Format::Json, "");
+        assert_eq!(error.pointer, "/info/title");
+        assert_eq!(error.line, Some(1));
+        assert!(!error.message.contains(" at line "));
+    }
+}
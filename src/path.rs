@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
 use crate::components::ObjectOrReference;
-use crate::spec::{Schema};
+use crate::Map;
+use crate::spec::{Example, Schema};
 use crate::server::Server;
 use crate::operation::{Operation};
 use actix_web::client::{ClientRequest, ClientRequestBuilder};
@@ -8,9 +9,14 @@ use actix_web::http::{Method};
 
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
-enum ParameterStyle {
+pub enum ParameterStyle {
+    Matrix,
+    Label,
     Form,
     Simple,
+    SpaceDelimited,
+    PipeDelimited,
+    DeepObject,
 }
 
 
@@ -24,29 +30,28 @@ enum ParameterStyle {
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Default)]
 pub struct Parameter {
     /// The name of the parameter.
-    name: String,
+    pub name: String,
     /// values depend on parameter type
     /// may be `header`, `query`, 'path`, `formData`
     #[serde(rename = "in")]
-    location: String,
+    pub location: String,
     #[serde(skip_serializing_if = "Option::is_none")]
-    required: Option<bool>,
+    pub required: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    schema: Option<Schema>,
+    pub schema: Option<Schema>,
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(rename = "uniqueItems")]
-    unique_items: Option<bool>,
+    pub unique_items: Option<bool>,
     /// string, number, boolean, integer, array, file ( only for formData )
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(rename = "type")]
-    param_type: Option<String>,
+    pub param_type: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    format: Option<String>,
+    pub format: Option<String>,
     /// A brief description of the parameter. This could contain examples
     /// of use.  GitHub Flavored Markdown is allowed.
     #[serde(skip_serializing_if = "Option::is_none")]
-    description: Option<String>,
-    // collectionFormat: ???
+    pub description: Option<String>,
     // default: ???
     // maximum ?
     // exclusiveMaximum ??
@@ -64,7 +69,229 @@ pub struct Parameter {
     /// value. Default values (based on value of in): for `query` - `form`; for `path` - `simple`; for
     /// `header` - `simple`; for cookie - `form`.
     #[serde(skip_serializing_if = "Option::is_none")]
-    style: Option<ParameterStyle>,
+    pub style: Option<ParameterStyle>,
+    /// When this is true, parameter values of type `array` or `object` generate separate
+    /// parameters for each value of the array or key-value pair of the map. When `style`
+    /// is `form`, the default value is `true`. For all other styles, the default value is `false`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub explode: Option<bool>,
+    /// Example of the parameter's potential value. The `example` field is mutually exclusive
+    /// of the `examples` field.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub example: Option<serde_json::Value>,
+    /// Examples of the parameter's potential value. The `examples` field is mutually
+    /// exclusive of the `example` field.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub examples: Option<Map<String, ObjectOrReference<Example>>>,
+
+    /// `x-` extensions and the fields not described by the model, see `Map`.
+    #[serde(flatten)]
+    pub extensions: Map<String, serde_json::Value>,
+}
+
+impl Parameter {
+    /// The style, defaulting to `form` for `query` and `cookie` parameters and to `simple`
+    /// for `path` and `header` parameters.
+    pub fn style_or_default(&self) -> ParameterStyle {
+        match (&self.style, self.location.as_str()) {
+            (Some(style), _) => style.clone(),
+            (None, "query") | (None, "cookie") => ParameterStyle::Form,
+            (None, _) => ParameterStyle::Simple,
+        }
+    }
+
+    /// Whether arrays and objects are exploded, by default only with the `form` style.
+    pub fn explode_or_default(&self) -> bool {
+        self.explode.unwrap_or(self.style_or_default() == ParameterStyle::Form)
+    }
+
+    /// Serialize `value` as the style of the parameter describes, into `(name, value)` pairs.
+    /// Values are not percent-encoded. `path` and `header` parameters serialize to a single
+    /// pair, whose value replaces the path template segment or is the header value.
+    pub fn serialize(&self, value: &serde_json::Value) -> Vec<(String, String)> {
+        let name = self.name.as_str();
+        let explode = self.explode_or_default();
+        let pair = |value: String| vec![(name.to_string(), value)];
+
+        if value.is_null() {
+            return Vec::new();
+        }
+        let entries: Vec<(String, String)> = match value {
+            serde_json::Value::Object(object) => object
+                .iter()
+                .filter(|(_, value)| !value.is_null())
+                .map(|(key, value)| (key.clone(), primitive(value)))
+                .collect(),
+            _ => Vec::new(),
+        };
+        let items: Vec<String> = match value {
+            serde_json::Value::Array(items) => items.iter().map(primitive).collect(),
+            serde_json::Value::Object(_) => Vec::new(),
+            value => vec![primitive(value)],
+        };
+        // key,value,key,value for objects that are not exploded
+        let flat = || {
+            entries
+                .iter()
+                .flat_map(|(key, value)| vec![key.clone(), value.clone()])
+                .collect::<Vec<_>>()
+        };
+        let assigned = |separator: &str| {
+            entries
+                .iter()
+                .map(|(key, value)| format!("{}={}", key, value))
+                .collect::<Vec<_>>()
+                .join(separator)
+        };
+        let is_object = value.is_object();
+        let is_array = value.is_array();
+
+        match self.style_or_default() {
+            ParameterStyle::Simple => match (is_object, explode) {
+                (true, true) => pair(assigned(",")),
+                (true, false) => pair(flat().join(",")),
+                (false, _) => pair(items.join(",")),
+            },
+            ParameterStyle::Label => match (is_object, explode) {
+                (true, true) => pair(format!(".{}", assigned("."))),
+                (true, false) => pair(format!(".{}", flat().join(","))),
+                (false, true) => pair(format!(".{}", items.join("."))),
+                (false, false) => pair(format!(".{}", items.join(","))),
+            },
+            ParameterStyle::Matrix => match (is_object, explode) {
+                (true, true) => pair(format!(";{}", assigned(";"))),
+                (true, false) => pair(format!(";{}={}", name, flat().join(","))),
+                (false, true) => pair(
+                    items
+                        .iter()
+                        .map(|item| format!(";{}={}", name, item))
+                        .collect::<String>(),
+                ),
+                (false, false) => pair(format!(";{}={}", name, items.join(","))),
+            },
+            ParameterStyle::DeepObject if is_object => entries
+                .into_iter()
+                .map(|(key, value)| (format!("{}[{}]", name, key), value))
+                .collect(),
+            ParameterStyle::SpaceDelimited if is_array && !explode => pair(items.join(" ")),
+            ParameterStyle::PipeDelimited if is_array && !explode => pair(items.join("|")),
+            _ => match (is_object, explode) {
+                (true, true) => entries,
+                (true, false) => pair(flat().join(",")),
+                (false, true) => items.into_iter().map(|item| (name.to_string(), item)).collect(),
+                (false, false) => pair(items.join(",")),
+            },
+        }
+    }
+
+    /// Deserialize the parameter from the `(name, value)` pairs of its location, as `serialize`
+    /// produces them, with one pair holding the path segment or header value for `path` and
+    /// `header` parameters. Whether the value is an array or an object is taken from the type
+    /// of the schema. Primitives are left as strings. `None` if the parameter is absent.
+    pub fn deserialize(&self, pairs: &[(String, String)]) -> Option<serde_json::Value> {
+        use serde_json::Value;
+
+        let name = self.name.as_str();
+        let explode = self.explode_or_default();
+        let kind = self.schema.as_ref().and_then(|schema| schema.schema_type.as_ref()).map(|schema_type| {
+            let types = schema_type.types();
+            if types.contains(&"array") {
+                "array"
+            } else if types.contains(&"object") {
+                "object"
+            } else {
+                "primitive"
+            }
+        });
+        let kind = kind.unwrap_or("primitive");
+        let values: Vec<&str> = pairs
+            .iter()
+            .filter(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+            .collect();
+        let first = values.first().cloned();
+
+        let string = |value: &str| Value::String(value.to_string());
+        let split = |value: &'_ str, separator: char| -> Vec<String> {
+            match (kind, value) {
+                (_, "") => Vec::new(),
+                ("primitive", value) => vec![value.to_string()],
+                (_, value) => value.split(separator).map(str::to_string).collect(),
+            }
+        };
+        // key=value items, or key,value,key,value items, into an object
+        let object = |items: &[String], assigned: bool| -> Value {
+            let mut object = serde_json::Map::new();
+            if assigned {
+                for item in items {
+                    let mut parts = item.splitn(2, '=');
+                    let key = parts.next().unwrap_or("");
+                    object.insert(key.to_string(), string(parts.next().unwrap_or("")));
+                }
+            } else {
+                for chunk in items.chunks(2) {
+                    object.insert(chunk[0].clone(), string(chunk.get(1).map(String::as_str).unwrap_or("")));
+                }
+            }
+            Value::Object(object)
+        };
+        let shape = |items: Vec<String>, assigned: bool| match kind {
+            "array" => Value::Array(items.iter().map(|item| string(item)).collect()),
+            "object" => object(&items, assigned),
+            _ => string(&items.concat()),
+        };
+
+        match self.style_or_default() {
+            ParameterStyle::Simple => first.map(|value| shape(split(value, ','), explode)),
+            ParameterStyle::Label => {
+                let value = first?.trim_start_matches('.');
+                let separator = if explode { '.' } else { ',' };
+                Some(shape(split(value, separator), explode))
+            }
+            ParameterStyle::Matrix => {
+                let parts: Vec<&str> = first?.split(';').filter(|part| !part.is_empty()).collect();
+                let prefix = format!("{}=", name);
+                let unprefixed = |part: &&str| part.trim_start_matches(prefix.as_str()).to_string();
+                match (kind, explode) {
+                    ("object", true) => Some(object(&parts.iter().map(|part| part.to_string()).collect::<Vec<_>>(), true)),
+                    ("array", true) => Some(shape(parts.iter().map(unprefixed).collect(), false)),
+                    _ => parts.first().map(|part| shape(split(&unprefixed(part), ','), false)),
+                }
+            }
+            ParameterStyle::DeepObject => {
+                let prefix = format!("{}[", name);
+                let entries: Vec<String> = pairs
+                    .iter()
+                    .filter(|(key, _)| key.starts_with(prefix.as_str()) && key.ends_with(']'))
+                    .map(|(key, value)| format!("{}={}", &key[prefix.len()..key.len() - 1], value))
+                    .collect();
+                if entries.is_empty() {
+                    None
+                } else {
+                    Some(object(&entries, true))
+                }
+            }
+            ParameterStyle::SpaceDelimited if kind == "array" && !explode => first.map(|value| shape(split(value, ' '), false)),
+            ParameterStyle::PipeDelimited if kind == "array" && !explode => first.map(|value| shape(split(value, '|'), false)),
+            _ => match (kind, explode) {
+                ("object", true) if !pairs.is_empty() => {
+                    Some(object(&pairs.iter().map(|(key, value)| format!("{}={}", key, value)).collect::<Vec<_>>(), true))
+                }
+                ("array", true) if !values.is_empty() => Some(shape(values.iter().map(|value| value.to_string()).collect(), false)),
+                ("object", true) | ("array", true) => None,
+                _ => first.map(|value| shape(split(value, ','), false)),
+            },
+        }
+    }
+}
+
+/// A primitive value as it appears in a serialized parameter; nested values stay JSON.
+fn primitive(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(value) => value.clone(),
+        serde_json::Value::Null => String::new(),
+        value => value.to_string(),
+    }
 }
 
 /// Describes the operations available on a single path.
@@ -133,10 +360,73 @@ pub struct PathItem {
     /// [OpenAPI Object's components/parameters](https://github.com/OAI/OpenAPI-Specification/blob/master/versions/3.0.1.md#componentsParameters).
     #[serde(skip_serializing_if = "Option::is_none")]
     pub parameters: Option<Vec<ObjectOrReference<Parameter>>>,
-    // TODO: Add "Specification Extensions" https://github.com/OAI/OpenAPI-Specification/blob/master/versions/3.0.1.md#specificationExtensions}
+
+    /// `x-` extensions and the fields not described by the model, see `Map`.
+    #[serde(flatten)]
+    pub extensions: Map<String, serde_json::Value>,
 }
 
 impl PathItem {
+    /// The operations defined on this path, in the order of the fields of the Path Item Object.
+    pub fn operations(&self) -> Vec<(Method, &Operation)> {
+        vec![
+            (Method::GET, &self.get),
+            (Method::PUT, &self.put),
+            (Method::POST, &self.post),
+            (Method::DELETE, &self.delete),
+            (Method::OPTIONS, &self.options),
+            (Method::HEAD, &self.head),
+            (Method::PATCH, &self.patch),
+            (Method::TRACE, &self.trace),
+        ]
+        .into_iter()
+        .filter_map(|(method, operation)| operation.as_ref().map(|operation| (method, operation)))
+        .collect()
+    }
+
+    /// The operations defined on this path, mutably, in the same order as `operations`.
+    pub fn operations_mut(&mut self) -> Vec<(Method, &mut Operation)> {
+        vec![
+            (Method::GET, &mut self.get),
+            (Method::PUT, &mut self.put),
+            (Method::POST, &mut self.post),
+            (Method::DELETE, &mut self.delete),
+            (Method::OPTIONS, &mut self.options),
+            (Method::HEAD, &mut self.head),
+            (Method::PATCH, &mut self.patch),
+            (Method::TRACE, &mut self.trace),
+        ]
+        .into_iter()
+        .filter_map(|(method, operation)| operation.as_mut().map(|operation| (method, operation)))
+        .collect()
+    }
+
+    /// Remove the operations for which `keep` is false.
+    pub fn retain_operations<F>(&mut self, mut keep: F)
+    where
+        F: FnMut(&Method, &Operation) -> bool,
+    {
+        let operations = vec![
+            (Method::GET, &mut self.get),
+            (Method::PUT, &mut self.put),
+            (Method::POST, &mut self.post),
+            (Method::DELETE, &mut self.delete),
+            (Method::OPTIONS, &mut self.options),
+            (Method::HEAD, &mut self.head),
+            (Method::PATCH, &mut self.patch),
+            (Method::TRACE, &mut self.trace),
+        ];
+        for (method, operation) in operations {
+            let dropped = match operation {
+                Some(ref operation) => !keep(&method, operation),
+                None => false,
+            };
+            if dropped {
+                *operation = None;
+            }
+        }
+    }
+
     fn apply_operation(&self, method: Method, servers: Vec<Server>) -> Vec<ClientRequestBuilder> {
         let mut builders: Vec<ClientRequestBuilder> = Vec::new();
 
@@ -154,10 +444,10 @@ impl PathItem {
     // parameter is Vec<Server> which is then iterated and applied to every path
     pub fn to_client_request(&self, servers: Vec<Server>) -> Vec<ClientRequestBuilder> {
         let mut builders: Vec<ClientRequestBuilder> = Vec::new();
-        let lst_methods:Vec<Method> = Vec::new();
+        let mut lst_methods:Vec<Method> = Vec::new();
         
         if self.get.is_some() {
-            builders.append(&self.apply_operation(Method::GET, servers));
+            builders.append(&mut self.apply_operation(Method::GET, servers.clone()));
         }
 
         if self.put.is_some() {
@@ -207,7 +497,6 @@ impl PathItem {
 mod tests {
     use super::*;
     use crate::operation::Operation;
-    use std::collections::BTreeMap;
     use crate::spec::{Response};
     
     #[test]
@@ -218,6 +507,7 @@ mod tests {
                 url: "http://localhost:8000/v1".to_string(),
                 description: None,
                 variables: None,
+                extensions: Map::new(),
             });
 
         let paths = PathItem {
@@ -234,6 +524,7 @@ mod tests {
             trace: None,
             servers: None,
             parameters: None,
+            extensions: Map::new(),
         };
 
         for mut path in paths.to_client_request(servers) {
@@ -246,7 +537,7 @@ mod tests {
 
     #[test]
     fn test_to_client_request_multi() {
-        let respns: BTreeMap<String, Response> = BTreeMap::new();
+        let respns: Map<String, Response> = Map::new();
 
         let mut servers:Vec<Server> = Vec::new();
         servers.push(
@@ -254,6 +545,7 @@ mod tests {
                 url: "http://localhost:8000/v1".to_string(),
                 description: None,
                 variables: None,
+                extensions: Map::new(),
             });
 
         let get_oper = Operation {
@@ -267,7 +559,9 @@ mod tests {
             responses: respns.clone(),
             callbacks: None,
             deprecated: Some(false),
+            security: None,
             servers: None,
+            extensions: Map::new(),
         };
         let post_oper = Operation {
             tags: None,
@@ -280,7 +574,9 @@ mod tests {
             responses: respns,
             callbacks: None,
             deprecated: Some(false),
+            security: None,
             servers: None,
+            extensions: Map::new(),
         };
 
         let paths = PathItem {
@@ -297,6 +593,7 @@ mod tests {
             trace: None,
             servers: None,
             parameters: None,
+            extensions: Map::new(),
         };
 
         let clients = paths.to_client_request(servers);
@@ -312,4 +609,88 @@ mod tests {
             assert_eq!(client.finish().unwrap().uri().path(), "/v1");
         }
     }
+    #[test]
+    fn test_serialize_parameter() {
+        let parameter = |location: &str, style: Option<ParameterStyle>, explode: Option<bool>| Parameter {
+            name: "id".to_string(),
+            location: location.to_string(),
+            style,
+            explode,
+            ..Default::default()
+        };
+        let pairs = |values: &[(&str, &str)]| -> Vec<(String, String)> {
+            values.iter().map(|(name, value)| (name.to_string(), value.to_string())).collect()
+        };
+        let array = serde_json::json!([3, 4, 5]);
+        let object = serde_json::json!({"name": "Alex", "role": "admin"});
+
+        assert_eq!(parameter("path", None, None).serialize(&serde_json::json!(5)), pairs(&[("id", "5")]));
+        assert_eq!(parameter("path", None, None).serialize(&array), pairs(&[("id", "3,4,5")]));
+        assert_eq!(
+            parameter("path", Some(ParameterStyle::Label), Some(true)).serialize(&array),
+            pairs(&[("id", ".3.4.5")])
+        );
+        assert_eq!(
+            parameter("path", Some(ParameterStyle::Matrix), Some(true)).serialize(&array),
+            pairs(&[("id", ";id=3;id=4;id=5")])
+        );
+        assert_eq!(
+            parameter("query", None, None).serialize(&array),
+            pairs(&[("id", "3"), ("id", "4"), ("id", "5")])
+        );
+        assert_eq!(parameter("query", None, Some(false)).serialize(&array), pairs(&[("id", "3,4,5")]));
+        assert_eq!(
+            parameter("query", Some(ParameterStyle::PipeDelimited), Some(false)).serialize(&array),
+            pairs(&[("id", "3|4|5")])
+        );
+        assert_eq!(
+            parameter("query", Some(ParameterStyle::DeepObject), Some(true)).serialize(&object),
+            pairs(&[("id[name]", "Alex"), ("id[role]", "admin")])
+        );
+        assert_eq!(parameter("header", None, Some(true)).serialize(&object), pairs(&[("id", "name=Alex,role=admin")]));
+        assert!(parameter("query", None, None).serialize(&serde_json::Value::Null).is_empty());
+    }
+
+    #[test]
+    fn test_deserialize_parameter() {
+        let parameter = |location: &str, style: Option<ParameterStyle>, explode: Option<bool>, kind: &str| Parameter {
+            name: "id".to_string(),
+            location: location.to_string(),
+            schema: Some(Schema {
+                schema_type: Some(kind.into()),
+                ..Default::default()
+            }),
+            style,
+            explode,
+            ..Default::default()
+        };
+        let array = serde_json::json!(["3", "4", "5"]);
+        let object = serde_json::json!({"name": "Alex", "role": "admin"});
+        let styles = vec![
+            ("path", None, None),
+            ("path", Some(ParameterStyle::Label), Some(true)),
+            ("path", Some(ParameterStyle::Label), Some(false)),
+            ("path", Some(ParameterStyle::Matrix), Some(true)),
+            ("path", Some(ParameterStyle::Matrix), Some(false)),
+            ("header", None, Some(true)),
+            ("query", None, None),
+            ("query", None, Some(false)),
+        ];
+
+        for (location, style, explode) in styles {
+            for (kind, value) in vec![("array", &array), ("object", &object)] {
+                let parameter = parameter(location, style.clone(), explode, kind);
+                assert_eq!(parameter.deserialize(&parameter.serialize(value)).as_ref(), Some(value));
+            }
+            let parameter = parameter(location, style.clone(), explode, "integer");
+            let value = serde_json::json!("5");
+            assert_eq!(parameter.deserialize(&parameter.serialize(&value)), Some(value));
+        }
+
+        let deep = parameter("query", Some(ParameterStyle::DeepObject), Some(true), "object");
+        assert_eq!(deep.deserialize(&deep.serialize(&object)), Some(object));
+        let piped = parameter("query", Some(ParameterStyle::PipeDelimited), Some(false), "array");
+        assert_eq!(piped.deserialize(&piped.serialize(&array)), Some(array));
+        assert_eq!(parameter("query", None, None, "array").deserialize(&[]), None);
+    }
 }
\ No newline at end of file
@@ -0,0 +1,38 @@
+//! [JSON Pointer](https://tools.ietf.org/html/rfc6901) helpers used to report locations
+//! within a spec document.
+
+/// Escape a single reference token, e.g. `/pets` becomes `~1pets`.
+pub fn escape(token: &str) -> String {
+    token.replace('~', "~0").replace('/', "~1")
+}
+
+/// Unescape a single reference token, e.g. `~1pets` becomes `/pets`.
+pub fn unescape(token: &str) -> String {
+    token.replace("~1", "/").replace("~0", "~")
+}
+
+/// Append a reference token to a pointer.
+pub fn push(pointer: &str, token: &str) -> String {
+    format!("{}/{}", pointer, escape(token))
+}
+
+/// Split a pointer into its unescaped reference tokens.
+pub fn tokens(pointer: &str) -> Vec<String> {
+    pointer
+        .split('/')
+        .skip(1)
+        .map(unescape)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_push() {
+        let pointer = push(&push("", "paths"), "/pets/{id}");
+        assert_eq!(pointer, "/paths/~1pets~1{id}");
+        assert_eq!(tokens(&pointer), vec!["paths", "/pets/{id}"]);
+    }
+}
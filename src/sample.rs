@@ -0,0 +1,404 @@
+//! Generate sample data for a `Schema`.
+//!
+//! Values honor the schema's `example`, `examples`, `const`, `default` and `enum`, well known string formats,
+//! numeric, string and array bounds, merge `allOf` parts and pick one of the `oneOf` parts. The generator
+//! is seeded, so the same seed always produces the same output.
+
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use serde_json::{Map, Number, Value};
+
+use crate::components::ObjectOrReference;
+use crate::spec::{Schema, Spec};
+
+const WORDS: &[&str] = &[
+    "alpha", "bravo", "charlie", "delta", "echo", "foxtrot", "golf", "hotel", "india", "juliet",
+];
+const MAX_DEPTH: usize = 8;
+
+/// Produces `serde_json::Value`s matching the schemas of a spec.
+pub struct Generator<'a> {
+    spec: &'a Spec,
+    rng: StdRng,
+    optional_probability: f64,
+}
+
+impl<'a> Generator<'a> {
+    /// A generator seeded with `0`.
+    pub fn new(spec: &'a Spec) -> Generator<'a> {
+        Generator {
+            spec,
+            rng: StdRng::seed_from_u64(0),
+            optional_probability: 0.5,
+        }
+    }
+
+    pub fn seed(mut self, seed: u64) -> Generator<'a> {
+        self.rng = StdRng::seed_from_u64(seed);
+        self
+    }
+
+    /// The probability that an optional property is included in a generated object.
+    /// Required properties are always included.
+    pub fn optional_probability(mut self, probability: f64) -> Generator<'a> {
+        self.optional_probability = probability.max(0.0).min(1.0);
+        self
+    }
+
+    pub fn generate(&mut self, schema: &Schema) -> Value {
+        self.value(schema, 0)
+    }
+
+    fn value(&mut self, schema: &Schema, depth: usize) -> Value {
+        let spec = self.spec;
+        let schema = spec.resolve_schema(schema);
+
+        if let Some(ref example) = schema.example {
+            return example.clone();
+        }
+        if let Some(example) = schema.examples.as_ref().and_then(|examples| examples.first()) {
+            return example.clone();
+        }
+        if let Some(ref value) = schema.const_value {
+            return value.clone();
+        }
+        if let Some(ref default) = schema.default {
+            return default.clone();
+        }
+        if depth > MAX_DEPTH {
+            return Value::Null;
+        }
+        if let Some(ref values) = schema.enum_values {
+            if let Some(value) = values.choose(&mut self.rng) {
+                return enum_value(schema, value);
+            }
+        }
+        if schema.all_of.is_some() {
+            return self.all_of(schema, depth);
+        }
+        if let Some(part) = schema.one_of.as_ref().and_then(|one_of| one_of.choose(&mut self.rng)) {
+            let schemas = spec.components.as_ref().and_then(|c| c.schemas.as_ref());
+            if let Some(part) = part.resolve(schemas) {
+                return self.value(part, depth + 1);
+            }
+        }
+
+        match schema_type(schema) {
+            "object" => self.object(schema, depth),
+            "array" => self.array(schema, depth),
+            "integer" => self.integer(schema),
+            "number" => self.number(schema),
+            "boolean" => Value::Bool(self.rng.gen()),
+            "string" => Value::String(self.string(schema)),
+            _ => Value::Null,
+        }
+    }
+
+    /// Merge the objects generated for every `allOf` part with the schema's own properties.
+    fn all_of(&mut self, schema: &Schema, depth: usize) -> Value {
+        let spec = self.spec;
+        let schemas = spec.components.as_ref().and_then(|c| c.schemas.as_ref());
+        let mut merged = Map::new();
+
+        for part in schema.all_of.iter().flatten() {
+            if let Some(part) = part.resolve(schemas) {
+                match self.value(part, depth + 1) {
+                    Value::Object(map) => merged.extend(map),
+                    other => return other,
+                }
+            }
+        }
+        if let Value::Object(map) = self.object(schema, depth) {
+            merged.extend(map);
+        }
+
+        Value::Object(merged)
+    }
+
+    fn object(&mut self, schema: &Schema, depth: usize) -> Value {
+        let required = schema.required.clone().unwrap_or_default();
+        let mut map = Map::new();
+
+        for (name, property) in schema.properties.iter().flatten() {
+            if required.contains(name) || self.rng.gen_bool(self.optional_probability) {
+                map.insert(name.clone(), self.value(property, depth + 1));
+            }
+        }
+
+        if let Some(ObjectOrReference::Object(ref additional)) = schema.additional_properties {
+            if map.is_empty() {
+                map.insert(self.word(), self.value(additional, depth + 1));
+            }
+        }
+
+        Value::Object(map)
+    }
+
+    fn array(&mut self, schema: &Schema, depth: usize) -> Value {
+        let min = schema.min_items.unwrap_or(1).min(schema.max_items.unwrap_or(u64::MAX));
+        let max = schema.max_items.unwrap_or(min + 2).max(min);
+        let len = self.rng.gen_range(min, max + 1);
+
+        match schema.items {
+            Some(ref items) => Value::Array((0..len).map(|_| self.value(items, depth + 1)).collect()),
+            None => Value::Array(Vec::new()),
+        }
+    }
+
+    fn integer(&mut self, schema: &Schema) -> Value {
+        let min = schema.minimum_bound().map(|(n, exclusive)| {
+            if exclusive && n.fract() == 0.0 { n as i64 + 1 } else { n.ceil() as i64 }
+        });
+        let max = schema.maximum_bound().map(|(n, exclusive)| {
+            if exclusive && n.fract() == 0.0 { n as i64 - 1 } else { n.floor() as i64 }
+        });
+        let (min, max) = bounds(min, max, 100);
+        let mut value = self.rng.gen_range(min, max + 1);
+
+        if let Some(step) = schema.multiple_of.as_ref().and_then(Number::as_f64).and_then(whole_step) {
+            if step > 0 {
+                value = value - value.rem_euclid(step);
+                if value < min {
+                    value += step;
+                }
+            }
+        }
+
+        Value::from(value)
+    }
+
+    fn number(&mut self, schema: &Schema) -> Value {
+        let lower = schema.minimum_bound();
+        let upper = schema.maximum_bound();
+        let (min, max) = match (lower, upper) {
+            (Some((min, _)), Some((max, _))) => (min, max.max(min)),
+            (Some((min, _)), None) => (min, min + 100.0),
+            (None, Some((max, _))) => (max - 100.0, max),
+            (None, None) => (0.0, 100.0),
+        };
+        let exclusive_min = lower.map(|(_, exclusive)| exclusive).unwrap_or(false);
+        let exclusive_max = upper.map(|(_, exclusive)| exclusive).unwrap_or(false);
+
+        if let Some(step) = schema.multiple_of.as_ref().and_then(Number::as_f64).filter(|step| *step > 0.0) {
+            // pick a multiple within the bounds
+            let mut first = (min / step).ceil();
+            if exclusive_min && first * step <= min {
+                first += 1.0;
+            }
+            let mut last = (max / step).floor();
+            if exclusive_max && last * step >= max {
+                last -= 1.0;
+            }
+            if first <= last {
+                let multiple = self.rng.gen_range(first as i64, last as i64 + 1) as f64;
+                return Value::from(multiple * step);
+            }
+        }
+
+        let within = |value: f64| {
+            (value > min || !exclusive_min && value >= min) && (value < max || !exclusive_max && value <= max)
+        };
+        let value = if max > min {
+            (self.rng.gen_range(min, max) * 100.0).round() / 100.0
+        } else {
+            min
+        };
+        // rounding may land on, or past, a bound
+        Value::from(if within(value) { value } else { (min + max) / 2.0 })
+    }
+
+    fn string(&mut self, schema: &Schema) -> String {
+        let value = match schema.format.as_ref().map(String::as_str) {
+            Some("date") => format!("2019-{:02}-{:02}", self.rng.gen_range(1, 13), self.rng.gen_range(1, 29)),
+            Some("date-time") => format!(
+                "2019-{:02}-{:02}T{:02}:{:02}:00Z",
+                self.rng.gen_range(1, 13),
+                self.rng.gen_range(1, 29),
+                self.rng.gen_range(0, 24),
+                self.rng.gen_range(0, 60)
+            ),
+            Some("uuid") => {
+                let bytes: [u8; 16] = self.rng.gen();
+                let hex: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+                format!("{}-{}-4{}-a{}-{}", &hex[0..8], &hex[8..12], &hex[13..16], &hex[17..20], &hex[20..32])
+            }
+            Some("email") => format!("{}@example.com", self.word()),
+            Some("uri") | Some("url") => format!("https://example.com/{}", self.word()),
+            Some("hostname") => format!("{}.example.com", self.word()),
+            Some("ipv4") => format!("192.0.2.{}", self.rng.gen_range(1, 255)),
+            Some("ipv6") => format!("2001:db8::{:x}", self.rng.gen_range(1, 0xffff)),
+            Some("byte") => "c2FtcGxl".to_string(),
+            Some("password") => "********".to_string(),
+            _ => self.word(),
+        };
+
+        fit_length(value, schema.min_length, schema.max_length)
+    }
+
+    fn word(&mut self) -> String {
+        WORDS.choose(&mut self.rng).unwrap_or(&"sample").to_string()
+    }
+}
+
+/// The declared type of a schema, or the one implied by its keywords.
+fn schema_type(schema: &Schema) -> &str {
+    match schema.type_name() {
+        Some(schema_type) => schema_type,
+        None if schema.properties.is_some() || schema.additional_properties.is_some() => "object",
+        None if schema.items.is_some() => "array",
+        None => "",
+    }
+}
+
+/// Convert an `enum` entry, which is held as a string, to the schema's type.
+fn enum_value(schema: &Schema, value: &str) -> Value {
+    match schema_type(schema) {
+        "integer" | "number" | "boolean" => serde_json::from_str(value).unwrap_or_else(|_| Value::from(value)),
+        _ => Value::from(value),
+    }
+}
+
+/// The smallest whole multiple of a `multipleOf` step, e.g. 5 for 2.5, as integers are
+/// multiples of it. Steps without one in their first thousand multiples are not followed.
+fn whole_step(step: f64) -> Option<i64> {
+    if step <= 0.0 {
+        return None;
+    }
+    (1..=1000)
+        .map(|n| n as f64 * step)
+        .find(|multiple| (multiple - multiple.round()).abs() < 1e-9)
+        .map(|multiple| multiple.round() as i64)
+}
+
+fn bounds(min: Option<i64>, max: Option<i64>, span: i64) -> (i64, i64) {
+    match (min, max) {
+        (Some(min), Some(max)) => (min, max.max(min)),
+        (Some(min), None) => (min, min + span),
+        (None, Some(max)) => (max - span, max),
+        (None, None) => (0, span),
+    }
+}
+
+fn fit_length(mut value: String, min: Option<u64>, max: Option<u64>) -> String {
+    let min = min.unwrap_or(0) as usize;
+    while value.chars().count() < min {
+        value.push('x');
+    }
+    if let Some(max) = max {
+        value = value.chars().take(max as usize).collect();
+    }
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spec::ExclusiveBound;
+    use crate::validation;
+
+    #[test]
+    fn test_generate_is_deterministic() {
+        let spec = crate::from_path("./data/v3.0/petstore-exanded.yaml").unwrap();
+        let schema = Schema {
+            ref_path: Some("#/components/schemas/Pet".to_string()),
+            ..Default::default()
+        };
+
+        let first = Generator::new(&spec).seed(7).generate(&schema);
+        let second = Generator::new(&spec).seed(7).generate(&schema);
+        assert_eq!(first, second);
+        assert!(validation::validate(&spec, &schema, "", &first).is_empty());
+    }
+
+    #[test]
+    fn test_generate_bounds() {
+        let spec = Spec::default();
+        let schema = Schema {
+            schema_type: Some("integer".into()),
+            minimum: Some(Number::from(10)),
+            maximum: Some(Number::from(20)),
+            multiple_of: Some(Number::from(5)),
+            ..Default::default()
+        };
+
+        for seed in 0..20 {
+            let value = Generator::new(&spec).seed(seed).generate(&schema).as_i64().unwrap();
+            assert!(value >= 10 && value <= 20 && value % 5 == 0);
+        }
+    }
+
+    #[test]
+    fn test_generate_integer_fractional_step() {
+        let spec = Spec::default();
+        let schema = Schema {
+            schema_type: Some("integer".into()),
+            multiple_of: Some(Number::from_f64(2.5).unwrap()),
+            ..Default::default()
+        };
+
+        for seed in 0..20 {
+            let value = Generator::new(&spec).seed(seed).generate(&schema);
+            assert_eq!(value.as_i64().unwrap() % 5, 0);
+            assert!(validation::validate(&spec, &schema, "", &value).is_empty());
+        }
+    }
+
+    #[test]
+    fn test_generate_number_bounds() {
+        let spec = Spec::default();
+        let schema = Schema {
+            schema_type: Some("number".into()),
+            minimum: Some(Number::from(0)),
+            exclusive_minimum: Some(ExclusiveBound::Flag(true)),
+            maximum: Some(Number::from(1)),
+            exclusive_maximum: Some(ExclusiveBound::Flag(true)),
+            ..Default::default()
+        };
+        for seed in 0..50 {
+            let value = Generator::new(&spec).seed(seed).generate(&schema).as_f64().unwrap();
+            assert!(value > 0.0 && value < 1.0, "{}", value);
+        }
+
+        let schema = Schema {
+            schema_type: Some("number".into()),
+            minimum: Some(Number::from(10)),
+            exclusive_minimum: Some(ExclusiveBound::Flag(true)),
+            maximum: Some(Number::from(12)),
+            multiple_of: Some(Number::from_f64(0.5).unwrap()),
+            ..Default::default()
+        };
+        for seed in 0..50 {
+            let value = Generator::new(&spec).seed(seed).generate(&schema).as_f64().unwrap();
+            assert!(value > 10.0 && value <= 12.0 && (value * 2.0).fract() == 0.0, "{}", value);
+        }
+    }
+
+    #[test]
+    fn test_generate_empty_array() {
+        let spec = Spec::default();
+        let schema = Schema {
+            schema_type: Some("array".into()),
+            items: Some(Box::new(Schema {
+                schema_type: Some("string".into()),
+                ..Default::default()
+            })),
+            max_items: Some(0),
+            ..Default::default()
+        };
+        assert_eq!(Generator::new(&spec).generate(&schema), Value::Array(Vec::new()));
+    }
+
+    #[test]
+    fn test_generate_formats() {
+        let spec = Spec::default();
+        let schema = Schema {
+            schema_type: Some("string".into()),
+            format: Some("uuid".to_string()),
+            ..Default::default()
+        };
+
+        let value = Generator::new(&spec).generate(&schema);
+        assert_eq!(value.as_str().unwrap().len(), 36);
+    }
+}
@@ -0,0 +1,479 @@
+//! Describe Rust types as `Schema`s.
+//!
+//! `OpenApiSchema` is implemented for the primitive and standard library types, and can be
+//! derived for structs and enums with the `derive` feature. Named types register their
+//! schema in a `Schemas` map, which becomes `components.schemas`, and are referenced from
+//! elsewhere with a `$ref`.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::ops::{Deref, DerefMut};
+
+use serde_json::{Number, Value};
+
+use crate::components::ObjectOrReference;
+use crate::spec::Schema;
+use crate::Map;
+
+/// The named schemas collected while describing types, keyed by schema name.
+///
+/// Derefs to the map of schemas. The type registered under each name is recorded, so that
+/// two types of the same name, e.g. in different modules, are reported by `collisions`
+/// instead of sharing one schema.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Schemas {
+    schemas: Map<String, ObjectOrReference<Schema>>,
+    types: Map<String, &'static str>,
+    collisions: Vec<String>,
+}
+
+impl Schemas {
+    pub fn new() -> Schemas {
+        Schemas::default()
+    }
+
+    /// The schema names registered by more than one type, as messages.
+    pub fn collisions(&self) -> &[String] {
+        &self.collisions
+    }
+
+    pub fn into_map(self) -> Map<String, ObjectOrReference<Schema>> {
+        self.schemas
+    }
+
+    /// Record that `type_name` is registered as `name`. Returns whether the name is new, in
+    /// which case the schema is still to be described.
+    fn register(&mut self, name: &str, type_name: &'static str) -> bool {
+        match self.types.get(name) {
+            Some(registered) if *registered == type_name => false,
+            Some(registered) => {
+                let collision = format!(
+                    "schema `{}` is registered by both `{}` and `{}`; rename one of them with #[openapi(rename = \"...\")]",
+                    name, registered, type_name
+                );
+                if !self.collisions.contains(&collision) {
+                    self.collisions.push(collision);
+                }
+                false
+            }
+            None => {
+                self.types.insert(name.to_string(), type_name);
+                !self.schemas.contains_key(name)
+            }
+        }
+    }
+}
+
+impl Deref for Schemas {
+    type Target = Map<String, ObjectOrReference<Schema>>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.schemas
+    }
+}
+
+impl DerefMut for Schemas {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.schemas
+    }
+}
+
+/// A Rust type with an OpenApi schema.
+pub trait OpenApiSchema {
+    /// The name under which the type is registered in `components.schemas`. Types without a
+    /// name are always inlined.
+    fn schema_name() -> Option<String> {
+        None
+    }
+
+    /// The type registered under `schema_name`, to tell apart types of the same name.
+    fn schema_type_name() -> &'static str {
+        std::any::type_name::<Self>()
+    }
+
+    /// The schema of the type itself. Nested named types are registered in `schemas`.
+    fn schema(schemas: &mut Schemas) -> Schema;
+
+    /// Whether a property of this type may be left out of an object.
+    fn is_optional() -> bool {
+        false
+    }
+
+    /// The schema to use where the type appears: a `$ref` to the registered schema for
+    /// named types, the inline schema otherwise.
+    fn schema_ref(schemas: &mut Schemas) -> Schema {
+        let name = match Self::schema_name() {
+            Some(name) => name,
+            None => return Self::schema(schemas),
+        };
+        if schemas.register(&name, Self::schema_type_name()) {
+            // register a placeholder first so recursive types terminate
+            schemas.insert(name.clone(), ObjectOrReference::Object(Schema::default()));
+            let schema = Self::schema(schemas);
+            schemas.insert(name.clone(), ObjectOrReference::Object(schema));
+        }
+        Schema {
+            ref_path: Some(format!("#/components/schemas/{}", name)),
+            ..Default::default()
+        }
+    }
+}
+
+fn typed(schema_type: &str, format: Option<&str>) -> Schema {
+    Schema {
+        schema_type: Some(schema_type.into()),
+        format: format.map(str::to_string),
+        ..Default::default()
+    }
+}
+
+macro_rules! impl_schema {
+    ($($ty:ty => $schema_type:expr, $format:expr;)*) => {
+        $(
+            impl OpenApiSchema for $ty {
+                fn schema(_: &mut Schemas) -> Schema {
+                    typed($schema_type, $format)
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_unsigned_schema {
+    ($($ty:ty => $format:expr;)*) => {
+        $(
+            impl OpenApiSchema for $ty {
+                fn schema(_: &mut Schemas) -> Schema {
+                    Schema {
+                        minimum: Some(Number::from(0)),
+                        ..typed("integer", $format)
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_schema! {
+    i8 => "integer", Some("int32");
+    i16 => "integer", Some("int32");
+    i32 => "integer", Some("int32");
+    i64 => "integer", Some("int64");
+    isize => "integer", Some("int64");
+    f32 => "number", Some("float");
+    f64 => "number", Some("double");
+    bool => "boolean", None;
+    char => "string", None;
+    str => "string", None;
+    String => "string", None;
+}
+
+impl_unsigned_schema! {
+    u8 => Some("int32");
+    u16 => Some("int32");
+    u32 => Some("int64");
+    u64 => Some("int64");
+    usize => Some("int64");
+}
+
+/// Any JSON value.
+impl OpenApiSchema for Value {
+    fn schema(_: &mut Schemas) -> Schema {
+        Schema::default()
+    }
+}
+
+impl<'a, T: OpenApiSchema + ?Sized> OpenApiSchema for &'a T {
+    fn schema_name() -> Option<String> {
+        T::schema_name()
+    }
+
+    fn schema_type_name() -> &'static str {
+        T::schema_type_name()
+    }
+
+    fn schema(schemas: &mut Schemas) -> Schema {
+        T::schema(schemas)
+    }
+}
+
+impl<T: OpenApiSchema + ?Sized> OpenApiSchema for Box<T> {
+    fn schema_name() -> Option<String> {
+        T::schema_name()
+    }
+
+    fn schema_type_name() -> &'static str {
+        T::schema_type_name()
+    }
+
+    fn schema(schemas: &mut Schemas) -> Schema {
+        T::schema(schemas)
+    }
+}
+
+/// Optional values may be left out and may be `null`.
+impl<T: OpenApiSchema> OpenApiSchema for Option<T> {
+    fn schema(schemas: &mut Schemas) -> Schema {
+        let schema = T::schema_ref(schemas);
+        if schema.ref_path.is_some() {
+            // siblings of `$ref` are ignored, so mark the reference nullable through `allOf`
+            Schema {
+                all_of: Some(vec![ObjectOrReference::Object(schema)]),
+                nullable: Some(true),
+                ..Default::default()
+            }
+        } else {
+            Schema {
+                nullable: Some(true),
+                ..schema
+            }
+        }
+    }
+
+    fn is_optional() -> bool {
+        true
+    }
+}
+
+fn array(items: Schema, unique: bool) -> Schema {
+    Schema {
+        items: Some(Box::new(items)),
+        unique_items: if unique { Some(true) } else { None },
+        ..typed("array", None)
+    }
+}
+
+impl<T: OpenApiSchema> OpenApiSchema for Vec<T> {
+    fn schema(schemas: &mut Schemas) -> Schema {
+        array(T::schema_ref(schemas), false)
+    }
+}
+
+impl<T: OpenApiSchema> OpenApiSchema for [T] {
+    fn schema(schemas: &mut Schemas) -> Schema {
+        array(T::schema_ref(schemas), false)
+    }
+}
+
+impl<T: OpenApiSchema> OpenApiSchema for HashSet<T> {
+    fn schema(schemas: &mut Schemas) -> Schema {
+        array(T::schema_ref(schemas), true)
+    }
+}
+
+impl<T: OpenApiSchema> OpenApiSchema for BTreeSet<T> {
+    fn schema(schemas: &mut Schemas) -> Schema {
+        array(T::schema_ref(schemas), true)
+    }
+}
+
+fn map(values: Schema) -> Schema {
+    Schema {
+        additional_properties: Some(ObjectOrReference::Object(Box::new(values))),
+        ..typed("object", None)
+    }
+}
+
+impl<V: OpenApiSchema> OpenApiSchema for HashMap<String, V> {
+    fn schema(schemas: &mut Schemas) -> Schema {
+        map(V::schema_ref(schemas))
+    }
+}
+
+impl<V: OpenApiSchema> OpenApiSchema for BTreeMap<String, V> {
+    fn schema(schemas: &mut Schemas) -> Schema {
+        map(V::schema_ref(schemas))
+    }
+}
+
+// Building blocks for `#[derive(OpenApiSchema)]`, which composes them into the schema of
+// structs and enums.
+
+/// An object schema without properties.
+pub fn object() -> Schema {
+    typed("object", None)
+}
+
+/// Add a property to an object schema, listing it in `required` unless it is optional.
+pub fn property(object: &mut Schema, name: &str, schema: Schema, optional: bool) {
+    object.properties.get_or_insert_with(Map::new).insert(name.to_string(), schema);
+    if !optional {
+        object.required.get_or_insert_with(Vec::new).push(name.to_string());
+    }
+}
+
+/// Merge the properties of `flattened` into `object`, as `#[serde(flatten)]` does.
+pub fn flatten(object: &mut Schema, flattened: Schema) {
+    for (name, schema) in flattened.properties.into_iter().flatten() {
+        object.properties.get_or_insert_with(Map::new).insert(name, schema);
+    }
+    for name in flattened.required.into_iter().flatten() {
+        object.required.get_or_insert_with(Vec::new).push(name);
+    }
+}
+
+/// Attach a description. Siblings of `$ref` are ignored, so references are wrapped in `allOf`.
+pub fn describe(schema: Schema, description: &str) -> Schema {
+    if description.is_empty() {
+        return schema;
+    }
+    if schema.ref_path.is_some() {
+        return Schema {
+            description: Some(description.to_string()),
+            all_of: Some(vec![ObjectOrReference::Object(schema)]),
+            ..Default::default()
+        };
+    }
+    Schema {
+        description: Some(description.to_string()),
+        ..schema
+    }
+}
+
+/// A string that is one of `values`.
+pub fn string_enum(values: &[&str]) -> Schema {
+    Schema {
+        enum_values: Some(values.iter().map(|value| value.to_string()).collect()),
+        ..typed("string", None)
+    }
+}
+
+/// A value matching exactly one of `schemas`.
+pub fn one_of(mut schemas: Vec<Schema>) -> Schema {
+    if schemas.len() == 1 {
+        return schemas.remove(0);
+    }
+    Schema {
+        one_of: Some(schemas.into_iter().map(ObjectOrReference::Object).collect()),
+        ..Default::default()
+    }
+}
+
+/// A fixed length array, with one item schema per position.
+pub fn tuple(items: Vec<Schema>) -> Schema {
+    let len = items.len() as u64;
+    let mut distinct: Vec<Schema> = Vec::new();
+    for item in items {
+        if !distinct.contains(&item) {
+            distinct.push(item);
+        }
+    }
+    Schema {
+        min_items: Some(len),
+        max_items: Some(len),
+        ..array(one_of(distinct), false)
+    }
+}
+
+/// A unit value, serialized as `null`.
+pub fn unit() -> Schema {
+    Schema {
+        nullable: Some(true),
+        ..Default::default()
+    }
+}
+
+/// An externally tagged enum variant: an object with the variant name as its only property.
+pub fn external(variant: &str, content: Schema) -> Schema {
+    let mut schema = object();
+    property(&mut schema, variant, content, false);
+    schema
+}
+
+/// An internally tagged enum variant: `content` with an additional `tag` property.
+pub fn with_tag(content: Schema, tag: &str, variant: &str) -> Schema {
+    let mut tag_object = object();
+    property(&mut tag_object, tag, string_enum(&[variant]), false);
+    if content.ref_path.is_some() || content.type_name() != Some("object") {
+        return Schema {
+            all_of: Some(vec![ObjectOrReference::Object(content), ObjectOrReference::Object(tag_object)]),
+            ..Default::default()
+        };
+    }
+    let mut content = content;
+    flatten(&mut content, tag_object);
+    content
+}
+
+/// An adjacently tagged enum variant: an object holding the tag and the content side by side.
+pub fn adjacent(tag: &str, variant: &str, content_key: &str, content: Schema) -> Schema {
+    let mut schema = object();
+    property(&mut schema, tag, string_enum(&[variant]), false);
+    property(&mut schema, content_key, content, false);
+    schema
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Pet;
+
+    impl OpenApiSchema for Pet {
+        fn schema_name() -> Option<String> {
+            Some("Pet".to_string())
+        }
+
+        fn schema(schemas: &mut Schemas) -> Schema {
+            let mut properties = Map::new();
+            properties.insert("name".to_string(), String::schema_ref(schemas));
+            properties.insert("friends".to_string(), Vec::<Pet>::schema_ref(schemas));
+            Schema {
+                properties: Some(properties),
+                required: Some(vec!["name".to_string()]),
+                ..typed("object", None)
+            }
+        }
+    }
+
+    #[test]
+    fn test_named_types_are_referenced() {
+        let mut schemas = Schemas::new();
+        let schema = Option::<Pet>::schema_ref(&mut schemas);
+        assert_eq!(schema.nullable, Some(true));
+        assert!(Option::<Pet>::is_optional());
+
+        let pet = match schemas["Pet"] {
+            ObjectOrReference::Object(ref pet) => pet.clone(),
+            _ => panic!("expected an inline schema"),
+        };
+        let friends = &pet.properties.unwrap()["friends"];
+        assert_eq!(friends.type_name(), Some("array"));
+        assert_eq!(friends.items.as_ref().unwrap().ref_path, Some("#/components/schemas/Pet".to_string()));
+    }
+
+    struct Any;
+
+    impl OpenApiSchema for Any {
+        fn schema_name() -> Option<String> {
+            Some("Pet".to_string())
+        }
+
+        fn schema(_: &mut Schemas) -> Schema {
+            Schema::default()
+        }
+    }
+
+    #[test]
+    fn test_name_collisions() {
+        let mut schemas = Schemas::new();
+        Any::schema_ref(&mut schemas);
+        Box::<Any>::schema_ref(&mut schemas);
+        assert!(schemas.collisions().is_empty());
+
+        Pet::schema_ref(&mut schemas);
+        Pet::schema_ref(&mut schemas);
+        assert_eq!(schemas.collisions().len(), 1);
+        assert!(schemas.collisions()[0].contains("tests::Any"));
+        assert_eq!(schemas["Pet"], ObjectOrReference::Object(Schema::default()));
+    }
+
+    #[test]
+    fn test_primitives() {
+        let mut schemas = Schemas::new();
+        assert_eq!(u64::schema_ref(&mut schemas).minimum, Some(Number::from(0)));
+        assert_eq!(f32::schema_ref(&mut schemas).format, Some("float".to_string()));
+        assert_eq!(HashMap::<String, bool>::schema_ref(&mut schemas).type_name(), Some("object"));
+        assert!(schemas.is_empty());
+        assert_eq!(u32::schema_ref(&mut schemas).format, Some("int64".to_string()));
+    }
+}
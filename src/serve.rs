@@ -0,0 +1,261 @@
+//! Serve a `Spec` from an actix-web `App`.
+//!
+//! The serialized document is cached when the endpoint is built and every response carries
+//! an `ETag` so clients can revalidate with `If-None-Match`.
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+use actix_web::{App, HttpRequest, HttpResponse};
+use actix_web::http::header::{ACCEPT, ETAG, IF_NONE_MATCH};
+
+use crate::server::Server;
+use crate::spec::Spec;
+use crate::{to_json, to_yaml, Format, Result};
+
+const JSON_CONTENT_TYPE: &str = "application/json";
+const YAML_CONTENT_TYPE: &str = "application/yaml";
+
+/// A serialized document together with its entity tag.
+#[derive(Clone, Debug, PartialEq)]
+struct Document {
+    body: String,
+    etag: String,
+}
+
+impl Document {
+    fn new(body: String) -> Document {
+        let etag = etag_for(&body);
+        Document { body, etag }
+    }
+}
+
+/// Serves a `Spec` as JSON or YAML.
+#[derive(Clone, Debug)]
+pub struct SpecEndpoint {
+    spec: Spec,
+    json: Document,
+    yaml: Document,
+    rewrite_servers: bool,
+}
+
+impl SpecEndpoint {
+    /// Serialize the spec once so that every request is answered from the cache.
+    pub fn new(spec: Spec) -> Result<SpecEndpoint> {
+        let json = Document::new(to_json(&spec)?);
+        let yaml = Document::new(to_yaml(&spec)?);
+
+        Ok(SpecEndpoint {
+            spec,
+            json,
+            yaml,
+            rewrite_servers: false,
+        })
+    }
+
+    /// When enabled, the `servers` of the served document are rewritten to the scheme and host
+    /// the request was received on, so the document stays correct behind a proxy.
+    /// Rewritten documents depend on the request and are therefore not cached.
+    pub fn rewrite_servers(mut self, rewrite: bool) -> SpecEndpoint {
+        self.rewrite_servers = rewrite;
+        self
+    }
+
+    /// Answer a request in the given format, or in the format negotiated from the `Accept`
+    /// header when `format` is `None`.
+    pub fn handle<S>(&self, req: &HttpRequest<S>, format: Option<Format>) -> HttpResponse {
+        let format = format.unwrap_or_else(|| negotiate(req));
+
+        let document = if self.rewrite_servers {
+            let info = req.connection_info();
+            match self.render(format, info.scheme(), info.host()) {
+                Ok(document) => document,
+                Err(err) => {
+                    error!("Could not serialize the spec: {}", err);
+                    return HttpResponse::InternalServerError().finish();
+                }
+            }
+        } else {
+            match format {
+                Format::Json => self.json.clone(),
+                Format::Yaml => self.yaml.clone(),
+            }
+        };
+
+        if matches_etag(req, &document.etag) {
+            return HttpResponse::NotModified()
+                .header(ETAG, document.etag)
+                .finish();
+        }
+
+        HttpResponse::Ok()
+            .content_type(content_type(format))
+            .header(ETAG, document.etag)
+            .body(document.body)
+    }
+
+    fn render(&self, format: Format, scheme: &str, host: &str) -> Result<Document> {
+        let mut spec = self.spec.clone();
+        spec.servers = Some(
+            spec.servers
+                .clone()
+                .unwrap_or_else(|| vec![Server { url: "/".to_string(), ..Default::default() }])
+                .into_iter()
+                .map(|server| Server { url: rebase_url(&server.url, scheme, host), ..server })
+                .collect(),
+        );
+
+        let body = match format {
+            Format::Json => to_json(&spec)?,
+            Format::Yaml => to_yaml(&spec)?,
+        };
+
+        Ok(Document::new(body))
+    }
+}
+
+/// Mount `{path}.json` and `{path}.yaml`, plus `path` itself negotiating on `Accept`.
+///
+/// ```ignore
+/// let endpoint = SpecEndpoint::new(spec)?;
+/// let app = serve::mount(App::new(), "/openapi", endpoint);
+/// ```
+pub fn mount<S: 'static>(app: App<S>, path: &str, endpoint: SpecEndpoint) -> App<S> {
+    let endpoint = Arc::new(endpoint);
+    let json = endpoint.clone();
+    let yaml = endpoint.clone();
+
+    app.resource(&format!("{}.json", path), move |r| {
+        r.get().f(move |req| json.handle(req, Some(Format::Json)))
+    })
+    .resource(&format!("{}.yaml", path), move |r| {
+        r.get().f(move |req| yaml.handle(req, Some(Format::Yaml)))
+    })
+    .resource(path, move |r| r.get().f(move |req| endpoint.handle(req, None)))
+}
+
+/// The format of the media range of `Accept` with the highest quality, the first one among
+/// equals, JSON when none names a format.
+fn negotiate<S>(req: &HttpRequest<S>) -> Format {
+    let accept = req
+        .headers()
+        .get(ACCEPT)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or("");
+
+    let mut best: Option<(f32, Format)> = None;
+    for range in accept.split(',') {
+        let mut params = range.split(';');
+        let media_type = params.next().unwrap_or("").trim().to_lowercase();
+        let format = if media_type.contains("yaml") {
+            Format::Yaml
+        } else if media_type.contains("json") || media_type == "*/*" || media_type == "application/*" {
+            Format::Json
+        } else {
+            continue;
+        };
+        let quality = params
+            .filter_map(|param| {
+                let mut pair = param.splitn(2, '=');
+                match (pair.next().map(str::trim), pair.next()) {
+                    (Some("q"), Some(value)) => value.trim().parse::<f32>().ok(),
+                    _ => None,
+                }
+            })
+            .next()
+            .unwrap_or(1.0);
+        let better = match best {
+            Some((best_quality, _)) => quality > best_quality,
+            None => quality > 0.0,
+        };
+        if better {
+            best = Some((quality, format));
+        }
+    }
+    best.map(|(_, format)| format).unwrap_or(Format::Json)
+}
+
+fn content_type(format: Format) -> &'static str {
+    match format {
+        Format::Json => JSON_CONTENT_TYPE,
+        Format::Yaml => YAML_CONTENT_TYPE,
+    }
+}
+
+fn etag_for(body: &str) -> String {
+    let mut hasher = DefaultHasher::new();
+    body.hash(&mut hasher);
+    format!("\"{:x}\"", hasher.finish())
+}
+
+fn matches_etag<S>(req: &HttpRequest<S>, etag: &str) -> bool {
+    match req.headers().get(IF_NONE_MATCH).and_then(|value| value.to_str().ok()) {
+        Some(value) => value
+            .split(',')
+            .map(|tag| tag.trim().trim_start_matches("W/"))
+            .any(|tag| tag == "*" || tag == etag),
+        None => false,
+    }
+}
+
+/// Replace the scheme and host of `url`, keeping its path. Relative urls are made absolute.
+fn rebase_url(url: &str, scheme: &str, host: &str) -> String {
+    let path = match url.find("://") {
+        Some(pos) => {
+            let rest = &url[pos + 3..];
+            match rest.find('/') {
+                Some(slash) => &rest[slash..],
+                None => "",
+            }
+        }
+        None => url,
+    };
+
+    format!("{}://{}{}", scheme, host, path.trim_end_matches('/'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::http::StatusCode;
+    use actix_web::test::TestRequest;
+
+    fn endpoint() -> SpecEndpoint {
+        SpecEndpoint::new(crate::from_path("./data/v3.0/petstore.yaml").unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_negotiate_yaml() {
+        let req = TestRequest::with_header("accept", "application/yaml").finish();
+        let resp = endpoint().handle(&req, None);
+
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(resp.headers().get("content-type").unwrap(), YAML_CONTENT_TYPE);
+    }
+
+    #[test]
+    fn test_negotiate_quality() {
+        let format = |accept: &str| negotiate(&TestRequest::with_header("accept", accept).finish());
+
+        assert_eq!(format("application/json;q=0.1, application/yaml"), Format::Yaml);
+        assert_eq!(format("application/yaml;q=0.5, */*"), Format::Json);
+        assert_eq!(format("text/yaml, application/json"), Format::Yaml);
+        assert_eq!(format("application/yaml;q=0"), Format::Json);
+        assert_eq!(format("text/html"), Format::Json);
+    }
+
+    #[test]
+    fn test_if_none_match() {
+        let endpoint = endpoint();
+        let req = TestRequest::with_header("if-none-match", endpoint.json.etag.as_str()).finish();
+        let resp = endpoint.handle(&req, Some(Format::Json));
+
+        assert_eq!(resp.status(), StatusCode::NOT_MODIFIED);
+    }
+
+    #[test]
+    fn test_rebase_url() {
+        assert_eq!(rebase_url("http://petstore.swagger.io/v1", "https", "api.example.com"), "https://api.example.com/v1");
+        assert_eq!(rebase_url("/", "http", "localhost:8080"), "http://localhost:8080");
+    }
+}
@@ -1,5 +1,5 @@
 use serde::{Deserialize, Serialize};
-use std::collections::BTreeMap;
+use crate::Map;
 use actix_web::client::{ClientRequest, ClientRequestBuilder};
 
 /// An object representing a Server.
@@ -18,7 +18,11 @@ pub struct Server {
     /// A map between a variable name and its value. The value is used for substitution in
     /// the server's URL template.
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub variables: Option<BTreeMap<String, ServerVariable>>,
+    pub variables: Option<Map<String, ServerVariable>>,
+
+    /// `x-` extensions and the fields not described by the model, see `Map`.
+    #[serde(flatten)]
+    pub extensions: Map<String, serde_json::Value>,
 }
 
 /// An object representing a Server Variable for server URL template substitution.
@@ -40,11 +44,30 @@ pub struct ServerVariable {
     /// [CommonMark]: https://spec.commonmark.org/
     #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
+
+    /// `x-` extensions and the fields not described by the model, see `Map`.
+    #[serde(flatten)]
+    pub extensions: Map<String, serde_json::Value>,
 }
 
 impl Server {
+    /// The URL with each `{variable}` replaced by its value in `values`, or by its default.
+    pub fn url_with(&self, values: &Map<String, String>) -> String {
+        let mut url = self.url.clone();
+        for (name, variable) in self.variables.iter().flatten() {
+            let value = values.get(name).unwrap_or(&variable.default);
+            url = url.replace(&format!("{{{}}}", name), value);
+        }
+        url
+    }
+
+    /// The URL with each variable replaced by its default.
+    pub fn default_url(&self) -> String {
+        self.url_with(&Map::new())
+    }
+
     pub fn to_client_request(&self, mut builder: ClientRequestBuilder) -> ClientRequestBuilder {
-        builder.uri(&self.url);
+        builder.uri(self.default_url());
 
         builder
     }
@@ -61,6 +84,7 @@ mod tests {
             url: "http://localhost:8000/v1".to_string(),
             description: None,
             variables: None,
+            extensions: Map::new(),
         };
 
         match server.to_client_request(builder).finish() {
@@ -73,4 +97,29 @@ mod tests {
             Err(_err) => assert!(false),
         }        
     }
+
+    #[test]
+    fn test_url_with() {
+        let mut variables = Map::new();
+        variables.insert("host".to_string(), ServerVariable {
+            default: "localhost".to_string(),
+            ..Default::default()
+        });
+        variables.insert("port".to_string(), ServerVariable {
+            default: "8000".to_string(),
+            substitutions_enum: Some(vec!["8000".to_string(), "443".to_string()]),
+            ..Default::default()
+        });
+        let server = Server {
+            url: "https://{host}:{port}/v1".to_string(),
+            description: None,
+            variables: Some(variables),
+            extensions: Map::new(),
+        };
+
+        assert_eq!(server.default_url(), "https://localhost:8000/v1");
+        let mut values = Map::new();
+        values.insert("port".to_string(), "443".to_string());
+        assert_eq!(server.url_with(&values), "https://localhost:443/v1");
+    }
 }
\ No newline at end of file
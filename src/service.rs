@@ -0,0 +1,238 @@
+//! Answer the operations of a spec from an actix-web `App`.
+//!
+//! Services generated by `codegen::ServiceGenerator` are made of these. `route` registers a
+//! handler for an operation, which reads the parameters with `parameter`, decodes the body
+//! and encodes the response enum the service answers with through `OperationResponse`.
+//! Requests whose parameters or body do not decode are answered with `400 Bad Request`, as
+//! the mock server does.
+
+use std::rc::Rc;
+
+use actix_web::http::header::COOKIE;
+use actix_web::http::StatusCode;
+use actix_web::{App, HttpRequest, HttpResponse};
+use futures::{future, Future};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{json, Value};
+use url::form_urlencoded;
+use url::percent_encoding::percent_decode;
+
+pub use actix_web::http::Method;
+pub use bytes::Bytes;
+
+use crate::path::{Parameter, ParameterStyle};
+use crate::spec::Schema;
+use crate::{Error, Result};
+
+const JSON_CONTENT_TYPE: &str = "application/json";
+
+/// The response of an operation. Errors are answered as actix-web describes them, e.g.
+/// `actix_web::error::ErrorNotFound`.
+pub type ServiceFuture<T> = Box<dyn Future<Item = T, Error = actix_web::Error>>;
+
+/// The response enum of an operation, encoded as the spec describes it.
+pub trait OperationResponse {
+    fn into_response(self) -> HttpResponse;
+}
+
+/// Route `method` requests for `path` on `app` to `handle`, which is given the service, the
+/// request and its body.
+pub fn route<S, T, F>(app: App<S>, path: &str, method: Method, service: &Rc<T>, handle: F) -> App<S>
+where
+    S: 'static,
+    T: 'static,
+    F: Fn(&T, &HttpRequest<S>, &[u8]) -> ServiceFuture<HttpResponse> + 'static,
+{
+    let service = service.clone();
+    app.route(path, method, move |(request, body): (HttpRequest<S>, Bytes)| {
+        handle(&service, &request, &body)
+    })
+}
+
+/// Read the parameter `name` in `location` of `request`, serialized as `style` and `explode`
+/// describe or as their defaults for the location. `schema_type` tells arrays and objects
+/// from primitives. An absent parameter is read as `null`, so as `None` for an `Option`.
+pub fn parameter<S, T>(
+    request: &HttpRequest<S>,
+    location: &str,
+    name: &str,
+    style: Option<ParameterStyle>,
+    explode: Option<bool>,
+    schema_type: Option<&str>,
+) -> Result<T>
+where
+    T: DeserializeOwned,
+{
+    let parameter = Parameter {
+        name: name.to_string(),
+        location: location.to_string(),
+        schema: schema_type.map(|schema_type| Schema {
+            schema_type: Some(schema_type.into()),
+            ..Default::default()
+        }),
+        style,
+        explode,
+        ..Default::default()
+    };
+
+    let pairs: Vec<(String, String)> = match location {
+        // actix-web leaves reserved characters such as `%` and `/` encoded
+        "path" => request
+            .match_info()
+            .get(name)
+            .map(|value| vec![(name.to_string(), percent_decode(value.as_bytes()).decode_utf8_lossy().into_owned())])
+            .unwrap_or_default(),
+        "header" => request
+            .headers()
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| vec![(name.to_string(), value.to_string())])
+            .unwrap_or_default(),
+        "cookie" => request
+            .headers()
+            .get_all(COOKIE)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(';'))
+            .filter_map(|cookie| {
+                let mut parts = cookie.trim().splitn(2, '=');
+                let name = parts.next().filter(|name| !name.is_empty())?;
+                Some((name.to_string(), parts.next().unwrap_or("").to_string()))
+            })
+            .collect(),
+        _ => form_urlencoded::parse(request.query_string().as_bytes()).into_owned().collect(),
+    };
+
+    // primitives are read as strings, numbers and booleans are tried when those do not fit
+    let value = parameter.deserialize(&pairs).unwrap_or(Value::Null);
+    serde_json::from_value(value.clone())
+        .or_else(|_| serde_json::from_value(coerce(value)))
+        .map_err(|e| Error::InvalidParameter(name.to_string(), e.to_string()))
+}
+
+/// Turn strings holding numbers or booleans into those.
+fn coerce(value: Value) -> Value {
+    match value {
+        Value::String(text) => match serde_json::from_str::<Value>(&text) {
+            Ok(ref parsed) if parsed.is_number() || parsed.is_boolean() => parsed.clone(),
+            _ => Value::String(text),
+        },
+        Value::Array(items) => Value::Array(items.into_iter().map(coerce).collect()),
+        Value::Object(object) => Value::Object(object.into_iter().map(|(key, value)| (key, coerce(value))).collect()),
+        value => value,
+    }
+}
+
+/// Decode a JSON request body.
+pub fn json_body<T>(body: &[u8]) -> Result<T>
+where
+    T: DeserializeOwned,
+{
+    serde_json::from_slice(body).map_err(Error::Serialize)
+}
+
+/// Decode a text request body, replacing invalid UTF-8.
+pub fn text_body(body: &[u8]) -> Result<String> {
+    Ok(String::from_utf8_lossy(body).into_owned())
+}
+
+/// Take a request body as is.
+pub fn bytes_body(body: &[u8]) -> Result<Vec<u8>> {
+    Ok(body.to_vec())
+}
+
+/// Decode an optional request body with `decode`, `None` if the request has no body.
+pub fn optional_body<T, F>(body: &[u8], decode: F) -> Result<Option<T>>
+where
+    F: FnOnce(&[u8]) -> Result<T>,
+{
+    if body.is_empty() {
+        Ok(None)
+    } else {
+        decode(body).map(Some)
+    }
+}
+
+/// Encode the response the service answers with.
+pub fn respond<R>(response: ServiceFuture<R>) -> ServiceFuture<HttpResponse>
+where
+    R: OperationResponse + 'static,
+{
+    Box::new(response.map(OperationResponse::into_response))
+}
+
+/// Answer a request whose parameters or body do not decode.
+pub fn bad_request(error: Error) -> ServiceFuture<HttpResponse> {
+    Box::new(future::ok(
+        HttpResponse::BadRequest().json(json!({ "errors": [error.to_string()] })),
+    ))
+}
+
+/// A response with `status` and no body.
+pub fn empty_response(status: u16) -> HttpResponse {
+    HttpResponse::build(status_code(status)).finish()
+}
+
+/// A response with `status` and `body` as JSON.
+pub fn json_response<T>(status: u16, body: &T) -> HttpResponse
+where
+    T: Serialize,
+{
+    match serde_json::to_string(body) {
+        Ok(body) => HttpResponse::build(status_code(status))
+            .content_type(JSON_CONTENT_TYPE)
+            .body(body),
+        Err(e) => {
+            error!("Failed to serialize a response: {}", e);
+            HttpResponse::InternalServerError().finish()
+        }
+    }
+}
+
+/// A response with `status` and `body` as is, of `content_type`.
+pub fn bytes_response<B>(status: u16, content_type: &str, body: B) -> HttpResponse
+where
+    B: Into<Bytes>,
+{
+    HttpResponse::build(status_code(status))
+        .content_type(content_type)
+        .body(body.into())
+}
+
+fn status_code(status: u16) -> StatusCode {
+    StatusCode::from_u16(status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::test::TestRequest;
+
+    #[test]
+    fn test_parameter() {
+        let request = TestRequest::with_uri("/pets/Rex%20II?limit=10&tags=cat&tags=dog")
+            .header("Cookie", "session=abc; theme=dark")
+            .param("petId", "Rex%20II")
+            .finish();
+
+        let limit: Option<u32> = parameter(&request, "query", "limit", None, None, Some("integer")).unwrap();
+        assert_eq!(limit, Some(10));
+        let tags: Vec<String> = parameter(&request, "query", "tags", None, None, Some("array")).unwrap();
+        assert_eq!(tags, vec!["cat", "dog"]);
+        let pet_id: String = parameter(&request, "path", "petId", None, None, Some("string")).unwrap();
+        assert_eq!(pet_id, "Rex II");
+        let theme: String = parameter(&request, "cookie", "theme", None, None, Some("string")).unwrap();
+        assert_eq!(theme, "dark");
+        let offset: Option<u32> = parameter(&request, "query", "offset", None, None, Some("integer")).unwrap();
+        assert_eq!(offset, None);
+        assert!(parameter::<_, u32>(&request, "query", "tags", None, None, Some("integer")).is_err());
+    }
+
+    #[test]
+    fn test_optional_body() {
+        assert_eq!(optional_body(b"", json_body::<u32>).unwrap(), None);
+        assert_eq!(optional_body(b"7", json_body::<u32>).unwrap(), Some(7));
+        assert!(optional_body(b"seven", json_body::<u32>).is_err());
+    }
+}
@@ -0,0 +1,497 @@
+//! Generate a skeleton `Spec` from the routes of an actix-web `App`.
+//!
+//! actix-web does not expose the resources of a built `App`, so a `RouteTable` records them
+//! while they are registered: `RouteTable::resource`, `route` and `scope` wrap the `App`
+//! methods of the same name, handing the closures a `RecordedResource` or `RecordedScope`
+//! that records each route added through it, and `add` records routes registered by other
+//! means. `to_spec` then emits one `PathItem` per pattern, with actix `{id}` and `{id:regex}`
+//! segments turned into path parameters, and one `Operation` per method, ready to be enriched
+//! by hand.
+//!
+//! Routes added without a method, e.g. with `RecordedResource::f`, match any method. Their
+//! path is emitted without operations, and a warning is logged.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use actix_web::dev::{Handler, Resource, Route};
+use actix_web::http::Method;
+use actix_web::{App, FromRequest, HttpRequest, Responder, Scope};
+
+use crate::components::ObjectOrReference;
+use crate::operation::Operation;
+use crate::path::{Parameter, PathItem};
+use crate::spec::{Info, Response, Schema, Spec};
+use crate::Map;
+
+/// The OpenApi version of skeleton documents.
+const SKELETON_VERSION: &str = "3.0.3";
+
+/// Routes as actix patterns and methods, `None` matching any method.
+type Routes = Rc<RefCell<Vec<(String, Option<Method>)>>>;
+
+/// The routes registered on an `App`, as actix patterns and methods.
+#[derive(Clone, Debug, Default)]
+pub struct RouteTable {
+    routes: Vec<(String, Option<Method>)>,
+}
+
+impl RouteTable {
+    pub fn new() -> RouteTable {
+        RouteTable::default()
+    }
+
+    /// Record a route registered on the app by other means. Methods other than GET, PUT,
+    /// POST, DELETE, OPTIONS, HEAD, PATCH and TRACE are left out of the spec.
+    pub fn add(&mut self, pattern: &str, method: Method) -> &mut RouteTable {
+        self.record(pattern.to_string(), Some(method));
+        self
+    }
+
+    fn record(&mut self, pattern: String, method: Option<Method>) {
+        let route = (pattern, method);
+        if !self.routes.contains(&route) {
+            self.routes.push(route);
+        }
+    }
+
+    fn extend(&mut self, routes: Routes) {
+        for (pattern, method) in routes.borrow_mut().drain(..) {
+            self.record(pattern, method);
+        }
+    }
+
+    /// Register a resource on `app`, as `App::resource` does, recording the methods the
+    /// closure adds routes for.
+    pub fn resource<S, F, R>(&mut self, app: App<S>, pattern: &str, f: F) -> App<S>
+    where
+        S: 'static,
+        F: FnOnce(&mut RecordedResource<S>) -> R + 'static,
+    {
+        let routes = Routes::default();
+        let app = app.resource(pattern, recording(pattern, &routes, f));
+        self.extend(routes);
+        app
+    }
+
+    /// Register a route on `app`, as `App::route` does. Handlers taking several extractors
+    /// take them as a tuple.
+    pub fn route<S, T, F, R>(&mut self, app: App<S>, pattern: &str, method: Method, handler: F) -> App<S>
+    where
+        S: 'static,
+        F: Fn(T) -> R + 'static,
+        R: Responder + 'static,
+        T: FromRequest<S> + 'static,
+    {
+        self.record(pattern.to_string(), Some(method.clone()));
+        app.route(pattern, method, handler)
+    }
+
+    /// Register a scope on `app`, as `App::scope` does, recording the routes the closure
+    /// adds to it.
+    pub fn scope<S, F>(&mut self, app: App<S>, path: &str, f: F) -> App<S>
+    where
+        S: 'static,
+        F: FnOnce(RecordedScope<S>) -> RecordedScope<S>,
+    {
+        let routes = Routes::default();
+        let (prefix, recorded) = (join("", path), Rc::clone(&routes));
+        let app = app.scope(path, move |scope| {
+            f(RecordedScope {
+                scope,
+                prefix,
+                routes: recorded,
+            })
+            .scope
+        });
+        self.extend(routes);
+        app
+    }
+
+    /// A skeleton spec with one path per recorded pattern and one operation per method.
+    pub fn to_spec(&self, title: &str, version: &str) -> Spec {
+        let mut paths: Map<String, PathItem> = Map::new();
+        for (pattern, method) in self.routes.iter() {
+            let (path, parameters) = templated_path(pattern);
+            let method = match method {
+                Some(method) => method,
+                None => {
+                    warn!("{} matches any method, add its operations by hand", pattern);
+                    let item = paths.entry(path).or_default();
+                    if item.parameters.is_none() && !parameters.is_empty() {
+                        item.parameters = Some(parameters.into_iter().map(ObjectOrReference::Object).collect());
+                    }
+                    continue;
+                }
+            };
+            let operation = skeleton_operation(method, &path, parameters);
+            let item = paths.entry(path).or_default();
+            match *method {
+                Method::GET => item.get = Some(operation),
+                Method::PUT => item.put = Some(operation),
+                Method::POST => item.post = Some(operation),
+                Method::DELETE => item.delete = Some(operation),
+                Method::OPTIONS => item.options = Some(operation),
+                Method::HEAD => item.head = Some(operation),
+                Method::PATCH => item.patch = Some(operation),
+                Method::TRACE => item.trace = Some(operation),
+                _ => warn!("Skipping {} {}: the method cannot be described", method, pattern),
+            }
+        }
+
+        Spec {
+            openapi: SKELETON_VERSION.to_string(),
+            info: Info {
+                title: title.to_string(),
+                version: version.to_string(),
+                ..Default::default()
+            },
+            paths,
+            ..Default::default()
+        }
+    }
+}
+
+/// Wrap a `RecordedResource` closure into one `App::resource` and `Scope::resource` accept,
+/// recording its routes under `pattern`.
+fn recording<S, F, R>(pattern: &str, routes: &Routes, f: F) -> impl FnOnce(&mut Resource<S>) -> R + 'static
+where
+    S: 'static,
+    F: FnOnce(&mut RecordedResource<S>) -> R + 'static,
+{
+    let pattern = pattern.to_string();
+    let routes = Rc::clone(routes);
+    move |resource| {
+        let mut resource = RecordedResource {
+            resource,
+            methods: Vec::new(),
+        };
+        let result = f(&mut resource);
+        let mut routes = routes.borrow_mut();
+        for method in resource.methods {
+            routes.push((pattern.clone(), method));
+        }
+        result
+    }
+}
+
+/// Append `path` to a scope prefix, adding the slash actix inserts.
+fn join(prefix: &str, path: &str) -> String {
+    let prefix = prefix.trim_end_matches('/');
+    if path.is_empty() || path.starts_with('/') {
+        format!("{}{}", prefix, path)
+    } else {
+        format!("{}/{}", prefix, path)
+    }
+}
+
+/// A `Resource` being registered by `RouteTable::resource`, recording the method of each
+/// route added through it.
+pub struct RecordedResource<'a, S: 'static> {
+    resource: &'a mut Resource<S>,
+    methods: Vec<Option<Method>>,
+}
+
+impl<'a, S: 'static> RecordedResource<'a, S> {
+    fn record(&mut self, method: Option<Method>) {
+        if !self.methods.contains(&method) {
+            self.methods.push(method);
+        }
+    }
+
+    /// Add a route for `method`, as `Resource::method` does.
+    pub fn method(&mut self, method: Method) -> &mut Route<S> {
+        self.record(Some(method.clone()));
+        self.resource.method(method)
+    }
+
+    /// Add a route matching any method, as `Resource::route` does. Routes narrowed down with
+    /// `Route::filter` are still recorded as matching any method, use `method` instead.
+    pub fn route(&mut self) -> &mut Route<S> {
+        self.record(None);
+        self.resource.route()
+    }
+
+    /// Add a route matching any method with a handler function, as `Resource::f` does.
+    pub fn f<F, R>(&mut self, handler: F)
+    where
+        F: Fn(&HttpRequest<S>) -> R + 'static,
+        R: Responder + 'static,
+    {
+        self.route().f(handler)
+    }
+
+    /// Add a route matching any method with a handler, as `Resource::with` does. Handlers
+    /// taking several extractors take them as a tuple.
+    pub fn with<T, F, R>(&mut self, handler: F)
+    where
+        F: Fn(T) -> R + 'static,
+        R: Responder + 'static,
+        T: FromRequest<S> + 'static,
+    {
+        self.route().with(handler)
+    }
+
+    /// Add a route matching any method with a handler, as `Resource::h` does.
+    pub fn h<H: Handler<S>>(&mut self, handler: H) {
+        self.route().h(handler)
+    }
+
+    pub fn get(&mut self) -> &mut Route<S> {
+        self.method(Method::GET)
+    }
+
+    pub fn post(&mut self) -> &mut Route<S> {
+        self.method(Method::POST)
+    }
+
+    pub fn put(&mut self) -> &mut Route<S> {
+        self.method(Method::PUT)
+    }
+
+    pub fn delete(&mut self) -> &mut Route<S> {
+        self.method(Method::DELETE)
+    }
+
+    pub fn options(&mut self) -> &mut Route<S> {
+        self.method(Method::OPTIONS)
+    }
+
+    pub fn head(&mut self) -> &mut Route<S> {
+        self.method(Method::HEAD)
+    }
+
+    pub fn patch(&mut self) -> &mut Route<S> {
+        self.method(Method::PATCH)
+    }
+
+    pub fn trace(&mut self) -> &mut Route<S> {
+        self.method(Method::TRACE)
+    }
+
+    /// The wrapped resource, e.g. to add middlewares. Routes added through it are not
+    /// recorded.
+    pub fn resource(&mut self) -> &mut Resource<S> {
+        self.resource
+    }
+}
+
+/// A `Scope` being registered by `RouteTable::scope`, recording the routes added through it
+/// under its prefix.
+pub struct RecordedScope<S: 'static> {
+    scope: Scope<S>,
+    prefix: String,
+    routes: Routes,
+}
+
+impl<S: 'static> RecordedScope<S> {
+    fn map<F>(self, f: F) -> RecordedScope<S>
+    where
+        F: FnOnce(Scope<S>) -> Scope<S>,
+    {
+        RecordedScope {
+            scope: f(self.scope),
+            ..self
+        }
+    }
+
+    /// Register a resource on the scope, as `Scope::resource` does, recording the methods
+    /// the closure adds routes for.
+    pub fn resource<F, R>(self, path: &str, f: F) -> RecordedScope<S>
+    where
+        F: FnOnce(&mut RecordedResource<S>) -> R + 'static,
+    {
+        let f = recording(&join(&self.prefix, path), &self.routes, f);
+        self.map(|scope| scope.resource(path, f))
+    }
+
+    /// Register a route on the scope, as `Scope::route` does.
+    pub fn route<T, F, R>(self, path: &str, method: Method, handler: F) -> RecordedScope<S>
+    where
+        F: Fn(T) -> R + 'static,
+        R: Responder + 'static,
+        T: FromRequest<S> + 'static,
+    {
+        let route = (join(&self.prefix, path), Some(method.clone()));
+        self.routes.borrow_mut().push(route);
+        self.map(|scope| scope.route(path, method, handler))
+    }
+
+    /// Register a nested scope, as `Scope::nested` does, recording the routes the closure
+    /// adds to it.
+    pub fn nested<F>(self, path: &str, f: F) -> RecordedScope<S>
+    where
+        F: FnOnce(RecordedScope<S>) -> RecordedScope<S>,
+    {
+        let (prefix, routes) = (join(&self.prefix, path), Rc::clone(&self.routes));
+        self.map(|scope| scope.nested(path, move |scope| f(RecordedScope { scope, prefix, routes }).scope))
+    }
+
+    /// Apply `f` to the wrapped scope, e.g. to add middlewares. Routes added through it are
+    /// not recorded.
+    pub fn scope<F>(self, f: F) -> RecordedScope<S>
+    where
+        F: FnOnce(Scope<S>) -> Scope<S>,
+    {
+        self.map(f)
+    }
+}
+
+/// Convert an actix pattern to an OpenApi path template and its path parameters.
+///
+/// `{name}` becomes a string parameter. `{name:regex}` keeps the regex as the parameter's
+/// `pattern`, except for digit-only regexes which become integer parameters.
+pub fn templated_path(pattern: &str) -> (String, Vec<Parameter>) {
+    let mut path = String::new();
+    let mut parameters = Vec::new();
+    let mut chars = pattern.chars();
+
+    while let Some(ch) = chars.next() {
+        if ch != '{' {
+            path.push(ch);
+            continue;
+        }
+
+        // read up to the matching brace, as regexes may contain braces themselves
+        let mut depth = 1;
+        let mut segment = String::new();
+        for ch in chars.by_ref() {
+            match ch {
+                '{' => depth += 1,
+                '}' => depth -= 1,
+                _ => {}
+            }
+            if depth == 0 {
+                break;
+            }
+            segment.push(ch);
+        }
+
+        let mut parts = segment.splitn(2, ':');
+        let name = parts.next().unwrap_or("").trim().to_string();
+        let regex = parts.next().map(str::trim);
+        path.push_str(&format!("{{{}}}", name));
+        parameters.push(Parameter {
+            name,
+            location: "path".to_string(),
+            required: Some(true),
+            schema: Some(parameter_schema(regex)),
+            ..Default::default()
+        });
+    }
+
+    (path, parameters)
+}
+
+fn parameter_schema(regex: Option<&str>) -> Schema {
+    match regex {
+        Some(r"\d+") | Some("[0-9]+") => Schema {
+            schema_type: Some("integer".into()),
+            ..Default::default()
+        },
+        Some(regex) => Schema {
+            schema_type: Some("string".into()),
+            pattern: Some(format!("^{}$", regex)),
+            ..Default::default()
+        },
+        None => Schema {
+            schema_type: Some("string".into()),
+            ..Default::default()
+        },
+    }
+}
+
+/// An operation with a generated `operationId`, e.g. `get_pets_id` for `GET /pets/{id}`.
+fn skeleton_operation(method: &Method, path: &str, parameters: Vec<Parameter>) -> Operation {
+    let words: Vec<String> = path
+        .split(|ch: char| !ch.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+    let operation_id = format!("{}_{}", method.as_str().to_lowercase(), words.join("_"));
+
+    let mut responses = Map::new();
+    responses.insert(
+        "200".to_string(),
+        Response {
+            description: Some("Successful response".to_string()),
+            ..Default::default()
+        },
+    );
+
+    Operation {
+        operation_id: Some(operation_id.trim_end_matches('_').to_string()),
+        parameters: if parameters.is_empty() {
+            None
+        } else {
+            Some(parameters.into_iter().map(ObjectOrReference::Object).collect())
+        },
+        responses,
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::HttpResponse;
+
+    #[test]
+    fn test_templated_path() {
+        let (path, parameters) = templated_path(r"/pets/{id:\d+}/files/{name:[a-z]{2,8}}.{ext}");
+        assert_eq!(path, "/pets/{id}/files/{name}.{ext}");
+        let names: Vec<&str> = parameters.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["id", "name", "ext"]);
+        assert_eq!(parameters[0].schema.as_ref().unwrap().type_name(), Some("integer"));
+        assert_eq!(parameters[1].schema.as_ref().unwrap().pattern, Some("^[a-z]{2,8}$".to_string()));
+    }
+
+    #[test]
+    fn test_to_spec() {
+        let mut routes = RouteTable::new();
+        let app = routes.resource(App::new(), "/pets", |r| {
+            r.get().f(|_| HttpResponse::Ok().finish());
+            r.post().f(|_| HttpResponse::Created().finish());
+        });
+        let _app = routes.resource(app, "/pets/{id}", |r| r.get().f(|_| HttpResponse::Ok().finish()));
+
+        let spec = routes.to_spec("Petstore", "1.0.0");
+        assert_eq!(spec.paths.len(), 2);
+        assert!(spec.paths["/pets"].post.is_some());
+        assert!(spec.paths["/pets"].put.is_none());
+        assert!(spec.paths["/pets/{id}"].post.is_none());
+        let get = spec.paths["/pets/{id}"].get.as_ref().unwrap();
+        assert_eq!(get.operation_id, Some("get_pets_id".to_string()));
+        assert_eq!(get.parameters.as_ref().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_routes_without_resource() {
+        let mut routes = RouteTable::new();
+        let app = routes.resource(App::new(), "/health", |r| {
+            r.options().f(|_| HttpResponse::Ok().finish());
+            r.f(|_| HttpResponse::Ok().finish());
+        });
+        let app = routes.route(app, "/pets", Method::GET, |_: HttpRequest| HttpResponse::Ok().finish());
+        let _app = routes.scope(app, "/store", |scope| {
+            scope
+                .route("orders", Method::POST, |_: HttpRequest| HttpResponse::Created().finish())
+                .nested("/orders/{id}", |scope| {
+                    scope.resource("/items", |r| r.with(|_: HttpRequest| HttpResponse::Ok().finish()))
+                })
+        });
+
+        let spec = routes.to_spec("Petstore", "1.0.0");
+        let paths: Vec<&str> = spec.paths.keys().map(String::as_str).collect();
+        assert_eq!(paths.len(), 4);
+        assert!(spec.paths["/health"].options.is_some());
+        assert!(spec.paths["/pets"].get.is_some());
+        assert!(spec.paths["/store/orders"].post.is_some());
+
+        // routes matching any method are left without operations
+        assert!(spec.paths["/health"].get.is_none());
+        let items = &spec.paths["/store/orders/{id}/items"];
+        assert!(items.get.is_none());
+        assert_eq!(items.parameters.as_ref().unwrap().len(), 1);
+    }
+}
@@ -1,9 +1,9 @@
 //! Schema specification for [OpenAPI 3.0.0](https://github.com/OAI/OpenAPI-Specification/blob/master/versions/3.0.0.md)
 
 use semver;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 use serde_json;
-use std::collections::BTreeMap;
+use crate::Map;
 use url;
 use url_serde;
 use actix_web::client::{ClientRequest};
@@ -12,21 +12,51 @@ use crate::server::{Server};
 use crate::path::{PathItem};
 use crate::external_doc::{ExternalDoc};
 use crate::components::{Components, ObjectOrReference};
-use crate::{Error, Result, MINIMUM_OPENAPI30_VERSION};
+use crate::{Error, Result, OPENAPI30_VERSION, OPENAPI31_VERSION};
     
 
 impl Spec {
     pub fn validate_version(&self) -> Result<semver::Version> {
         let spec_version = &self.openapi;
         let sem_ver = semver::Version::parse(spec_version)?;
-        let required_version = semver::VersionReq::parse(MINIMUM_OPENAPI30_VERSION).unwrap();
-        if required_version.matches(&sem_ver) {
+        let supported = [OPENAPI30_VERSION, OPENAPI31_VERSION]
+            .iter()
+            .any(|req| semver::VersionReq::parse(req).unwrap().matches(&sem_ver));
+        if supported {
             Ok(sem_ver)
         } else {
             Err(Error::UnsupportedSpecFileVersion(sem_ver))?
         }
     }
 
+    /// Whether the document declares an OpenApi 3.1 version.
+    pub fn is_openapi31(&self) -> bool {
+        semver::Version::parse(&self.openapi)
+            .map(|version| semver::VersionReq::parse(OPENAPI31_VERSION).unwrap().matches(&version))
+            .unwrap_or(false)
+    }
+
+    /// Follow the `$ref` of a schema into `components.schemas`. Schemas that are not a
+    /// reference, or whose reference cannot be resolved, are returned unchanged.
+    pub fn resolve_schema<'a>(&'a self, schema: &'a Schema) -> &'a Schema {
+        let schemas = self.components.as_ref().and_then(|c| c.schemas.as_ref());
+        let mut current = schema;
+        // guard against reference cycles
+        for _ in 0..32 {
+            let resolved = current
+                .ref_path
+                .as_ref()
+                .and_then(|ref_path| crate::components::ref_name(ref_path))
+                .and_then(|name| schemas?.get(name))
+                .and_then(|schema| schema.resolve(schemas));
+            match resolved {
+                Some(next) => current = next,
+                None => break,
+            }
+        }
+        current
+    }
+
     pub fn to_client_request(&self) -> Result<Vec<ClientRequest>> {
         let mut client_requests: Vec<ClientRequest> = Vec::new();
 
@@ -84,19 +114,31 @@ pub struct Spec {
     /// [`Server Object`](https://github.com/OAI/OpenAPI-Specification/blob/master/versions/3.0.1.md#serverObject)
     /// in order to construct the full URL. The Paths MAY be empty, due to
     /// [ACL constraints](https://github.com/OAI/OpenAPI-Specification/blob/master/versions/3.0.1.md#securityFiltering).
-    pub paths: BTreeMap<String, PathItem>,
+    /// OpenApi 3.1 documents MAY omit the paths.
+    #[serde(default)]
+    pub paths: Map<String, PathItem>,
+
+    /// OpenApi 3.1 only. The incoming webhooks that MAY be received as part of this API.
+    /// The key is a unique name for the webhook.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub webhooks: Option<Map<String, PathItem>>,
+
+    /// OpenApi 3.1 only. The default value for the `$schema` keyword within the Schema
+    /// Objects contained within this document.
+    #[serde(skip_serializing_if = "Option::is_none", rename = "jsonSchemaDialect")]
+    pub json_schema_dialect: Option<String>,
 
     /// An element to hold various schemas for the specification.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub components: Option<Components>,
 
-    // FIXME: Implement
-    // /// A declaration of which security mechanisms can be used across the API.
-    // /// The list of  values includes alternative security requirement objects that can be used.
-    // /// Only one of the security requirement objects need to be satisfied to authorize a request.
-    // /// Individual operations can override this definition.
-    // #[serde(skip_serializing_if = "Option::is_none")]
-    // pub security: Option<SecurityRequirement>,
+    /// A declaration of which security mechanisms can be used across the API.
+    /// The list of  values includes alternative security requirement objects that can be used.
+    /// Only one of the security requirement objects need to be satisfied to authorize a request.
+    /// Individual operations can override this definition.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub security: Option<Vec<SecurityRequirement>>,
+
     /// A list of tags used by the specification with additional metadata.
     ///The order of the tags can be used to reflect on their order by the parsing tools.
     /// Not all tags that are used by the
@@ -109,7 +151,10 @@ pub struct Spec {
     /// Additional external documentation.
     #[serde(skip_serializing_if = "Option::is_none", rename = "externalDocs")]
     pub external_docs: Option<ExternalDoc>,
-    // TODO: Add "Specification Extensions" https://github.com/OAI/OpenAPI-Specification/blob/master/versions/3.0.1.md#specificationExtensions}
+
+    /// `x-` extensions and the fields not described by the model, see `Map`.
+    #[serde(flatten)]
+    pub extensions: Map<String, serde_json::Value>,
 }
 
 /// General information about the API.
@@ -121,6 +166,9 @@ pub struct Spec {
 pub struct Info {
     /// The title of the application.
     pub title: String,
+    /// OpenApi 3.1 only. A short summary of the API.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
     /// A short description of the application. CommonMark syntax MAY be used for rich text representation.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
@@ -136,6 +184,10 @@ pub struct Info {
     /// The license information for the exposed API.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub license: Option<License>,
+
+    /// `x-` extensions and the fields not described by the model, see `Map`.
+    #[serde(flatten)]
+    pub extensions: Map<String, serde_json::Value>,
 }
 
 /// Wraper around `url::Url` to fix serde issue
@@ -156,7 +208,10 @@ pub struct Contact {
     // TODO: Make sure the email is a valid email
     #[serde(skip_serializing_if = "Option::is_none")]
     pub email: Option<String>,
-    // TODO: Add "Specification Extensions" https://github.com/OAI/OpenAPI-Specification/blob/master/versions/3.0.1.md#specificationExtensions
+
+    /// `x-` extensions and the fields not described by the model, see `Map`.
+    #[serde(flatten)]
+    pub extensions: Map<String, serde_json::Value>,
 }
 
 /// License information for the exposed API.
@@ -166,10 +221,17 @@ pub struct Contact {
 pub struct License {
     /// The license name used for the API.
     pub name: String,
+    /// OpenApi 3.1 only. An [SPDX](https://spdx.org/spdx-specification-21-web-version#h.jxpfx0ykyb60)
+    /// license expression for the API. Mutually exclusive of the `url` field.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub identifier: Option<String>,
     /// A URL to the license used for the API.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub url: Option<Url>,
-    // TODO: Add "Specification Extensions" https://github.com/OAI/OpenAPI-Specification/blob/master/versions/3.0.1.md#specificationExtensions}
+
+    /// `x-` extensions and the fields not described by the model, see `Map`.
+    #[serde(flatten)]
+    pub extensions: Map<String, serde_json::Value>,
 }
 
 // FIXME: Verify against OpenAPI 3.0
@@ -195,9 +257,10 @@ pub struct Schema {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
 
+    /// The type of the value. OpenApi 3.1 allows a list of types, e.g. `[string, "null"]`.
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(rename = "type")]
-    pub schema_type: Option<String>,
+    pub schema_type: Option<SchemaType>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub format: Option<String>,
@@ -213,7 +276,7 @@ pub struct Schema {
     pub items: Option<Box<Schema>>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub properties: Option<BTreeMap<String, Schema>>,
+    pub properties: Option<Map<String, Schema>>,
 
     #[serde(skip_serializing_if = "Option::is_none", rename = "readOnly")]
     pub read_only: Option<bool>,
@@ -240,24 +303,62 @@ pub struct Schema {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub example: Option<serde_json::value::Value>,
 
+    /// OpenApi 3.1 only. A list of example instances for this schema.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub examples: Option<Vec<serde_json::Value>>,
+
+    /// OpenApi 3.1 only. The value MUST be equal to this constant.
+    #[serde(skip_serializing_if = "Option::is_none", rename = "const")]
+    pub const_value: Option<serde_json::Value>,
+
     // The following properties are taken directly from the JSON Schema definition and
-    // follow the same specifications:
-    // title
-    // multipleOf
-    // maximum
-    // exclusiveMaximum
-    // minimum
-    // exclusiveMinimum
-    // maxLength
-    // minLength
-    // pattern (This string SHOULD be a valid regular expression, according to the ECMA 262 regular expression dialect)
-    // maxItems
-    // minItems
-    // uniqueItems
+    // follow the same specifications.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none", rename = "multipleOf")]
+    pub multiple_of: Option<serde_json::Number>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub maximum: Option<serde_json::Number>,
+
+    /// A boolean modifying `maximum` in OpenApi 3.0, a number in OpenApi 3.1.
+    #[serde(skip_serializing_if = "Option::is_none", rename = "exclusiveMaximum")]
+    pub exclusive_maximum: Option<ExclusiveBound>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub minimum: Option<serde_json::Number>,
+
+    /// A boolean modifying `minimum` in OpenApi 3.0, a number in OpenApi 3.1.
+    #[serde(skip_serializing_if = "Option::is_none", rename = "exclusiveMinimum")]
+    pub exclusive_minimum: Option<ExclusiveBound>,
+
+    #[serde(skip_serializing_if = "Option::is_none", rename = "maxLength")]
+    pub max_length: Option<u64>,
+
+    #[serde(skip_serializing_if = "Option::is_none", rename = "minLength")]
+    pub min_length: Option<u64>,
+
+    /// This string SHOULD be a valid regular expression, according to the ECMA 262 regular
+    /// expression dialect.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none", rename = "maxItems")]
+    pub max_items: Option<u64>,
+
+    #[serde(skip_serializing_if = "Option::is_none", rename = "minItems")]
+    pub min_items: Option<u64>,
+
+    #[serde(skip_serializing_if = "Option::is_none", rename = "uniqueItems")]
+    pub unique_items: Option<bool>,
+
+    /// Allows sending a `null` value for the defined schema. Default value is `false`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nullable: Option<bool>,
+
     // maxProperties
     // minProperties
-    // required
-    // enum
 
     // The following properties are taken from the JSON Schema definition but their
     // definitions were adjusted to the OpenAPI Specification.
@@ -277,12 +378,129 @@ pub struct Schema {
     /// defined type for the Schema Object defined at the same level. For example, if type is
     /// `string`, then `default` can be `"foo"` but cannot be `1`.
     #[serde(skip_serializing_if = "Option::is_none")]
-    default: Option<serde_json::Value>,
+    pub default: Option<serde_json::Value>,
 
     /// Inline or referenced schema MUST be of a [Schema Object](#schemaObject) and not a standard
     /// JSON Schema.
     #[serde(rename = "allOf", skip_serializing_if = "Option::is_none")]
     pub all_of: Option<Vec<ObjectOrReference<Schema>>>,
+
+    /// The value MUST be valid against exactly one of the inline or referenced schemas.
+    #[serde(rename = "oneOf", skip_serializing_if = "Option::is_none")]
+    pub one_of: Option<Vec<ObjectOrReference<Schema>>>,
+
+    /// Tells which of the `oneOf` schemas a value matches, by the value of one of its properties.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub discriminator: Option<Discriminator>,
+
+    /// `x-` extensions and the fields not described by the model, see `Map`.
+    #[serde(flatten)]
+    pub extensions: Map<String, serde_json::Value>,
+}
+
+/// Aids serialization, deserialization and validation of `oneOf` schemas.
+///
+/// See [link]
+/// [link][https://github.com/OAI/OpenAPI-Specification/blob/master/versions/3.0.1.md#discriminatorObject]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Default)]
+pub struct Discriminator {
+    /// The name of the property holding the discriminator value.
+    #[serde(rename = "propertyName")]
+    pub property_name: String,
+
+    /// Maps discriminator values to schema names or references. Values not listed name the
+    /// schema directly.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mapping: Option<Map<String, String>>,
+}
+
+/// The `type` of a schema.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(untagged)]
+pub enum SchemaType {
+    Single(String),
+    /// OpenApi 3.1 only.
+    Multiple(Vec<String>),
+}
+
+impl SchemaType {
+    /// Every type listed, including `null`.
+    pub fn types(&self) -> Vec<&str> {
+        match self {
+            SchemaType::Single(schema_type) => vec![schema_type.as_str()],
+            SchemaType::Multiple(types) => types.iter().map(String::as_str).collect(),
+        }
+    }
+}
+
+impl<'a> From<&'a str> for SchemaType {
+    fn from(schema_type: &'a str) -> SchemaType {
+        SchemaType::Single(schema_type.to_string())
+    }
+}
+
+impl From<String> for SchemaType {
+    fn from(schema_type: String) -> SchemaType {
+        SchemaType::Single(schema_type)
+    }
+}
+
+/// The `exclusiveMinimum` and `exclusiveMaximum` keywords.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(untagged)]
+pub enum ExclusiveBound {
+    /// OpenApi 3.0: whether `minimum`/`maximum` itself is excluded.
+    Flag(bool),
+    /// OpenApi 3.1: the exclusive limit.
+    Limit(serde_json::Number),
+}
+
+impl Schema {
+    /// The type of the schema, ignoring `null` in an OpenApi 3.1 list of types.
+    pub fn type_name(&self) -> Option<&str> {
+        self.schema_type
+            .as_ref()
+            .and_then(|schema_type| schema_type.types().into_iter().find(|t| *t != "null"))
+    }
+
+    /// Whether `null` is allowed, through `nullable` or a `null` type.
+    pub fn is_nullable(&self) -> bool {
+        self.nullable == Some(true)
+            || self
+                .schema_type
+                .as_ref()
+                .map_or(false, |schema_type| schema_type.types().contains(&"null"))
+    }
+
+    /// The lower bound of a number, and whether it is exclusive.
+    pub fn minimum_bound(&self) -> Option<(f64, bool)> {
+        bound(self.minimum.as_ref(), self.exclusive_minimum.as_ref(), f64::max)
+    }
+
+    /// The upper bound of a number, and whether it is exclusive.
+    pub fn maximum_bound(&self) -> Option<(f64, bool)> {
+        bound(self.maximum.as_ref(), self.exclusive_maximum.as_ref(), f64::min)
+    }
+}
+
+/// Combine an inclusive limit with an exclusive flag or limit, keeping the tighter one.
+fn bound(
+    inclusive: Option<&serde_json::Number>,
+    exclusive: Option<&ExclusiveBound>,
+    tighter: fn(f64, f64) -> f64,
+) -> Option<(f64, bool)> {
+    let inclusive = inclusive.and_then(|n| n.as_f64());
+    match (inclusive, exclusive) {
+        (Some(limit), Some(ExclusiveBound::Flag(exclusive))) => Some((limit, *exclusive)),
+        (limit, Some(ExclusiveBound::Limit(exclusive))) => {
+            let exclusive = exclusive.as_f64()?;
+            match limit {
+                Some(limit) if tighter(limit, exclusive) == limit && limit != exclusive => Some((limit, false)),
+                _ => Some((exclusive, true)),
+            }
+        }
+        (limit, _) => limit.map(|limit| (limit, false)),
+    }
 }
 
 /// Describes a single response from an API Operation, including design-time, static `links`
@@ -301,21 +519,24 @@ pub struct Response {
     /// insensitive. If a response header is defined with the name `"Content-Type"`, it SHALL
     /// be ignored.
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub headers: Option<BTreeMap<String, ObjectOrReference<Header>>>,
+    pub headers: Option<Map<String, ObjectOrReference<Header>>>,
 
     /// A map containing descriptions of potential response payloads. The key is a media type
     /// or [media type range](https://tools.ietf.org/html/rfc7231#appendix-D) and the value
     /// describes it. For responses that match multiple keys, only the most specific key is
     /// applicable. e.g. text/plain overrides text/*
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub content: Option<BTreeMap<String, MediaType>>,
+    pub content: Option<Map<String, MediaType>>,
 
     /// A map of operations links that can be followed from the response. The key of the map
     /// is a short name for the link, following the naming constraints of the names for
     /// [Component Objects](https://github.com/OAI/OpenAPI-Specification/blob/master/versions/3.0.1.md#componentsObject).
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub links: Option<BTreeMap<String, ObjectOrReference<Link>>>,
-    // TODO: Add "Specification Extensions" https://github.com/OAI/OpenAPI-Specification/blob/master/versions/3.0.1.md#specificationExtensions}
+    pub links: Option<Map<String, ObjectOrReference<Link>>>,
+
+    /// `x-` extensions and the fields not described by the model, see `Map`.
+    #[serde(flatten)]
+    pub extensions: Map<String, serde_json::Value>,
 }
 
 /// The Header Object follows the structure of the
@@ -332,22 +553,22 @@ pub struct Header {
     // FIXME: Is the third change properly implemented?
     // FIXME: Merge `ObjectOrReference<Header>::Reference` and `ParameterOrRef::Reference`
     #[serde(skip_serializing_if = "Option::is_none")]
-    required: Option<bool>,
+    pub required: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    schema: Option<Schema>,
+    pub schema: Option<Schema>,
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(rename = "uniqueItems")]
-    unique_items: Option<bool>,
+    pub unique_items: Option<bool>,
     /// string, number, boolean, integer, array, file ( only for formData )
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(rename = "type")]
-    param_type: Option<String>,
+    pub param_type: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    format: Option<String>,
+    pub format: Option<String>,
     /// A brief description of the parameter. This could contain examples
     /// of use.  GitHub Flavored Markdown is allowed.
     #[serde(skip_serializing_if = "Option::is_none")]
-    description: Option<String>,
+    pub description: Option<String>,
     // collectionFormat: ???
     // default: ???
     // maximum ?
@@ -362,6 +583,10 @@ pub struct Header {
     // enum ??
     // multipleOf ??
     // allowEmptyValue ( for query / body params )
+
+    /// `x-` extensions and the fields not described by the model, see `Map`.
+    #[serde(flatten)]
+    pub extensions: Map<String, serde_json::Value>,
 }
 
 /// Describes a single request body.
@@ -378,10 +603,14 @@ pub struct RequestBody {
     /// [media type range](https://tools.ietf.org/html/rfc7231#appendix-D) and the
     /// value describes it. For requests that match multiple keys, only the most specific key
     /// is applicable. e.g. text/plain overrides text/*
-    pub content: BTreeMap<String, MediaType>,
+    pub content: Map<String, MediaType>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub required: Option<bool>,
+
+    /// `x-` extensions and the fields not described by the model, see `Map`.
+    #[serde(flatten)]
+    pub extensions: Map<String, serde_json::Value>,
 }
 
 /// The Link object represents a possible design-time link for a response.
@@ -421,9 +650,9 @@ pub enum Link {
         // /// [parameter location](https://github.com/OAI/OpenAPI-Specification/blob/master/versions/3.0.1.md#parameterIn)
         // /// `[{in}.]{name}` for operations that use the same parameter name in different
         // /// locations (e.g. path.id).
-        // parameters: BTreeMap<String, Any | {expression}>,
+        // parameters: Map<String, Any | {expression}>,
         #[serde(skip_serializing_if = "Option::is_none")]
-        parameters: Option<BTreeMap<String, String>>,
+        parameters: Option<Map<String, String>>,
 
         // FIXME: Implement
         // /// A literal value or
@@ -455,9 +684,9 @@ pub enum Link {
         // /// [parameter location](https://github.com/OAI/OpenAPI-Specification/blob/master/versions/3.0.1.md#parameterIn)
         // /// `[{in}.]{name}` for operations that use the same parameter name in different
         // /// locations (e.g. path.id).
-        // parameters: BTreeMap<String, Any | {expression}>,
+        // parameters: Map<String, Any | {expression}>,
         #[serde(skip_serializing_if = "Option::is_none")]
-        parameters: Option<BTreeMap<String, String>>,
+        parameters: Option<Map<String, String>>,
 
         // FIXME: Implement
         // /// A literal value or
@@ -495,7 +724,23 @@ pub struct MediaType {
     /// only apply to `requestBody` objects when the media type is `multipart`
     /// or `application/x-www-form-urlencoded`.
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub encoding: Option<BTreeMap<String, Encoding>>,
+    pub encoding: Option<Map<String, Encoding>>,
+
+    /// `x-` extensions and the fields not described by the model, see `Map`.
+    #[serde(flatten, deserialize_with = "media_type_extensions")]
+    pub extensions: Map<String, serde_json::Value>,
+}
+
+/// The extensions of a media type, leaving out the fields read by `MediaTypeExample`, which
+/// a flattened map would otherwise see as well.
+fn media_type_extensions<'de, D>(deserializer: D) -> std::result::Result<Map<String, serde_json::Value>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let mut extensions = Map::<String, serde_json::Value>::deserialize(deserializer)?;
+    extensions.shift_remove("example");
+    extensions.shift_remove("examples");
+    Ok(extensions)
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
@@ -511,7 +756,7 @@ pub enum MediaTypeExample {
     /// the `example` field. Furthermore, if referencing a `schema` which contains an
     /// example, the `examples` value SHALL override the example provided by the schema.
     Examples {
-        examples: BTreeMap<String, ObjectOrReference<Example>>,
+        examples: Map<String, ObjectOrReference<Example>>,
     },
 }
 
@@ -532,7 +777,7 @@ pub struct Encoding {
     /// ignored in this section. This property SHALL be ignored if the request body
     /// media type is not a `multipart`.
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub headers: Option<BTreeMap<String, ObjectOrReference<Header>>>,
+    pub headers: Option<Map<String, ObjectOrReference<Header>>>,
 
     /// Describes how a specific property value will be serialized depending on its type.
     /// See [Parameter Object](https://github.com/OAI/OpenAPI-Specification/blob/master/versions/3.0.1.md#parameterObject)
@@ -561,6 +806,10 @@ pub struct Encoding {
     /// not `application/x-www-form-urlencoded`.
     #[serde(skip_serializing_if = "Option::is_none", rename = "allowReserved")]
     pub allow_reserved: Option<bool>,
+
+    /// `x-` extensions and the fields not described by the model, see `Map`.
+    #[serde(flatten)]
+    pub extensions: Map<String, serde_json::Value>,
 }
 
 ///
@@ -589,7 +838,9 @@ pub struct Example {
     // #[serde(skip_serializing_if = "Option::is_none")]
     // pub externalValue: Option<String>,
 
-    // TODO: Add "Specification Extensions" https://github.com/OAI/OpenAPI-Specification/blob/master/versions/3.0.1.md#specificationExtensions}
+    /// `x-` extensions and the fields not described by the model, see `Map`.
+    #[serde(flatten)]
+    pub extensions: Map<String, serde_json::Value>,
 }
 
 /// Defines a security scheme that can be used by the operations. Supported schemes are
@@ -607,28 +858,33 @@ pub enum SecurityScheme {
         name: String,
         #[serde(rename = "in")]
         location: String,
+        /// `x-` extensions and the fields not described by the model, see `Map`.
+        #[serde(flatten)]
+        extensions: Map<String, serde_json::Value>,
     },
     #[serde(rename = "http")]
     Http {
         scheme: String,
-        #[serde(rename = "bearerFormat")]
-        bearer_format: String,
+        #[serde(rename = "bearerFormat", skip_serializing_if = "Option::is_none")]
+        bearer_format: Option<String>,
+        /// `x-` extensions and the fields not described by the model, see `Map`.
+        #[serde(flatten)]
+        extensions: Map<String, serde_json::Value>,
+    },
+    #[serde(rename = "oauth2")]
+    OAuth2 {
+        flows: OAuthFlows,
+        /// `x-` extensions and the fields not described by the model, see `Map`.
+        #[serde(flatten)]
+        extensions: Map<String, serde_json::Value>,
     },
-    // FIXME: Implement
-    // #[serde(rename = "oauth2")]
-    // Oauth2 {
-    //     flow: String,
-    //     #[serde(rename = "authorizationUrl")]
-    //     authorization_url: String,
-    //     #[serde(rename = "tokenUrl")]
-    //     #[serde(skip_serializing_if = "Option::is_none")]
-    //     token_url: Option<String>,
-    //     scopes: BTreeMap<String, String>,
-    // },
     #[serde(rename = "openIdConnect")]
     OpenIdConnect {
         #[serde(rename = "openIdConnectUrl")]
         open_id_connect_url: String,
+        /// `x-` extensions and the fields not described by the model, see `Map`.
+        #[serde(flatten)]
+        extensions: Map<String, serde_json::Value>,
     },
 }
 
@@ -646,12 +902,57 @@ pub struct Callback(
     serde_json::Value, // TODO: Add "Specification Extensions" https://github.com/OAI/OpenAPI-Specification/blob/master/versions/3.0.1.md#specificationExtensions}
 );
 
-// FIXME: Implement
-// /// Allows configuration of the supported OAuth Flows.
-// /// https://github.com/OAI/OpenAPI-Specification/blob/master/versions/3.0.1.md#oauthFlowsObject
-// #[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Default)]
-// pub struct OAuthFlows {
-// }
+/// Lists the required security schemes to execute an operation. The name used for each
+/// property MUST correspond to a security scheme declared in the
+/// [Security Schemes](https://github.com/OAI/OpenAPI-Specification/blob/master/versions/3.0.1.md#componentsSecuritySchemes)
+/// under the Components Object. The value is the list of scope names required for the
+/// execution, which is empty unless the scheme is of type `oauth2` or `openIdConnect`.
+///
+/// See [link]
+/// [link][https://github.com/OAI/OpenAPI-Specification/blob/master/versions/3.0.1.md#securityRequirementObject]
+pub type SecurityRequirement = Map<String, Vec<String>>;
+
+/// Allows configuration of the supported OAuth Flows.
+///
+/// See [link]
+/// [link][https://github.com/OAI/OpenAPI-Specification/blob/master/versions/3.0.1.md#oauthFlowsObject]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Default)]
+pub struct OAuthFlows {
+    /// Configuration for the OAuth Implicit flow.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub implicit: Option<OAuthFlow>,
+    /// Configuration for the OAuth Resource Owner Password flow.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub password: Option<OAuthFlow>,
+    /// Configuration for the OAuth Client Credentials flow.
+    #[serde(skip_serializing_if = "Option::is_none", rename = "clientCredentials")]
+    pub client_credentials: Option<OAuthFlow>,
+    /// Configuration for the OAuth Authorization Code flow.
+    #[serde(skip_serializing_if = "Option::is_none", rename = "authorizationCode")]
+    pub authorization_code: Option<OAuthFlow>,
+}
+
+/// Configuration details for a supported OAuth Flow.
+///
+/// See [link]
+/// [link][https://github.com/OAI/OpenAPI-Specification/blob/master/versions/3.0.1.md#oauthFlowObject]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Default)]
+pub struct OAuthFlow {
+    /// The authorization URL to be used for this flow. Applies to `implicit` and
+    /// `authorizationCode`.
+    #[serde(skip_serializing_if = "Option::is_none", rename = "authorizationUrl")]
+    pub authorization_url: Option<String>,
+    /// The token URL to be used for this flow. Applies to `password`, `clientCredentials`
+    /// and `authorizationCode`.
+    #[serde(skip_serializing_if = "Option::is_none", rename = "tokenUrl")]
+    pub token_url: Option<String>,
+    /// The URL to be used for obtaining refresh tokens.
+    #[serde(skip_serializing_if = "Option::is_none", rename = "refreshUrl")]
+    pub refresh_url: Option<String>,
+    /// The available scopes for the OAuth2 security scheme. A map between the scope name
+    /// and a short description for it.
+    pub scopes: Map<String, String>,
+}
 
 /// Adds metadata to a single tag that is used by the
 /// [Operation Object](https://github.com/OAI/OpenAPI-Specification/blob/master/versions/3.0.1.md#operationObject).
@@ -672,6 +973,8 @@ pub struct Tag {
     // #[serde(skip_serializing_if = "Option::is_none")]
     // pub external_docs: Option<Vec<ExternalDoc>>,
 
-    // TODO: Add "Specification Extensions" https://github.com/OAI/OpenAPI-Specification/blob/master/versions/3.0.1.md#specificationExtensions}
+    /// `x-` extensions and the fields not described by the model, see `Map`.
+    #[serde(flatten)]
+    pub extensions: Map<String, serde_json::Value>,
 }
 
@@ -0,0 +1,890 @@
+//! Support for [Swagger 2.0](https://github.com/OAI/OpenAPI-Specification/blob/master/versions/2.0.md)
+//! documents and their conversion to an OpenApi 3.0 `Spec`.
+//!
+//! Swagger 2.0 features without an OpenApi 3.0 equivalent are dropped and reported as
+//! `LossyConversion`s.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use crate::Map;
+
+use crate::components::{Components, ObjectOrReference, Referable};
+use crate::conversion::{Conversion, LossyConversion};
+use crate::external_doc::ExternalDoc;
+use crate::operation::Operation as OperationV3;
+use crate::path::{Parameter as ParameterV3, ParameterStyle, PathItem as PathItemV3};
+use crate::pointer;
+use crate::server::Server;
+use crate::spec::{
+    Header as HeaderV3, Info, MediaType, MediaTypeExample, OAuthFlow, OAuthFlows, RequestBody,
+    Response as ResponseV3, Discriminator, Schema as SchemaV3, SchemaType, SecurityRequirement, SecurityScheme as SecuritySchemeV3, Spec,
+    Tag,
+};
+
+const OPENAPI_VERSION: &str = "3.0.1";
+const DEFAULT_MEDIA_TYPE: &str = "application/json";
+const FORM_URLENCODED: &str = "application/x-www-form-urlencoded";
+const MULTIPART_FORM_DATA: &str = "multipart/form-data";
+
+/// top level Swagger 2.0 document
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Default)]
+pub struct Swagger {
+    /// Specifies the Swagger Specification version being used. Its value MUST be `"2.0"`.
+    pub swagger: String,
+    /// Provides metadata about the API.
+    pub info: Info,
+    /// The host (name or ip) serving the API, optionally including the port.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub host: Option<String>,
+    /// The base path on which the API is served, relative to the `host`.
+    #[serde(skip_serializing_if = "Option::is_none", rename = "basePath")]
+    pub base_path: Option<String>,
+    /// The transfer protocol of the API: `http`, `https`, `ws` or `wss`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schemes: Option<Vec<String>>,
+    /// A list of MIME types the APIs can consume.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub consumes: Option<Vec<String>>,
+    /// A list of MIME types the APIs can produce.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub produces: Option<Vec<String>>,
+    /// The available paths and operations for the API.
+    pub paths: Map<String, PathItem>,
+    /// Data types produced and consumed by operations.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub definitions: Option<Map<String, Schema>>,
+    /// Parameters that can be used across operations.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parameters: Option<Map<String, Parameter>>,
+    /// Responses that can be used across operations.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub responses: Option<Map<String, Response>>,
+    /// Security scheme definitions that can be used across the specification.
+    #[serde(skip_serializing_if = "Option::is_none", rename = "securityDefinitions")]
+    pub security_definitions: Option<Map<String, SecurityScheme>>,
+    /// Security schemes applied to the whole API.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub security: Option<Vec<SecurityRequirement>>,
+    /// A list of tags used by the specification with additional metadata.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<Tag>>,
+    /// Additional external documentation.
+    #[serde(skip_serializing_if = "Option::is_none", rename = "externalDocs")]
+    pub external_docs: Option<ExternalDoc>,
+    /// `x-` extensions and the fields not described by the model, see `Map`.
+    #[serde(flatten)]
+    pub extensions: Map<String, Value>,
+}
+
+/// Describes the operations available on a single path.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Default)]
+pub struct PathItem {
+    #[serde(skip_serializing_if = "Option::is_none", rename = "$ref")]
+    pub reference: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub get: Option<Operation>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub put: Option<Operation>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub post: Option<Operation>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delete: Option<Operation>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub options: Option<Operation>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub head: Option<Operation>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub patch: Option<Operation>,
+    /// Parameters applicable for all the operations described under this path.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parameters: Option<Vec<ObjectOrReference<Parameter>>>,
+}
+
+/// Describes a single API operation on a path.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Default)]
+pub struct Operation {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", rename = "externalDocs")]
+    pub external_docs: Option<ExternalDoc>,
+    #[serde(skip_serializing_if = "Option::is_none", rename = "operationId")]
+    pub operation_id: Option<String>,
+    /// Overrides the `consumes` definition at the document level.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub consumes: Option<Vec<String>>,
+    /// Overrides the `produces` definition at the document level.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub produces: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parameters: Option<Vec<ObjectOrReference<Parameter>>>,
+    pub responses: Map<String, ObjectOrReference<Response>>,
+    /// Overrides the `schemes` definition at the document level.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schemes: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deprecated: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub security: Option<Vec<SecurityRequirement>>,
+}
+
+/// Describes a single operation parameter.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Default)]
+pub struct Parameter {
+    pub name: String,
+    /// `query`, `header`, `path`, `formData` or `body`.
+    #[serde(rename = "in")]
+    pub location: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub required: Option<bool>,
+    /// The schema of a `body` parameter.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schema: Option<Schema>,
+    /// The type of a non `body` parameter.
+    #[serde(skip_serializing_if = "Option::is_none", rename = "type")]
+    pub param_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub items: Option<Box<Schema>>,
+    /// `csv`, `ssv`, `tsv`, `pipes` or `multi`.
+    #[serde(skip_serializing_if = "Option::is_none", rename = "collectionFormat")]
+    pub collection_format: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none", rename = "enum")]
+    pub enum_values: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub minimum: Option<serde_json::Number>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub maximum: Option<serde_json::Number>,
+    #[serde(skip_serializing_if = "Option::is_none", rename = "minLength")]
+    pub min_length: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none", rename = "maxLength")]
+    pub max_length: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+}
+
+/// Describes a single response from an API Operation.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Default)]
+pub struct Response {
+    pub description: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schema: Option<Schema>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub headers: Option<Map<String, Header>>,
+    /// Examples keyed by MIME type.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub examples: Option<Map<String, Value>>,
+}
+
+impl Referable for Parameter {}
+impl Referable for Response {}
+
+/// A header that can be sent as part of a response.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Default)]
+pub struct Header {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(rename = "type")]
+    pub header_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub items: Option<Box<Schema>>,
+}
+
+/// A schema. Swagger 2.0 schemas are OpenApi 3.0 schemas, except for `discriminator`, which
+/// is the name of the property alone.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Default)]
+pub struct Schema {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub discriminator: Option<String>,
+    #[serde(flatten)]
+    pub schema: SchemaV3,
+}
+
+/// A security scheme that can be used by the operations.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(tag = "type")]
+pub enum SecurityScheme {
+    #[serde(rename = "basic")]
+    Basic,
+    #[serde(rename = "apiKey")]
+    ApiKey {
+        name: String,
+        #[serde(rename = "in")]
+        location: String,
+    },
+    #[serde(rename = "oauth2")]
+    OAuth2 {
+        /// `implicit`, `password`, `application` or `accessCode`.
+        flow: String,
+        #[serde(skip_serializing_if = "Option::is_none", rename = "authorizationUrl")]
+        authorization_url: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none", rename = "tokenUrl")]
+        token_url: Option<String>,
+        scopes: Map<String, String>,
+    },
+}
+
+impl Swagger {
+    /// Convert to an OpenApi 3.0 `Spec`.
+    pub fn convert(&self) -> Conversion {
+        let mut converter = Converter {
+            swagger: self,
+            lossy: Vec::new(),
+        };
+        let spec = converter.spec();
+
+        Conversion {
+            spec,
+            lossy: converter.lossy,
+        }
+    }
+}
+
+struct Converter<'a> {
+    swagger: &'a Swagger,
+    lossy: Vec<LossyConversion>,
+}
+
+impl<'a> Converter<'a> {
+    fn lossy(&mut self, path: &str, message: &str) {
+        self.lossy.push(LossyConversion {
+            path: path.to_string(),
+            message: message.to_string(),
+        });
+    }
+
+    fn spec(&mut self) -> Spec {
+        let swagger = self.swagger;
+        let paths = swagger
+            .paths
+            .iter()
+            .map(|(path, path_item)| {
+                let converted = self.path_item(path_item, &pointer::push("/paths", path));
+                (path.clone(), converted)
+            })
+            .collect();
+
+        Spec {
+            openapi: OPENAPI_VERSION.to_string(),
+            info: swagger.info.clone(),
+            servers: self.servers(),
+            paths,
+            webhooks: None,
+            json_schema_dialect: None,
+            components: Some(self.components()),
+            security: swagger.security.clone(),
+            tags: swagger.tags.clone(),
+            external_docs: swagger.external_docs.clone(),
+            extensions: self.extensions(),
+        }
+    }
+
+    /// The `x-` extensions of the document, the other fields it does not describe dropped.
+    fn extensions(&mut self) -> Map<String, Value> {
+        let mut extensions = Map::new();
+        for (key, value) in self.swagger.extensions.iter() {
+            if key.starts_with("x-") {
+                extensions.insert(key.clone(), value.clone());
+            } else {
+                self.lossy(&pointer::push("", key), "unknown Swagger 2.0 field");
+            }
+        }
+        extensions
+    }
+
+    /// `host`, `basePath` and `schemes` become one server per scheme.
+    fn servers(&mut self) -> Option<Vec<Server>> {
+        let swagger = self.swagger;
+        let base_path = swagger.base_path.clone().unwrap_or_default();
+
+        let host = match swagger.host {
+            Some(ref host) => host,
+            None if base_path.is_empty() => return None,
+            None => return Some(vec![Server { url: base_path, ..Default::default() }]),
+        };
+
+        let schemes = match swagger.schemes {
+            Some(ref schemes) if !schemes.is_empty() => schemes.clone(),
+            _ => {
+                self.lossy("/schemes", "no schemes declared, assuming https");
+                vec!["https".to_string()]
+            }
+        };
+
+        Some(
+            schemes
+                .iter()
+                .map(|scheme| Server {
+                    url: format!("{}://{}{}", scheme, host, base_path),
+                    ..Default::default()
+                })
+                .collect(),
+        )
+    }
+
+    fn components(&mut self) -> Components {
+        let swagger = self.swagger;
+        let mut components = Components::default();
+
+        components.schemas = swagger.definitions.as_ref().map(|definitions| {
+            definitions
+                .iter()
+                .map(|(name, schema)| (name.clone(), ObjectOrReference::Object(convert_schema(schema))))
+                .collect()
+        });
+
+        let mut parameters = Map::new();
+        let mut request_bodies = Map::new();
+        for (name, parameter) in swagger.parameters.iter().flatten() {
+            let path = pointer::push("/parameters", name);
+            match parameter.location.as_str() {
+                "body" => {
+                    let consumes = self.media_types(swagger.consumes.as_ref(), None);
+                    request_bodies.insert(name.clone(), ObjectOrReference::Object(body_request(parameter, &consumes)));
+                }
+                "formData" => self.lossy(&path, "shared formData parameters cannot be expressed as a component"),
+                _ => {
+                    let converted = self.parameter(parameter, &path);
+                    parameters.insert(name.clone(), ObjectOrReference::Object(converted));
+                }
+            }
+        }
+        if !parameters.is_empty() {
+            components.parameters = Some(parameters);
+        }
+        if !request_bodies.is_empty() {
+            components.request_bodies = Some(request_bodies);
+        }
+
+        components.responses = swagger.responses.as_ref().map(|responses| {
+            let produces = self.media_types(None, swagger.produces.as_ref());
+            responses
+                .iter()
+                .map(|(name, response)| (name.clone(), ObjectOrReference::Object(convert_response(response, &produces))))
+                .collect()
+        });
+
+        components.security_schemes = swagger.security_definitions.as_ref().map(|definitions| {
+            definitions
+                .iter()
+                .map(|(name, scheme)| (name.clone(), ObjectOrReference::Object(convert_security_scheme(scheme))))
+                .collect()
+        });
+
+        components
+    }
+
+    fn path_item(&mut self, path_item: &PathItem, path: &str) -> PathItemV3 {
+        if path_item.reference.is_some() {
+            self.lossy(path, "external path item references are kept as is");
+        }
+
+        let mut converted = PathItemV3 {
+            reference: path_item.reference.clone(),
+            ..Default::default()
+        };
+        let shared = path_item.parameters.clone().unwrap_or_default();
+        let operations = vec![
+            ("get", &path_item.get, &mut converted.get),
+            ("put", &path_item.put, &mut converted.put),
+            ("post", &path_item.post, &mut converted.post),
+            ("delete", &path_item.delete, &mut converted.delete),
+            ("options", &path_item.options, &mut converted.options),
+            ("head", &path_item.head, &mut converted.head),
+            ("patch", &path_item.patch, &mut converted.patch),
+        ];
+        for (method, operation, target) in operations {
+            if let Some(operation) = operation {
+                *target = Some(self.operation(operation, &shared, &pointer::push(path, method)));
+            }
+        }
+
+        converted
+    }
+
+    fn operation(
+        &mut self,
+        operation: &Operation,
+        shared: &[ObjectOrReference<Parameter>],
+        path: &str,
+    ) -> OperationV3 {
+        let swagger = self.swagger;
+        let consumes = self.media_types(operation.consumes.as_ref().or_else(|| swagger.consumes.as_ref()), None);
+        let produces = self.media_types(operation.produces.as_ref().or_else(|| swagger.produces.as_ref()), None);
+
+        if operation.schemes.is_some() {
+            self.lossy(&pointer::push(path, "schemes"), "operation level schemes are not supported");
+        }
+
+        let mut parameters = Vec::new();
+        let mut request_body = None;
+        let mut form_data = Vec::new();
+
+        // operation parameters override path parameters with the same name and location
+        let mut all: Vec<&ObjectOrReference<Parameter>> = operation.parameters.iter().flatten().collect();
+        for parameter in shared.iter() {
+            let key = self.parameter_key(parameter);
+            if !all.iter().any(|existing| self.parameter_key(existing) == key) {
+                all.push(parameter);
+            }
+        }
+
+        for (index, parameter) in all.into_iter().enumerate() {
+            let parameter_path = pointer::push(&pointer::push(path, "parameters"), &index.to_string());
+            match parameter {
+                ObjectOrReference::Ref { ref_path } => {
+                    let name = ref_path.rsplit('/').next().unwrap_or("");
+                    match self.shared_parameter(ref_path).map(|p| p.location.as_str()) {
+                        Some("body") => {
+                            request_body = Some(ObjectOrReference::Ref {
+                                ref_path: format!("#/components/requestBodies/{}", name),
+                            })
+                        }
+                        Some("formData") => {
+                            if let Some(shared) = self.shared_parameter(ref_path) {
+                                form_data.push(shared.clone());
+                            }
+                        }
+                        _ => parameters.push(ObjectOrReference::Ref {
+                            ref_path: format!("#/components/parameters/{}", name),
+                        }),
+                    }
+                }
+                ObjectOrReference::Object(parameter) => match parameter.location.as_str() {
+                    "body" => {
+                        if request_body.is_some() {
+                            self.lossy(&parameter_path, "only one body parameter is supported");
+                        } else {
+                            request_body = Some(ObjectOrReference::Object(body_request(parameter, &consumes)));
+                        }
+                    }
+                    "formData" => form_data.push(parameter.clone()),
+                    _ => parameters.push(ObjectOrReference::Object(self.parameter(parameter, &parameter_path))),
+                },
+            }
+        }
+
+        if !form_data.is_empty() {
+            if request_body.is_some() {
+                self.lossy(path, "body and formData parameters cannot be combined");
+            } else {
+                request_body = Some(ObjectOrReference::Object(form_request(&form_data, &consumes)));
+            }
+        }
+
+        let responses = operation
+            .responses
+            .iter()
+            .map(|(code, response)| {
+                let converted = match response {
+                    ObjectOrReference::Object(response) => convert_response(response, &produces),
+                    ObjectOrReference::Ref { ref_path } => {
+                        // responses are not references in the 3.0 model, so inline the shared one
+                        let name = ref_path.rsplit('/').next().unwrap_or("");
+                        match swagger.responses.as_ref().and_then(|responses| responses.get(name)) {
+                            Some(response) => convert_response(response, &produces),
+                            None => {
+                                self.lossy(&pointer::push(&pointer::push(path, "responses"), code), "unresolved response reference");
+                                ResponseV3::default()
+                            }
+                        }
+                    }
+                };
+                (code.clone(), converted)
+            })
+            .collect();
+
+        OperationV3 {
+            tags: operation.tags.clone(),
+            summary: operation.summary.clone(),
+            description: operation.description.clone(),
+            external_docs: operation.external_docs.clone(),
+            operation_id: operation.operation_id.clone(),
+            parameters: if parameters.is_empty() { None } else { Some(parameters) },
+            request_body,
+            responses,
+            callbacks: None,
+            deprecated: operation.deprecated,
+            security: operation.security.clone(),
+            servers: None,
+            extensions: Map::new(),
+        }
+    }
+
+    fn parameter_key(&self, parameter: &ObjectOrReference<Parameter>) -> Option<(String, String)> {
+        let parameter = match parameter {
+            ObjectOrReference::Object(parameter) => parameter,
+            ObjectOrReference::Ref { ref_path } => self.shared_parameter(ref_path)?,
+        };
+        Some((parameter.name.clone(), parameter.location.clone()))
+    }
+
+    fn shared_parameter(&self, ref_path: &str) -> Option<&'a Parameter> {
+        let name = ref_path.trim_start_matches("#/parameters/");
+        self.swagger.parameters.as_ref()?.get(name)
+    }
+
+    /// A `query`, `header` or `path` parameter, with `collectionFormat` mapped to `style`
+    /// and `explode`.
+    fn parameter(&mut self, parameter: &Parameter, path: &str) -> ParameterV3 {
+        let (style, explode) = match parameter.collection_format.as_ref().map(String::as_str) {
+            None => (None, None),
+            Some("csv") if parameter.location == "query" => (Some(ParameterStyle::Form), Some(false)),
+            Some("csv") => (Some(ParameterStyle::Simple), Some(false)),
+            Some("ssv") => (Some(ParameterStyle::SpaceDelimited), Some(false)),
+            Some("pipes") => (Some(ParameterStyle::PipeDelimited), Some(false)),
+            Some("multi") => (Some(ParameterStyle::Form), Some(true)),
+            Some(other) => {
+                self.lossy(
+                    &pointer::push(path, "collectionFormat"),
+                    &format!("collectionFormat `{}` has no equivalent style", other),
+                );
+                (None, None)
+            }
+        };
+
+        ParameterV3 {
+            name: parameter.name.clone(),
+            location: parameter.location.clone(),
+            required: parameter.required,
+            schema: Some(parameter_schema(parameter)),
+            description: parameter.description.clone(),
+            style,
+            explode,
+            ..Default::default()
+        }
+    }
+
+    /// The media types of an operation, defaulting to `application/json`.
+    fn media_types(&self, declared: Option<&Vec<String>>, fallback: Option<&Vec<String>>) -> Vec<String> {
+        declared
+            .or(fallback)
+            .filter(|media_types| !media_types.is_empty())
+            .cloned()
+            .unwrap_or_else(|| vec![DEFAULT_MEDIA_TYPE.to_string()])
+    }
+}
+
+/// Copy a schema, pointing `#/definitions/` references at `#/components/schemas/`.
+fn convert_schema(schema: &Schema) -> SchemaV3 {
+    let mut converted = schema.schema.clone();
+    if let Some(ref property_name) = schema.discriminator {
+        converted.discriminator = Some(Discriminator {
+            property_name: property_name.clone(),
+            mapping: None,
+        });
+    }
+    rewrite_refs(&mut converted);
+    converted
+}
+
+fn rewrite_refs(schema: &mut SchemaV3) {
+    if let Some(ref mut ref_path) = schema.ref_path {
+        if ref_path.starts_with("#/definitions/") {
+            *ref_path = ref_path.replacen("#/definitions/", "#/components/schemas/", 1);
+        }
+    }
+    if let Some(ref mut items) = schema.items {
+        rewrite_refs(items);
+    }
+    for property in schema.properties.iter_mut().flat_map(|properties| properties.values_mut()) {
+        rewrite_refs(property);
+    }
+    if let Some(ObjectOrReference::Object(ref mut additional)) = schema.additional_properties {
+        rewrite_refs(additional);
+    }
+    for part in schema.all_of.iter_mut().flatten() {
+        match part {
+            ObjectOrReference::Object(part) => rewrite_refs(part),
+            ObjectOrReference::Ref { ref_path } => {
+                *ref_path = ref_path.replacen("#/definitions/", "#/components/schemas/", 1)
+            }
+        }
+    }
+}
+
+/// The schema of a non `body` parameter, built from its inline type keywords.
+fn parameter_schema(parameter: &Parameter) -> SchemaV3 {
+    let file = parameter.param_type.as_ref().map(String::as_str) == Some("file");
+
+    SchemaV3 {
+        schema_type: if file { Some("string".into()) } else { parameter.param_type.clone().map(SchemaType::from) },
+        format: if file { Some("binary".to_string()) } else { parameter.format.clone() },
+        items: parameter.items.as_ref().map(|items| Box::new(convert_schema(items))),
+        default: parameter.default.clone(),
+        enum_values: parameter.enum_values.clone(),
+        minimum: parameter.minimum.clone(),
+        maximum: parameter.maximum.clone(),
+        min_length: parameter.min_length,
+        max_length: parameter.max_length,
+        pattern: parameter.pattern.clone(),
+        description: None,
+        ..Default::default()
+    }
+}
+
+fn body_request(parameter: &Parameter, consumes: &[String]) -> RequestBody {
+    let schema = parameter.schema.as_ref().map(convert_schema).unwrap_or_default();
+
+    RequestBody {
+        description: parameter.description.clone(),
+        content: consumes
+            .iter()
+            .map(|media_type| {
+                (
+                    media_type.clone(),
+                    MediaType {
+                        schema: Some(ObjectOrReference::Object(schema.clone())),
+                        ..Default::default()
+                    },
+                )
+            })
+            .collect(),
+        required: parameter.required,
+        extensions: Map::new(),
+    }
+}
+
+/// `formData` parameters become the properties of an object schema.
+fn form_request(parameters: &[Parameter], consumes: &[String]) -> RequestBody {
+    let has_file = parameters.iter().any(|p| p.param_type.as_ref().map(String::as_str) == Some("file"));
+    let mut media_types: Vec<String> = consumes
+        .iter()
+        .filter(|media_type| *media_type == FORM_URLENCODED || *media_type == MULTIPART_FORM_DATA)
+        .cloned()
+        .collect();
+    if media_types.is_empty() {
+        media_types.push(if has_file { MULTIPART_FORM_DATA } else { FORM_URLENCODED }.to_string());
+    }
+
+    let required: Vec<String> = parameters
+        .iter()
+        .filter(|p| p.required == Some(true))
+        .map(|p| p.name.clone())
+        .collect();
+    let schema = SchemaV3 {
+        schema_type: Some("object".into()),
+        properties: Some(
+            parameters
+                .iter()
+                .map(|p| {
+                    let mut schema = parameter_schema(p);
+                    schema.description = p.description.clone();
+                    (p.name.clone(), schema)
+                })
+                .collect(),
+        ),
+        required: if required.is_empty() { None } else { Some(required) },
+        ..Default::default()
+    };
+
+    RequestBody {
+        description: None,
+        content: media_types
+            .into_iter()
+            .map(|media_type| {
+                (
+                    media_type,
+                    MediaType {
+                        schema: Some(ObjectOrReference::Object(schema.clone())),
+                        ..Default::default()
+                    },
+                )
+            })
+            .collect(),
+        required: Some(parameters.iter().any(|p| p.required == Some(true))),
+        extensions: Map::new(),
+    }
+}
+
+fn convert_response(response: &Response, produces: &[String]) -> ResponseV3 {
+    let content = response.schema.as_ref().map(|schema| {
+        let schema = convert_schema(schema);
+        produces
+            .iter()
+            .map(|media_type| {
+                let example = response
+                    .examples
+                    .as_ref()
+                    .and_then(|examples| examples.get(media_type))
+                    .map(|example| MediaTypeExample::Example { example: example.clone() });
+                (
+                    media_type.clone(),
+                    MediaType {
+                        schema: Some(ObjectOrReference::Object(schema.clone())),
+                        examples: example,
+                        encoding: None,
+                        extensions: Map::new(),
+                    },
+                )
+            })
+            .collect()
+    });
+
+    let headers = response.headers.as_ref().map(|headers| {
+        headers
+            .iter()
+            .map(|(name, header)| {
+                let converted = HeaderV3 {
+                    description: header.description.clone(),
+                    schema: Some(SchemaV3 {
+                        schema_type: Some(header.header_type.clone().into()),
+                        format: header.format.clone(),
+                        items: header.items.as_ref().map(|items| Box::new(convert_schema(items))),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                };
+                (name.clone(), ObjectOrReference::Object(converted))
+            })
+            .collect()
+    });
+
+    ResponseV3 {
+        description: Some(response.description.clone()),
+        headers,
+        content,
+        links: None,
+        extensions: Map::new(),
+    }
+}
+
+fn convert_security_scheme(scheme: &SecurityScheme) -> SecuritySchemeV3 {
+    match scheme {
+        SecurityScheme::Basic => SecuritySchemeV3::Http {
+            scheme: "basic".to_string(),
+            bearer_format: None,
+            extensions: Map::new(),
+        },
+        SecurityScheme::ApiKey { name, location } => SecuritySchemeV3::ApiKey {
+            name: name.clone(),
+            location: location.clone(),
+            extensions: Map::new(),
+        },
+        SecurityScheme::OAuth2 {
+            flow,
+            authorization_url,
+            token_url,
+            scopes,
+        } => {
+            let config = OAuthFlow {
+                authorization_url: authorization_url.clone(),
+                token_url: token_url.clone(),
+                refresh_url: None,
+                scopes: scopes.clone(),
+            };
+            let mut flows = OAuthFlows::default();
+            match flow.as_str() {
+                "implicit" => flows.implicit = Some(config),
+                "password" => flows.password = Some(config),
+                "application" => flows.client_credentials = Some(config),
+                _ => flows.authorization_code = Some(config),
+            }
+            SecuritySchemeV3::OAuth2 {
+                flows,
+                extensions: Map::new(),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::File;
+
+    fn petstore() -> Swagger {
+        serde_yaml::from_reader(File::open("./data/v2.0/petstore.yaml").unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_convert_servers() {
+        let conversion = petstore().convert();
+        let servers = conversion.spec.servers.unwrap();
+
+        assert_eq!(servers.len(), 1);
+        assert_eq!(servers[0].url, "http://petstore.swagger.io/v1");
+    }
+
+    #[test]
+    fn test_convert_definitions() {
+        let spec = petstore().convert().spec;
+        let schemas = spec.components.unwrap().schemas.unwrap();
+
+        match schemas.get("Pets") {
+            Some(ObjectOrReference::Object(schema)) => assert_eq!(
+                schema.items.as_ref().unwrap().ref_path,
+                Some("#/components/schemas/Pet".to_string())
+            ),
+            _ => panic!("expected an inline Pets schema"),
+        }
+    }
+
+    #[test]
+    fn test_convert_body_and_collection_format() {
+        let conversion = petstore().convert();
+        let pets = &conversion.spec.paths["/pets"];
+
+        let request_body = match pets.post.as_ref().unwrap().request_body {
+            Some(ObjectOrReference::Object(ref request_body)) => request_body,
+            _ => panic!("expected an inline request body"),
+        };
+        assert!(request_body.content.contains_key("application/json"));
+
+        let parameters = pets.get.as_ref().unwrap().parameters.as_ref().unwrap();
+        match parameters[1] {
+            ObjectOrReference::Object(ref parameter) => {
+                assert_eq!(parameter.style, Some(ParameterStyle::Form));
+                assert_eq!(parameter.explode, Some(true));
+            }
+            _ => panic!("expected an inline parameter"),
+        }
+
+        assert_eq!(conversion.lossy.len(), 1);
+        assert_eq!(conversion.lossy[0].path, "/paths/~1pets/get/parameters/2/collectionFormat");
+    }
+
+    #[test]
+    fn test_convert_extensions() {
+        let swagger: Swagger = serde_yaml::from_str(
+            "swagger: '2.0'\ninfo: {title: Pets, version: '1', x-logo: logo.png}\npaths: {}\nx-gateway: {timeout: 5}\nbasepath: /v1\n",
+        )
+        .unwrap();
+        let conversion = swagger.convert();
+
+        assert_eq!(conversion.spec.info.extensions["x-logo"], "logo.png");
+        assert_eq!(conversion.spec.extensions["x-gateway"]["timeout"], 5);
+        assert!(!conversion.spec.extensions.contains_key("basepath"));
+        assert_eq!(conversion.lossy.len(), 1);
+        assert_eq!(conversion.lossy[0].path, "/basepath");
+    }
+
+    #[test]
+    fn test_convert_discriminator() {
+        let swagger: Swagger =
+            serde_yaml::from_reader(File::open("./data/v2.0/petstore-discriminator.yaml").unwrap()).unwrap();
+        let schemas = swagger.convert().spec.components.unwrap().schemas.unwrap();
+
+        match schemas.get("Pet") {
+            Some(ObjectOrReference::Object(schema)) => {
+                let discriminator = schema.discriminator.as_ref().unwrap();
+                assert_eq!(discriminator.property_name, "petType");
+                assert_eq!(discriminator.mapping, None);
+            }
+            _ => panic!("expected an inline Pet schema"),
+        }
+
+        // the string form is Swagger 2.0 only
+        assert!(serde_yaml::from_str::<SchemaV3>("discriminator: petType").is_err());
+    }
+}
@@ -0,0 +1,510 @@
+//! Validate JSON values against the `Schema` objects of a spec.
+
+use crate::Map;
+use std::fmt;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::components::ObjectOrReference;
+use crate::operation::Operation;
+use crate::path::Parameter;
+use crate::pointer;
+use crate::spec::{Example, MediaType, MediaTypeExample, RequestBody, Response, Schema, Spec};
+
+/// A value that does not conform to its schema.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ValidationError {
+    /// JSON pointer of the offending value within the validated instance.
+    pub instance_path: String,
+    /// JSON pointer of the violated schema within the spec.
+    pub schema_path: String,
+    /// What is wrong with the value.
+    pub message: String,
+}
+
+/// Validate `value` against `schema`, returning every violation found.
+/// `schema_path` is the location of `schema` within the spec and is used for reporting.
+pub fn validate(spec: &Spec, schema: &Schema, schema_path: &str, value: &Value) -> Vec<ValidationError> {
+    let mut errors = Vec::new();
+    validate_into(spec, schema, schema_path, value, "", &mut errors);
+    errors
+}
+
+fn validate_into(
+    spec: &Spec,
+    schema: &Schema,
+    schema_path: &str,
+    value: &Value,
+    instance_path: &str,
+    errors: &mut Vec<ValidationError>,
+) {
+    let schema_path = match schema.ref_path {
+        Some(ref ref_path) => ref_path.trim_start_matches('#').to_string(),
+        None => schema_path.to_string(),
+    };
+    let schema = spec.resolve_schema(schema);
+    let mut error = |schema_path: &str, message: String| {
+        errors.push(ValidationError {
+            instance_path: instance_path.to_string(),
+            schema_path: schema_path.to_string(),
+            message,
+        })
+    };
+
+    if value.is_null() && schema.is_nullable() {
+        return;
+    }
+
+    if let Some(ref schema_type) = schema.schema_type {
+        let types = schema_type.types();
+        if !types.iter().any(|schema_type| matches_type(schema_type, value)) {
+            error(
+                &pointer::push(&schema_path, "type"),
+                format!("expected {}, found {}", types.join(" or "), type_name(value)),
+            );
+            return;
+        }
+    }
+
+    if let Some(ref expected) = schema.const_value {
+        if value != expected {
+            error(&pointer::push(&schema_path, "const"), format!("expected {}, found {}", expected, value));
+        }
+    }
+
+    if let Some(ref enum_values) = schema.enum_values {
+        let found = match value {
+            Value::String(s) => enum_values.contains(s),
+            other => enum_values.contains(&other.to_string()),
+        };
+        if !found {
+            error(
+                &pointer::push(&schema_path, "enum"),
+                format!("{} is not one of {:?}", value, enum_values),
+            );
+        }
+    }
+
+    match value {
+        Value::Number(ref number) => {
+            let number = number.as_f64().unwrap_or_default();
+            if let Some((minimum, exclusive)) = schema.minimum_bound() {
+                if number < minimum || (exclusive && number == minimum) {
+                    error(&pointer::push(&schema_path, "minimum"), format!("{} is less than {}", number, minimum));
+                }
+            }
+            if let Some((maximum, exclusive)) = schema.maximum_bound() {
+                if number > maximum || (exclusive && number == maximum) {
+                    error(&pointer::push(&schema_path, "maximum"), format!("{} is greater than {}", number, maximum));
+                }
+            }
+        }
+        Value::String(ref string) => {
+            let len = string.chars().count() as u64;
+            if schema.min_length.map_or(false, |min| len < min) {
+                error(&pointer::push(&schema_path, "minLength"), format!("`{}` is too short", string));
+            }
+            if schema.max_length.map_or(false, |max| len > max) {
+                error(&pointer::push(&schema_path, "maxLength"), format!("`{}` is too long", string));
+            }
+        }
+        Value::Array(ref values) => {
+            let len = values.len() as u64;
+            if let Some(min) = schema.min_items.filter(|min| len < *min) {
+                error(&pointer::push(&schema_path, "minItems"), format!("expected at least {} items, found {}", min, len));
+            }
+            if let Some(max) = schema.max_items.filter(|max| len > *max) {
+                error(&pointer::push(&schema_path, "maxItems"), format!("expected at most {} items, found {}", max, len));
+            }
+        }
+        _ => {}
+    }
+
+    if let Value::Object(ref map) = value {
+        if let Some(ref required) = schema.required {
+            for name in required.iter().filter(|name| !map.contains_key(*name)) {
+                error(
+                    &pointer::push(&schema_path, "required"),
+                    format!("missing required property `{}`", name),
+                );
+            }
+        }
+
+        let properties_path = pointer::push(&schema_path, "properties");
+        for (name, property_value) in map.iter() {
+            let instance_path = pointer::push(instance_path, name);
+            match schema.properties.as_ref().and_then(|properties| properties.get(name)) {
+                Some(property) => validate_into(
+                    spec,
+                    property,
+                    &pointer::push(&properties_path, name),
+                    property_value,
+                    &instance_path,
+                    errors,
+                ),
+                None => {
+                    if let Some(additional) = schema.additional_properties.as_ref().and_then(|a| match a {
+                        ObjectOrReference::Object(schema) => Some(schema),
+                        _ => None,
+                    }) {
+                        validate_into(
+                            spec,
+                            additional,
+                            &pointer::push(&schema_path, "additionalProperties"),
+                            property_value,
+                            &instance_path,
+                            errors,
+                        )
+                    }
+                }
+            }
+        }
+    }
+
+    if let (Value::Array(ref values), Some(ref items)) = (value, &schema.items) {
+        let items_path = pointer::push(&schema_path, "items");
+        for (index, item) in values.iter().enumerate() {
+            validate_into(
+                spec,
+                items,
+                &items_path,
+                item,
+                &pointer::push(instance_path, &index.to_string()),
+                errors,
+            );
+        }
+    }
+
+    if let Some(ref all_of) = schema.all_of {
+        let schemas = spec.components.as_ref().and_then(|c| c.schemas.as_ref());
+        for (index, part) in all_of.iter().enumerate() {
+            if let Some(part) = part.resolve(schemas) {
+                validate_into(
+                    spec,
+                    part,
+                    &pointer::push(&pointer::push(&schema_path, "allOf"), &index.to_string()),
+                    value,
+                    instance_path,
+                    errors,
+                );
+            }
+        }
+    }
+
+    if let Some(ref one_of) = schema.one_of {
+        let schemas = spec.components.as_ref().and_then(|c| c.schemas.as_ref());
+        let matches = one_of
+            .iter()
+            .filter_map(|part| part.resolve(schemas))
+            .filter(|part| validate(spec, part, "", value).is_empty())
+            .count();
+        if matches != 1 {
+            errors.push(ValidationError {
+                instance_path: instance_path.to_string(),
+                schema_path: pointer::push(&schema_path, "oneOf"),
+                message: format!("expected exactly one oneOf schema to match, {} did", matches),
+            });
+        }
+    }
+}
+
+/// An example that does not conform to the schema it illustrates.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ExampleError {
+    /// JSON pointer of the example within the spec.
+    pub example_path: String,
+    /// The violation. Its `instance_path` is relative to the example.
+    pub error: ValidationError,
+}
+
+impl fmt::Display for ExampleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}{}: {} (schema {})",
+            self.example_path, self.error.instance_path, self.error.message, self.error.schema_path
+        )
+    }
+}
+
+/// Validate every example of the spec against its schema: media type `example` and
+/// `examples` (including those referenced from `components.examples`), parameter examples
+/// and schema examples, both inline and in `components`.
+pub fn validate_examples(spec: &Spec) -> Vec<ExampleError> {
+    let mut checker = ExampleChecker {
+        spec,
+        errors: Vec::new(),
+    };
+
+    for (path, path_item) in spec.paths.iter() {
+        let path_pointer = pointer::push("/paths", path);
+        checker.parameters(path_item.parameters.as_ref(), &path_pointer);
+        for (method, operation) in path_item.operations() {
+            let method = method.as_str().to_lowercase();
+            checker.operation(operation, &pointer::push(&path_pointer, &method));
+        }
+    }
+
+    if let Some(ref components) = spec.components {
+        for (name, schema) in components.schemas.iter().flatten() {
+            if let ObjectOrReference::Object(ref schema) = *schema {
+                checker.schema(schema, &pointer::push("/components/schemas", name));
+            }
+        }
+        for (name, parameter) in components.parameters.iter().flatten() {
+            if let ObjectOrReference::Object(ref parameter) = *parameter {
+                checker.parameter(parameter, &pointer::push("/components/parameters", name));
+            }
+        }
+        for (name, request_body) in components.request_bodies.iter().flatten() {
+            if let ObjectOrReference::Object(ref request_body) = *request_body {
+                checker.request_body(request_body, &pointer::push("/components/requestBodies", name));
+            }
+        }
+        for (name, response) in components.responses.iter().flatten() {
+            if let ObjectOrReference::Object(ref response) = *response {
+                checker.response(response, &pointer::push("/components/responses", name));
+            }
+        }
+    }
+
+    checker.errors
+}
+
+struct ExampleChecker<'a> {
+    spec: &'a Spec,
+    errors: Vec<ExampleError>,
+}
+
+impl<'a> ExampleChecker<'a> {
+    fn check(&mut self, example_path: &str, schema: &Schema, schema_path: &str, value: &Value) {
+        let errors = validate(self.spec, schema, schema_path, value);
+        self.errors.extend(errors.into_iter().map(|error| ExampleError {
+            example_path: example_path.to_string(),
+            error,
+        }));
+    }
+
+    fn operation(&mut self, operation: &Operation, path: &str) {
+        self.parameters(operation.parameters.as_ref(), path);
+        if let Some(ObjectOrReference::Object(ref request_body)) = operation.request_body {
+            self.request_body(request_body, &pointer::push(path, "requestBody"));
+        }
+        let responses = pointer::push(path, "responses");
+        for (code, response) in operation.responses.iter() {
+            self.response(response, &pointer::push(&responses, code));
+        }
+    }
+
+    fn parameters(&mut self, parameters: Option<&Vec<ObjectOrReference<Parameter>>>, path: &str) {
+        let path = pointer::push(path, "parameters");
+        for (index, parameter) in parameters.into_iter().flatten().enumerate() {
+            if let ObjectOrReference::Object(ref parameter) = *parameter {
+                self.parameter(parameter, &pointer::push(&path, &index.to_string()));
+            }
+        }
+    }
+
+    fn parameter(&mut self, parameter: &Parameter, path: &str) {
+        let schema = match parameter.schema {
+            Some(ref schema) => schema,
+            None => return,
+        };
+        let schema_path = pointer::push(path, "schema");
+        self.schema(schema, &schema_path);
+
+        if let Some(ref example) = parameter.example {
+            self.check(&pointer::push(path, "example"), schema, &schema_path, example);
+        }
+        self.examples(parameter.examples.as_ref(), path, schema, &schema_path);
+    }
+
+    fn request_body(&mut self, request_body: &RequestBody, path: &str) {
+        self.content(Some(&request_body.content), path);
+    }
+
+    fn response(&mut self, response: &Response, path: &str) {
+        self.content(response.content.as_ref(), path);
+    }
+
+    fn content(&mut self, content: Option<&Map<String, MediaType>>, path: &str) {
+        let path = pointer::push(path, "content");
+        for (media_type_name, media_type) in content.into_iter().flatten() {
+            self.media_type(media_type, &pointer::push(&path, media_type_name));
+        }
+    }
+
+    fn media_type(&mut self, media_type: &MediaType, path: &str) {
+        let schema = match media_type.schema {
+            Some(ObjectOrReference::Object(ref schema)) => schema,
+            _ => return,
+        };
+        let schema_path = pointer::push(path, "schema");
+        self.schema(schema, &schema_path);
+
+        match media_type.examples {
+            Some(MediaTypeExample::Example { ref example }) => {
+                self.check(&pointer::push(path, "example"), schema, &schema_path, example)
+            }
+            Some(MediaTypeExample::Examples { ref examples }) => {
+                self.examples(Some(examples), path, schema, &schema_path)
+            }
+            None => {}
+        }
+    }
+
+    fn examples(
+        &mut self,
+        examples: Option<&Map<String, ObjectOrReference<Example>>>,
+        path: &str,
+        schema: &Schema,
+        schema_path: &str,
+    ) {
+        let spec = self.spec;
+        let components = spec.components.as_ref().and_then(|c| c.examples.as_ref());
+        let path = pointer::push(path, "examples");
+
+        for (name, example) in examples.into_iter().flatten() {
+            let example_path = match example {
+                ObjectOrReference::Object(_) => pointer::push(&pointer::push(&path, name), "value"),
+                ObjectOrReference::Ref { ref_path } => {
+                    pointer::push(ref_path.trim_start_matches('#'), "value")
+                }
+            };
+            if let Some(value) = example.resolve(components).and_then(|example| example.value.as_ref()) {
+                self.check(&example_path, schema, schema_path, value);
+            }
+        }
+    }
+
+    /// Check the example of a schema and of its nested inline schemas.
+    fn schema(&mut self, schema: &Schema, path: &str) {
+        // referenced schemas are checked where they are defined
+        if schema.ref_path.is_some() {
+            return;
+        }
+
+        if let Some(ref example) = schema.example {
+            self.check(&pointer::push(path, "example"), schema, path, example);
+        }
+        for (name, property) in schema.properties.iter().flatten() {
+            self.schema(property, &pointer::push(&pointer::push(path, "properties"), name));
+        }
+        if let Some(ref items) = schema.items {
+            self.schema(items, &pointer::push(path, "items"));
+        }
+        for (index, part) in schema.all_of.iter().flatten().enumerate() {
+            if let ObjectOrReference::Object(ref part) = *part {
+                self.schema(part, &pointer::push(&pointer::push(path, "allOf"), &index.to_string()));
+            }
+        }
+        for (index, part) in schema.one_of.iter().flatten().enumerate() {
+            if let ObjectOrReference::Object(ref part) = *part {
+                self.schema(part, &pointer::push(&pointer::push(path, "oneOf"), &index.to_string()));
+            }
+        }
+    }
+}
+
+fn matches_type(schema_type: &str, value: &Value) -> bool {
+    match schema_type {
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "boolean" => value.is_boolean(),
+        "null" => value.is_null(),
+        "number" => value.is_number(),
+        "integer" => value.is_i64() || value.is_u64() || value.as_f64().map_or(false, |n| n.fract() == 0.0),
+        _ => true,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_validate_component_schema() {
+        let spec = crate::from_path("./data/v3.0/petstore.yaml").unwrap();
+        let pet = Schema {
+            ref_path: Some("#/components/schemas/Pet".to_string()),
+            ..Default::default()
+        };
+
+        assert!(validate(&spec, &pet, "", &json!({"id": 1, "name": "doggie"})).is_empty());
+
+        let errors = validate(&spec, &pet, "", &json!({"id": "one"}));
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0].schema_path, "/components/schemas/Pet/required");
+        assert_eq!(errors[1].instance_path, "/id");
+        assert_eq!(errors[1].schema_path, "/components/schemas/Pet/properties/id/type");
+    }
+
+    #[test]
+    fn test_validate_examples() {
+        let mut spec = crate::from_path("./data/v3.0/uspto.yaml").unwrap();
+        assert!(validate_examples(&spec).is_empty());
+
+        let media_type = spec
+            .paths
+            .get_mut("/")
+            .and_then(|path_item| path_item.get.as_mut())
+            .and_then(|operation| operation.responses.get_mut("200"))
+            .and_then(|response| response.content.as_mut())
+            .and_then(|content| content.get_mut("application/json"))
+            .unwrap();
+        media_type.examples = Some(MediaTypeExample::Example { example: json!({"total": "two"}) });
+
+        let errors = validate_examples(&spec);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].example_path, "/paths/~1/get/responses/200/content/application~1json/example");
+        assert_eq!(errors[0].error.instance_path, "/total");
+        assert_eq!(errors[0].error.schema_path, "/components/schemas/dataSetList/properties/total/type");
+    }
+
+    #[test]
+    fn test_validate_referenced_examples() {
+        let spec = crate::from_str(
+            r#"
+openapi: 3.0.0
+info: {title: Pets, version: "1"}
+paths:
+  /pets:
+    get:
+      responses:
+        '200':
+          description: A pet
+          content:
+            application/json:
+              schema:
+                type: object
+                properties:
+                  name: {type: string}
+              examples:
+                cat: {$ref: '#/components/examples/Cat'}
+components:
+  examples:
+    Cat:
+      value: {name: 1}
+"#,
+        )
+        .unwrap();
+
+        let errors = validate_examples(&spec);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].example_path, "/components/examples/Cat/value");
+        assert_eq!(errors[0].error.instance_path, "/name");
+    }
+}
@@ -0,0 +1,95 @@
+//! Detect which specification a document follows before deserializing it.
+
+use semver::Version;
+use serde_yaml::Value;
+
+use crate::{Error, Result};
+
+/// The specification version declared by a document.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SpecVersion {
+    /// Declared by the `swagger` field.
+    Swagger(Version),
+    /// Declared by the `openapi` field.
+    OpenApi(Version),
+}
+
+/// Peek at the `openapi` and `swagger` fields of a document.
+pub fn detect(document: &Value) -> Result<SpecVersion> {
+    if let Some(version) = document.get("openapi").and_then(version_string) {
+        return Ok(SpecVersion::OpenApi(parse(&version)?));
+    }
+    if let Some(version) = document.get("swagger").and_then(version_string) {
+        return Ok(SpecVersion::Swagger(parse(&version)?));
+    }
+
+    Err(Error::MissingSpecFileVersion)
+}
+
+/// Replace an `openapi` or `swagger` version written without quotes, and so read as a
+/// number, by its string so that the document deserializes.
+pub fn quote(document: &mut Value) {
+    if let Value::Mapping(mapping) = document {
+        for field in ["openapi", "swagger"].iter() {
+            let key = Value::String(field.to_string());
+            if let Some(value) = mapping.get_mut(&key) {
+                if let (Value::Number(_), Some(version)) = (&*value, version_string(value)) {
+                    *value = Value::String(version);
+                }
+            }
+        }
+    }
+}
+
+/// Parse a version leniently, so that `2.0` and `3` are accepted as `2.0.0` and `3.0.0`.
+pub fn parse(version: &str) -> Result<Version> {
+    let version = version.trim();
+    let parts = version.split('-').next().unwrap_or("").split('.').count();
+    let padded = match parts {
+        1 => format!("{}.0.0", version),
+        2 => format!("{}.0", version),
+        _ => version.to_string(),
+    };
+
+    Ok(Version::parse(&padded)?)
+}
+
+/// Versions written without quotes are read as numbers, `2.0` as a float.
+fn version_string(value: &Value) -> Option<String> {
+    match value {
+        Value::String(version) => Some(version.clone()),
+        Value::Number(version) if version.is_f64() && !version.to_string().contains('.') => {
+            Some(format!("{}.0", version))
+        }
+        Value::Number(version) => Some(version.to_string()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect() {
+        let document: Value = serde_yaml::from_str("swagger: '2.0'").unwrap();
+        assert_eq!(detect(&document).unwrap(), SpecVersion::Swagger(Version::new(2, 0, 0)));
+
+        let document: Value = serde_yaml::from_str("openapi: 3.0.1").unwrap();
+        assert_eq!(detect(&document).unwrap(), SpecVersion::OpenApi(Version::new(3, 0, 1)));
+
+        let document: Value = serde_yaml::from_str("info: {}").unwrap();
+        assert!(detect(&document).is_err());
+    }
+
+    #[test]
+    fn test_quote() {
+        let mut document: Value = serde_yaml::from_str("swagger: 2.0").unwrap();
+        quote(&mut document);
+        assert_eq!(document["swagger"], Value::String("2.0".to_string()));
+
+        let mut document: Value = serde_yaml::from_str("openapi: 3.0").unwrap();
+        quote(&mut document);
+        assert_eq!(document["openapi"], Value::String("3.0".to_string()));
+    }
+}
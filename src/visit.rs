@@ -0,0 +1,586 @@
+//! Walk the tree of a `Spec`, from its paths down to the schemas nested in each other.
+//!
+//! A `Visit` implementation overrides the methods of the nodes it cares about. Each default
+//! method calls the `walk_` function of the same name, which visits the children of the
+//! node, so an override calls it too to keep descending. Every node comes with the JSON
+//! pointer of its location in the document, e.g.
+//! `/paths/~1pets/get/responses/200/content/application~1json/schema`.
+//!
+//! ```ignore
+//! struct Undocumented(Vec<String>);
+//!
+//! impl Visit for Undocumented {
+//!     fn visit_schema(&mut self, schema: &Schema, pointer: &str) {
+//!         if schema.ref_path.is_none() && schema.description.is_none() {
+//!             self.0.push(pointer.to_string());
+//!         }
+//!         walk_schema(self, schema, pointer);
+//!     }
+//! }
+//! ```
+//!
+//! `VisitMut` does the same with mutable references, for transformations. Both reach
+//! operations, parameters, request bodies, responses, headers, media types and schemas,
+//! in paths, webhooks and components. Callbacks are kept as plain JSON and are not walked.
+//!
+//! Every `$ref` is reported once with `visit_reference`. A node that is nothing but a `$ref`
+//! gets `visit_reference` instead of its own method, whether the model reads it as an
+//! `ObjectOrReference::Ref` or, for schemas and path items, as an object holding only the
+//! reference. A schema or path item with fields next to its `$ref` gets its own method, and
+//! its walk reports the reference.
+//!
+//! The lint rules are written as visitors. The utilities rewriting whole documents, such as
+//! `diff`, `merge`, `filter` and `cleanup`, walk the serialized document instead, as they
+//! follow references into callbacks, links, examples, security requirements and the
+//! keywords the model keeps as JSON, which a visitor does not reach.
+
+use actix_web::http::Method;
+
+use crate::components::ObjectOrReference;
+use crate::operation::Operation;
+use crate::path::{Parameter, PathItem};
+use crate::pointer;
+use crate::spec::{Header, MediaType, RequestBody, Response, Schema, Spec};
+
+/// The pointer token of a method, e.g. `get`.
+fn method_token(method: &Method) -> String {
+    method.as_str().to_lowercase()
+}
+
+/// Whether a schema is nothing but a `$ref`.
+fn is_reference(schema: &Schema) -> bool {
+    schema.ref_path.is_some() && Schema { ref_path: None, ..schema.clone() } == Schema::default()
+}
+
+/// Whether a path item is nothing but a `$ref`.
+fn is_item_reference(item: &PathItem) -> bool {
+    item.reference.is_some() && PathItem { reference: None, ..item.clone() } == PathItem::default()
+}
+
+/// Visits the nodes of a `Spec`. See the module documentation.
+pub trait Visit {
+    fn visit_spec(&mut self, spec: &Spec, pointer: &str) {
+        walk_spec(self, spec, pointer);
+    }
+
+    /// A path item, under `paths` or `webhooks` by its path or name, or under
+    /// `components/pathItems` by its component name.
+    fn visit_path_item(&mut self, path: &str, item: &PathItem, pointer: &str) {
+        walk_path_item(self, path, item, pointer);
+    }
+
+    fn visit_operation(&mut self, method: &Method, operation: &Operation, pointer: &str) {
+        walk_operation(self, method, operation, pointer);
+    }
+
+    fn visit_parameter(&mut self, parameter: &Parameter, pointer: &str) {
+        walk_parameter(self, parameter, pointer);
+    }
+
+    fn visit_request_body(&mut self, body: &RequestBody, pointer: &str) {
+        walk_request_body(self, body, pointer);
+    }
+
+    fn visit_response(&mut self, response: &Response, pointer: &str) {
+        walk_response(self, response, pointer);
+    }
+
+    fn visit_header(&mut self, header: &Header, pointer: &str) {
+        walk_header(self, header, pointer);
+    }
+
+    fn visit_media_type(&mut self, media_type: &MediaType, pointer: &str) {
+        walk_media_type(self, media_type, pointer);
+    }
+
+    fn visit_schema(&mut self, schema: &Schema, pointer: &str) {
+        walk_schema(self, schema, pointer);
+    }
+
+    /// A `$ref`, found at `pointer`, in place of any of the nodes above.
+    fn visit_reference(&mut self, _ref_path: &str, _pointer: &str) {}
+}
+
+fn visit_object_or_reference<V, T, F>(visitor: &mut V, object: &ObjectOrReference<T>, pointer: &str, visit: F)
+where
+    V: Visit + ?Sized,
+    F: FnOnce(&mut V, &T, &str),
+{
+    match object {
+        ObjectOrReference::Object(object) => visit(visitor, object, pointer),
+        ObjectOrReference::Ref { ref_path } => visitor.visit_reference(ref_path, pointer),
+    }
+}
+
+fn visit_schema_node<V: Visit + ?Sized>(visitor: &mut V, schema: &Schema, pointer: &str) {
+    match schema.ref_path {
+        Some(ref ref_path) if is_reference(schema) => visitor.visit_reference(ref_path, pointer),
+        _ => visitor.visit_schema(schema, pointer),
+    }
+}
+
+fn visit_path_item_node<V: Visit + ?Sized>(visitor: &mut V, path: &str, item: &PathItem, pointer: &str) {
+    match item.reference {
+        Some(ref ref_path) if is_item_reference(item) => visitor.visit_reference(ref_path, pointer),
+        _ => visitor.visit_path_item(path, item, pointer),
+    }
+}
+
+pub fn walk_spec<V: Visit + ?Sized>(visitor: &mut V, spec: &Spec, pointer: &str) {
+    let paths = pointer::push(pointer, "paths");
+    for (path, item) in spec.paths.iter() {
+        visit_path_item_node(visitor, path, item, &pointer::push(&paths, path));
+    }
+    let webhooks = pointer::push(pointer, "webhooks");
+    for (name, item) in spec.webhooks.iter().flatten() {
+        visit_path_item_node(visitor, name, item, &pointer::push(&webhooks, name));
+    }
+
+    let components = match spec.components {
+        Some(ref components) => components,
+        None => return,
+    };
+    let pointer = pointer::push(pointer, "components");
+    let kind = |kind: &str, name: &str| pointer::push(&pointer::push(&pointer, kind), name);
+    for (name, schema) in components.schemas.iter().flatten() {
+        visit_object_or_reference(visitor, schema, &kind("schemas", name), visit_schema_node);
+    }
+    for (name, response) in components.responses.iter().flatten() {
+        visit_object_or_reference(visitor, response, &kind("responses", name), V::visit_response);
+    }
+    for (name, parameter) in components.parameters.iter().flatten() {
+        visit_object_or_reference(visitor, parameter, &kind("parameters", name), V::visit_parameter);
+    }
+    for (name, body) in components.request_bodies.iter().flatten() {
+        visit_object_or_reference(visitor, body, &kind("requestBodies", name), V::visit_request_body);
+    }
+    for (name, header) in components.headers.iter().flatten() {
+        visit_object_or_reference(visitor, header, &kind("headers", name), V::visit_header);
+    }
+    for (name, item) in components.path_items.iter().flatten() {
+        visit_object_or_reference(visitor, item, &kind("pathItems", name), |visitor, item, pointer| {
+            visit_path_item_node(visitor, name, item, pointer)
+        });
+    }
+}
+
+pub fn walk_path_item<V: Visit + ?Sized>(visitor: &mut V, _path: &str, item: &PathItem, pointer: &str) {
+    if let Some(ref ref_path) = item.reference {
+        visitor.visit_reference(ref_path, pointer);
+    }
+    let parameters = pointer::push(pointer, "parameters");
+    for (i, parameter) in item.parameters.iter().flatten().enumerate() {
+        let pointer = pointer::push(&parameters, &i.to_string());
+        visit_object_or_reference(visitor, parameter, &pointer, V::visit_parameter);
+    }
+    for (method, operation) in item.operations() {
+        visitor.visit_operation(&method, operation, &pointer::push(pointer, &method_token(&method)));
+    }
+}
+
+pub fn walk_operation<V: Visit + ?Sized>(visitor: &mut V, _method: &Method, operation: &Operation, pointer: &str) {
+    let parameters = pointer::push(pointer, "parameters");
+    for (i, parameter) in operation.parameters.iter().flatten().enumerate() {
+        let pointer = pointer::push(&parameters, &i.to_string());
+        visit_object_or_reference(visitor, parameter, &pointer, V::visit_parameter);
+    }
+    if let Some(ref body) = operation.request_body {
+        visit_object_or_reference(visitor, body, &pointer::push(pointer, "requestBody"), V::visit_request_body);
+    }
+    let responses = pointer::push(pointer, "responses");
+    for (status, response) in operation.responses.iter() {
+        visitor.visit_response(response, &pointer::push(&responses, status));
+    }
+}
+
+pub fn walk_parameter<V: Visit + ?Sized>(visitor: &mut V, parameter: &Parameter, pointer: &str) {
+    if let Some(ref schema) = parameter.schema {
+        visit_schema_node(visitor, schema, &pointer::push(pointer, "schema"));
+    }
+}
+
+pub fn walk_request_body<V: Visit + ?Sized>(visitor: &mut V, body: &RequestBody, pointer: &str) {
+    let content = pointer::push(pointer, "content");
+    for (name, media_type) in body.content.iter() {
+        visitor.visit_media_type(media_type, &pointer::push(&content, name));
+    }
+}
+
+pub fn walk_response<V: Visit + ?Sized>(visitor: &mut V, response: &Response, pointer: &str) {
+    let headers = pointer::push(pointer, "headers");
+    for (name, header) in response.headers.iter().flatten() {
+        visit_object_or_reference(visitor, header, &pointer::push(&headers, name), V::visit_header);
+    }
+    let content = pointer::push(pointer, "content");
+    for (name, media_type) in response.content.iter().flatten() {
+        visitor.visit_media_type(media_type, &pointer::push(&content, name));
+    }
+}
+
+pub fn walk_header<V: Visit + ?Sized>(visitor: &mut V, header: &Header, pointer: &str) {
+    if let Some(ref schema) = header.schema {
+        visit_schema_node(visitor, schema, &pointer::push(pointer, "schema"));
+    }
+}
+
+pub fn walk_media_type<V: Visit + ?Sized>(visitor: &mut V, media_type: &MediaType, pointer: &str) {
+    if let Some(ref schema) = media_type.schema {
+        visit_object_or_reference(visitor, schema, &pointer::push(pointer, "schema"), visit_schema_node);
+    }
+    let encodings = pointer::push(pointer, "encoding");
+    for (property, encoding) in media_type.encoding.iter().flatten() {
+        let headers = pointer::push(&pointer::push(&encodings, property), "headers");
+        for (name, header) in encoding.headers.iter().flatten() {
+            visit_object_or_reference(visitor, header, &pointer::push(&headers, name), V::visit_header);
+        }
+    }
+}
+
+pub fn walk_schema<V: Visit + ?Sized>(visitor: &mut V, schema: &Schema, pointer: &str) {
+    if let Some(ref ref_path) = schema.ref_path {
+        visitor.visit_reference(ref_path, pointer);
+    }
+    let properties = pointer::push(pointer, "properties");
+    for (name, property) in schema.properties.iter().flatten() {
+        visit_schema_node(visitor, property, &pointer::push(&properties, name));
+    }
+    if let Some(ref items) = schema.items {
+        visit_schema_node(visitor, items, &pointer::push(pointer, "items"));
+    }
+    if let Some(ref additional) = schema.additional_properties {
+        let pointer = pointer::push(pointer, "additionalProperties");
+        visit_object_or_reference(visitor, additional, &pointer, |visitor, schema, pointer| {
+            visit_schema_node(visitor, schema, pointer)
+        });
+    }
+    for (key, parts) in &[("allOf", &schema.all_of), ("oneOf", &schema.one_of)] {
+        let parts_pointer = pointer::push(pointer, key);
+        for (i, part) in parts.iter().flatten().enumerate() {
+            let pointer = pointer::push(&parts_pointer, &i.to_string());
+            visit_object_or_reference(visitor, part, &pointer, visit_schema_node);
+        }
+    }
+}
+
+/// Visits the nodes of a `Spec` mutably. See the module documentation.
+pub trait VisitMut {
+    fn visit_spec_mut(&mut self, spec: &mut Spec, pointer: &str) {
+        walk_spec_mut(self, spec, pointer);
+    }
+
+    /// A path item, under `paths` or `webhooks` by its path or name, or under
+    /// `components/pathItems` by its component name.
+    fn visit_path_item_mut(&mut self, path: &str, item: &mut PathItem, pointer: &str) {
+        walk_path_item_mut(self, path, item, pointer);
+    }
+
+    fn visit_operation_mut(&mut self, method: &Method, operation: &mut Operation, pointer: &str) {
+        walk_operation_mut(self, method, operation, pointer);
+    }
+
+    fn visit_parameter_mut(&mut self, parameter: &mut Parameter, pointer: &str) {
+        walk_parameter_mut(self, parameter, pointer);
+    }
+
+    fn visit_request_body_mut(&mut self, body: &mut RequestBody, pointer: &str) {
+        walk_request_body_mut(self, body, pointer);
+    }
+
+    fn visit_response_mut(&mut self, response: &mut Response, pointer: &str) {
+        walk_response_mut(self, response, pointer);
+    }
+
+    fn visit_header_mut(&mut self, header: &mut Header, pointer: &str) {
+        walk_header_mut(self, header, pointer);
+    }
+
+    fn visit_media_type_mut(&mut self, media_type: &mut MediaType, pointer: &str) {
+        walk_media_type_mut(self, media_type, pointer);
+    }
+
+    fn visit_schema_mut(&mut self, schema: &mut Schema, pointer: &str) {
+        walk_schema_mut(self, schema, pointer);
+    }
+
+    /// A `$ref`, found at `pointer`, in place of any of the nodes above.
+    fn visit_reference_mut(&mut self, _ref_path: &mut String, _pointer: &str) {}
+}
+
+fn visit_object_or_reference_mut<V, T, F>(visitor: &mut V, object: &mut ObjectOrReference<T>, pointer: &str, visit: F)
+where
+    V: VisitMut + ?Sized,
+    F: FnOnce(&mut V, &mut T, &str),
+{
+    match object {
+        ObjectOrReference::Object(object) => visit(visitor, object, pointer),
+        ObjectOrReference::Ref { ref_path } => visitor.visit_reference_mut(ref_path, pointer),
+    }
+}
+
+fn visit_schema_node_mut<V: VisitMut + ?Sized>(visitor: &mut V, schema: &mut Schema, pointer: &str) {
+    let reference = is_reference(schema);
+    match schema.ref_path {
+        Some(ref mut ref_path) if reference => visitor.visit_reference_mut(ref_path, pointer),
+        _ => visitor.visit_schema_mut(schema, pointer),
+    }
+}
+
+fn visit_path_item_node_mut<V: VisitMut + ?Sized>(visitor: &mut V, path: &str, item: &mut PathItem, pointer: &str) {
+    let reference = is_item_reference(item);
+    match item.reference {
+        Some(ref mut ref_path) if reference => visitor.visit_reference_mut(ref_path, pointer),
+        _ => visitor.visit_path_item_mut(path, item, pointer),
+    }
+}
+
+pub fn walk_spec_mut<V: VisitMut + ?Sized>(visitor: &mut V, spec: &mut Spec, pointer: &str) {
+    let paths = pointer::push(pointer, "paths");
+    for (path, item) in spec.paths.iter_mut() {
+        visit_path_item_node_mut(visitor, path, item, &pointer::push(&paths, path));
+    }
+    let webhooks = pointer::push(pointer, "webhooks");
+    for (name, item) in spec.webhooks.iter_mut().flatten() {
+        visit_path_item_node_mut(visitor, name, item, &pointer::push(&webhooks, name));
+    }
+
+    let components = match spec.components {
+        Some(ref mut components) => components,
+        None => return,
+    };
+    let pointer = pointer::push(pointer, "components");
+    let kind = |kind: &str, name: &str| pointer::push(&pointer::push(&pointer, kind), name);
+    for (name, schema) in components.schemas.iter_mut().flatten() {
+        visit_object_or_reference_mut(visitor, schema, &kind("schemas", name), visit_schema_node_mut);
+    }
+    for (name, response) in components.responses.iter_mut().flatten() {
+        visit_object_or_reference_mut(visitor, response, &kind("responses", name), V::visit_response_mut);
+    }
+    for (name, parameter) in components.parameters.iter_mut().flatten() {
+        visit_object_or_reference_mut(visitor, parameter, &kind("parameters", name), V::visit_parameter_mut);
+    }
+    for (name, body) in components.request_bodies.iter_mut().flatten() {
+        visit_object_or_reference_mut(visitor, body, &kind("requestBodies", name), V::visit_request_body_mut);
+    }
+    for (name, header) in components.headers.iter_mut().flatten() {
+        visit_object_or_reference_mut(visitor, header, &kind("headers", name), V::visit_header_mut);
+    }
+    for (name, item) in components.path_items.iter_mut().flatten() {
+        visit_object_or_reference_mut(visitor, item, &kind("pathItems", name), |visitor, item, pointer| {
+            visit_path_item_node_mut(visitor, name, item, pointer)
+        });
+    }
+}
+
+pub fn walk_path_item_mut<V: VisitMut + ?Sized>(visitor: &mut V, _path: &str, item: &mut PathItem, pointer: &str) {
+    if let Some(ref mut ref_path) = item.reference {
+        visitor.visit_reference_mut(ref_path, pointer);
+    }
+    let parameters = pointer::push(pointer, "parameters");
+    for (i, parameter) in item.parameters.iter_mut().flatten().enumerate() {
+        let pointer = pointer::push(&parameters, &i.to_string());
+        visit_object_or_reference_mut(visitor, parameter, &pointer, V::visit_parameter_mut);
+    }
+    for (method, operation) in item.operations_mut() {
+        visitor.visit_operation_mut(&method, operation, &pointer::push(pointer, &method_token(&method)));
+    }
+}
+
+pub fn walk_operation_mut<V: VisitMut + ?Sized>(visitor: &mut V, _method: &Method, operation: &mut Operation, pointer: &str) {
+    let parameters = pointer::push(pointer, "parameters");
+    for (i, parameter) in operation.parameters.iter_mut().flatten().enumerate() {
+        let pointer = pointer::push(&parameters, &i.to_string());
+        visit_object_or_reference_mut(visitor, parameter, &pointer, V::visit_parameter_mut);
+    }
+    if let Some(ref mut body) = operation.request_body {
+        let pointer = pointer::push(pointer, "requestBody");
+        visit_object_or_reference_mut(visitor, body, &pointer, V::visit_request_body_mut);
+    }
+    let responses = pointer::push(pointer, "responses");
+    for (status, response) in operation.responses.iter_mut() {
+        visitor.visit_response_mut(response, &pointer::push(&responses, status));
+    }
+}
+
+pub fn walk_parameter_mut<V: VisitMut + ?Sized>(visitor: &mut V, parameter: &mut Parameter, pointer: &str) {
+    if let Some(ref mut schema) = parameter.schema {
+        visit_schema_node_mut(visitor, schema, &pointer::push(pointer, "schema"));
+    }
+}
+
+pub fn walk_request_body_mut<V: VisitMut + ?Sized>(visitor: &mut V, body: &mut RequestBody, pointer: &str) {
+    let content = pointer::push(pointer, "content");
+    for (name, media_type) in body.content.iter_mut() {
+        visitor.visit_media_type_mut(media_type, &pointer::push(&content, name));
+    }
+}
+
+pub fn walk_response_mut<V: VisitMut + ?Sized>(visitor: &mut V, response: &mut Response, pointer: &str) {
+    let headers = pointer::push(pointer, "headers");
+    for (name, header) in response.headers.iter_mut().flatten() {
+        visit_object_or_reference_mut(visitor, header, &pointer::push(&headers, name), V::visit_header_mut);
+    }
+    let content = pointer::push(pointer, "content");
+    for (name, media_type) in response.content.iter_mut().flatten() {
+        visitor.visit_media_type_mut(media_type, &pointer::push(&content, name));
+    }
+}
+
+pub fn walk_header_mut<V: VisitMut + ?Sized>(visitor: &mut V, header: &mut Header, pointer: &str) {
+    if let Some(ref mut schema) = header.schema {
+        visit_schema_node_mut(visitor, schema, &pointer::push(pointer, "schema"));
+    }
+}
+
+pub fn walk_media_type_mut<V: VisitMut + ?Sized>(visitor: &mut V, media_type: &mut MediaType, pointer: &str) {
+    if let Some(ref mut schema) = media_type.schema {
+        visit_object_or_reference_mut(visitor, schema, &pointer::push(pointer, "schema"), visit_schema_node_mut);
+    }
+    let encodings = pointer::push(pointer, "encoding");
+    for (property, encoding) in media_type.encoding.iter_mut().flatten() {
+        let headers = pointer::push(&pointer::push(&encodings, property), "headers");
+        for (name, header) in encoding.headers.iter_mut().flatten() {
+            visit_object_or_reference_mut(visitor, header, &pointer::push(&headers, name), V::visit_header_mut);
+        }
+    }
+}
+
+pub fn walk_schema_mut<V: VisitMut + ?Sized>(visitor: &mut V, schema: &mut Schema, pointer: &str) {
+    if let Some(ref mut ref_path) = schema.ref_path {
+        visitor.visit_reference_mut(ref_path, pointer);
+    }
+    let properties = pointer::push(pointer, "properties");
+    for (name, property) in schema.properties.iter_mut().flatten() {
+        visit_schema_node_mut(visitor, property, &pointer::push(&properties, name));
+    }
+    if let Some(ref mut items) = schema.items {
+        visit_schema_node_mut(visitor, items, &pointer::push(pointer, "items"));
+    }
+    if let Some(ref mut additional) = schema.additional_properties {
+        let pointer = pointer::push(pointer, "additionalProperties");
+        visit_object_or_reference_mut(visitor, additional, &pointer, |visitor, schema, pointer| {
+            visit_schema_node_mut(visitor, schema, pointer)
+        });
+    }
+    for (key, parts) in [("allOf", &mut schema.all_of), ("oneOf", &mut schema.one_of)] {
+        let parts_pointer = pointer::push(pointer, key);
+        for (i, part) in parts.iter_mut().flatten().enumerate() {
+            let pointer = pointer::push(&parts_pointer, &i.to_string());
+            visit_object_or_reference_mut(visitor, part, &pointer, visit_schema_node_mut);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct Collect {
+        operations: Vec<String>,
+        schemas: Vec<String>,
+        references: Vec<(String, String)>,
+    }
+
+    impl Visit for Collect {
+        fn visit_operation(&mut self, method: &Method, operation: &Operation, pointer: &str) {
+            self.operations.push(format!("{} {}", method, pointer));
+            walk_operation(self, method, operation, pointer);
+        }
+
+        fn visit_schema(&mut self, schema: &Schema, pointer: &str) {
+            self.schemas.push(pointer.to_string());
+            walk_schema(self, schema, pointer);
+        }
+
+        fn visit_reference(&mut self, ref_path: &str, pointer: &str) {
+            self.references.push((pointer.to_string(), ref_path.to_string()));
+        }
+    }
+
+    #[test]
+    fn test_visit() {
+        let spec = crate::from_path("data/v3.0/petstore.yaml").unwrap();
+        let mut collect = Collect::default();
+        collect.visit_spec(&spec, "");
+
+        assert_eq!(
+            collect.operations,
+            vec![
+                "GET /paths/~1pets/get",
+                "POST /paths/~1pets/post",
+                "GET /paths/~1pets~1{petId}/get",
+            ]
+        );
+        assert!(collect.schemas.contains(&"/paths/~1pets/get/parameters/0/schema".to_string()));
+        assert!(collect.schemas.contains(&"/components/schemas/Pet/properties/id".to_string()));
+        // a schema that is only a reference is visited as one
+        assert!(!collect.schemas.contains(&"/components/schemas/Pets/items".to_string()));
+        assert!(collect.references.contains(&(
+            "/components/schemas/Pets/items".to_string(),
+            "#/components/schemas/Pet".to_string()
+        )));
+        assert!(collect.references.contains(&(
+            "/paths/~1pets/get/responses/200/content/application~1json/schema".to_string(),
+            "#/components/schemas/Pets".to_string()
+        )));
+        for (pointer, _) in collect.references.iter() {
+            assert!(crate::pointer::tokens(pointer).len() > 2);
+        }
+    }
+
+    #[test]
+    fn test_visit_references() {
+        let spec = crate::from_str(
+            r#"
+openapi: 3.1.0
+info: {title: Pets, version: "1"}
+paths:
+  /pets: {$ref: '#/components/pathItems/Pets'}
+components:
+  schemas:
+    Pet:
+      properties:
+        owner: {$ref: '#/components/schemas/Owner'}
+        parent: {$ref: '#/components/schemas/Pet', description: The parent}
+    Owner: {type: object}
+"#,
+        )
+        .unwrap();
+        let mut collect = Collect::default();
+        collect.visit_spec(&spec, "");
+
+        assert_eq!(collect.operations, Vec::<String>::new());
+        let owner = "/components/schemas/Pet/properties/owner".to_string();
+        let parent = "/components/schemas/Pet/properties/parent".to_string();
+        assert!(!collect.schemas.contains(&owner));
+        assert!(collect.schemas.contains(&parent));
+        let pointers: Vec<&String> = collect.references.iter().map(|(pointer, _)| pointer).collect();
+        assert_eq!(pointers, vec!["/paths/~1pets", &owner, &parent]);
+    }
+
+    struct Describe;
+
+    impl VisitMut for Describe {
+        fn visit_schema_mut(&mut self, schema: &mut Schema, pointer: &str) {
+            if schema.description.is_none() {
+                schema.description = Some(pointer.to_string());
+            }
+            walk_schema_mut(self, schema, pointer);
+        }
+
+        fn visit_reference_mut(&mut self, ref_path: &mut String, _pointer: &str) {
+            *ref_path = ref_path.replace("/Pet", "/Animal");
+        }
+    }
+
+    #[test]
+    fn test_visit_mut() {
+        let mut spec = crate::from_path("data/v3.0/petstore.yaml").unwrap();
+        Describe.visit_spec_mut(&mut spec, "");
+
+        let json = serde_json::to_value(&spec).unwrap();
+        assert_eq!(json["components"]["schemas"]["Pets"]["items"]["$ref"], "#/components/schemas/Animal");
+        let tag = &json["components"]["schemas"]["Pet"]["properties"]["tag"];
+        assert_eq!(tag["description"], "/components/schemas/Pet/properties/tag");
+    }
+}
@@ -0,0 +1,138 @@
+#![cfg(feature = "cli")]
+
+use std::fs;
+use std::io::Read;
+use std::path::PathBuf;
+use std::process::{Command, Output, Stdio};
+
+use serde_json::Value;
+
+const PETSTORE: &str = "data/v3.0/petstore.yaml";
+
+fn openapi(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_openapi"))
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn stdout_json(output: &Output) -> Value {
+    serde_json::from_slice(&output.stdout).unwrap()
+}
+
+/// Write a variant of the petstore spec, returning its path.
+fn petstore_with(name: &str, from: &str, to: &str) -> String {
+    let dir: PathBuf = ["target", "tests", "cli"].iter().collect();
+    fs::create_dir_all(&dir).unwrap();
+    let spec = fs::read_to_string(PETSTORE).unwrap();
+    assert!(spec.contains(from));
+    let path = dir.join(name);
+    fs::write(&path, spec.replace(from, to)).unwrap();
+    path.to_str().unwrap().to_string()
+}
+
+#[test]
+fn test_validate() {
+    let output = openapi(&["validate", PETSTORE, "--format", "json"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout_json(&output)["valid"], Value::Bool(true));
+    assert!(stdout_json(&output)["examples"].as_array().unwrap().is_empty());
+
+    let invalid = petstore_with(
+        "invalid-example.yaml",
+        "        tag:\n          type: string\n",
+        "        tag:\n          type: string\n      example:\n        id: one\n        name: Tom\n",
+    );
+    let output = openapi(&["validate", &invalid, "--format", "json"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stdout_json(&output)["valid"], Value::Bool(false));
+    assert_eq!(stdout_json(&output)["examples"].as_array().unwrap().len(), 1);
+
+    let output = openapi(&["validate", "data/missing.yaml", "--format", "json"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stdout_json(&output)["error"].is_string());
+}
+
+#[test]
+fn test_lint() {
+    let output = openapi(&["lint", PETSTORE, "--format", "json"]);
+    assert_eq!(output.status.code(), Some(0));
+    let problems = stdout_json(&output);
+    for problem in problems.as_array().unwrap() {
+        for key in ["rule", "severity", "pointer", "message"].iter() {
+            assert!(problem[key].is_string(), "{} of {}", key, problem);
+        }
+    }
+
+    let config: PathBuf = ["target", "tests", "cli", "lint.yaml"].iter().collect();
+    fs::create_dir_all(config.parent().unwrap()).unwrap();
+    fs::write(&config, "rules:\n  schema-description: error\n").unwrap();
+    let output = openapi(&["lint", PETSTORE, "--format", "json", "--config", config.to_str().unwrap()]);
+    assert_eq!(output.status.code(), Some(1));
+    let problems = stdout_json(&output);
+    assert!(problems
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|problem| problem["rule"] == "schema-description" && problem["severity"] == "error"));
+}
+
+#[test]
+fn test_diff() {
+    let output = openapi(&["diff", PETSTORE, PETSTORE, "--format", "json"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(stdout_json(&output)["breaking"], Value::Bool(false));
+    assert!(stdout_json(&output)["changes"].as_array().unwrap().is_empty());
+
+    let spec = fs::read_to_string(PETSTORE).unwrap();
+    let (start, end) = (spec.find("  /pets/{petId}:").unwrap(), spec.find("components:").unwrap());
+    let path: PathBuf = ["target", "tests", "cli", "removed-path.yaml"].iter().collect();
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(&path, format!("{}{}", &spec[..start], &spec[end..])).unwrap();
+    let output = openapi(&["diff", PETSTORE, path.to_str().unwrap(), "--format", "json"]);
+    assert_eq!(output.status.code(), Some(1));
+    let report = stdout_json(&output);
+    assert_eq!(report["breaking"], Value::Bool(true));
+    let change = &report["changes"][0];
+    assert_eq!(change["breaking"], Value::Bool(true));
+    assert!(change["pointer"].is_string());
+    assert!(change["message"].is_string());
+}
+
+#[test]
+fn test_invalid_arguments() {
+    let output = openapi(&["validate"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(!output.stderr.is_empty());
+
+    let output = openapi(&["validate", "--format", "json"]);
+    assert_eq!(output.status.code(), Some(2));
+    let error: Value = serde_json::from_slice(&output.stderr).unwrap();
+    assert!(error["error"].is_string());
+}
+
+#[test]
+fn test_lossy_conversion_warnings() {
+    let output = openapi(&["convert", "data/v2.0/petstore.yaml", "--format", "json"]);
+    assert_eq!(output.status.code(), Some(0));
+    for line in String::from_utf8(output.stderr).unwrap().lines() {
+        let warning: Value = serde_json::from_str(line).unwrap();
+        assert!(warning["warning"]["path"].is_string());
+    }
+}
+
+#[test]
+fn test_closed_stdout() {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_openapi"))
+        .args(&["convert", "data/v3.0/uspto.yaml", "--to", "json"])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    drop(child.stdout.take());
+    let status = child.wait().unwrap();
+    let mut stderr = String::new();
+    child.stderr.take().unwrap().read_to_string(&mut stderr).unwrap();
+    assert_eq!(status.code(), Some(0), "{}", stderr);
+    assert!(!stderr.contains("panicked"), "{}", stderr);
+}
@@ -0,0 +1,81 @@
+//! The types generated for the example specs compile. The generated sources are kept in
+//! `tests/generated`; run with `UPDATE_GENERATED=1` to regenerate them.
+extern crate actix_web_openapi;
+
+use std::env;
+use std::fs;
+
+use actix_web_openapi::codegen::TypeGenerator;
+
+const SPECS: &[(&str, &str)] = &[
+    ("./data/v3.0/api-with-example.yaml", "./tests/generated/api_with_example.rs"),
+    ("./data/v3.0/callback-example.yaml", "./tests/generated/callback_example.rs"),
+    ("./data/v3.0/field-names.yaml", "./tests/generated/field_names.rs"),
+    ("./data/v3.0/link-example.yaml", "./tests/generated/link_example.rs"),
+    ("./data/v3.0/petstore-exanded.yaml", "./tests/generated/petstore_expanded.rs"),
+    ("./data/v3.0/petstore.yaml", "./tests/generated/petstore.rs"),
+    ("./data/v3.0/uspto.yaml", "./tests/generated/uspto.rs"),
+];
+
+#[allow(dead_code)]
+mod api_with_example {
+    include!("generated/api_with_example.rs");
+}
+
+#[allow(dead_code)]
+mod callback_example {
+    include!("generated/callback_example.rs");
+}
+
+#[allow(dead_code)]
+mod field_names {
+    include!("generated/field_names.rs");
+}
+
+#[allow(dead_code)]
+mod link_example {
+    include!("generated/link_example.rs");
+}
+
+#[allow(dead_code)]
+mod petstore_expanded {
+    include!("generated/petstore_expanded.rs");
+}
+
+#[allow(dead_code)]
+mod petstore {
+    include!("generated/petstore.rs");
+}
+
+#[allow(dead_code)]
+mod uspto {
+    include!("generated/uspto.rs");
+}
+
+#[test]
+fn test_generated_types_are_current() {
+    for (spec_path, generated_path) in SPECS {
+        let spec = actix_web_openapi::from_path(spec_path).unwrap();
+        let code = TypeGenerator::new(&spec).chrono(false).uuid(false).generate();
+        if env::var_os("UPDATE_GENERATED").is_some() {
+            fs::write(generated_path, &code).unwrap();
+        } else {
+            let generated = fs::read_to_string(generated_path).unwrap();
+            assert!(generated == code, "{} is out of date, run with UPDATE_GENERATED=1", generated_path);
+        }
+    }
+}
+
+#[test]
+fn test_generated_types_round_trip() {
+    let pet: petstore::Pet = serde_json::from_str(r#"{"id": 1, "name": "Rex"}"#).unwrap();
+    assert_eq!(serde_json::to_value(&pet).unwrap(), serde_json::json!({"id": 1, "name": "Rex"}));
+
+    let link = r#"{"self": "/pets/1", "type": "pet", "expires": null}"#;
+    let link: field_names::Link = serde_json::from_str(link).unwrap();
+    assert_eq!(link.self_, "/pets/1");
+    assert_eq!(
+        serde_json::to_value(&link).unwrap(),
+        serde_json::json!({"self": "/pets/1", "type": "pet", "expires": null})
+    );
+}
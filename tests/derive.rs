@@ -0,0 +1,193 @@
+#![cfg(feature = "derive")]
+#![allow(dead_code)]
+extern crate actix_web_openapi;
+
+use actix_web::{Json, Path, Query};
+use actix_web_openapi::components::ObjectOrReference;
+use actix_web_openapi::{openapi_operation, Error, OpenApiSchema};
+use actix_web_openapi::schema::{OpenApiSchema as _, Schemas};
+use actix_web_openapi::spec::{Schema, Spec};
+use serde::{Deserialize, Serialize};
+
+/// A pet in the store.
+#[derive(Serialize, OpenApiSchema)]
+#[serde(rename_all = "camelCase")]
+struct Pet {
+    /// The unique id.
+    id: u64,
+    pet_name: String,
+    tag: Option<String>,
+    #[serde(rename = "kind")]
+    category: Category,
+    #[serde(skip)]
+    internal: bool,
+    #[serde(flatten)]
+    audit: Audit,
+}
+
+#[derive(Serialize, OpenApiSchema)]
+struct Audit {
+    created_by: String,
+}
+
+#[derive(Serialize, OpenApiSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+enum Category {
+    HouseCat,
+    Dog,
+}
+
+#[derive(Serialize, OpenApiSchema)]
+#[serde(tag = "type")]
+enum Event {
+    Created { pet: Pet },
+    Deleted,
+}
+
+#[derive(Serialize, OpenApiSchema)]
+#[serde(untagged)]
+enum Id {
+    Number(u64),
+    Name(String),
+}
+
+fn component(schemas: &Schemas, name: &str) -> Schema {
+    match schemas[name] {
+        ObjectOrReference::Object(ref schema) => schema.clone(),
+        _ => panic!("expected an inline schema"),
+    }
+}
+
+#[test]
+fn test_struct() {
+    let mut schemas = Schemas::new();
+    let reference = Pet::schema_ref(&mut schemas);
+    assert_eq!(reference.ref_path, Some("#/components/schemas/Pet".to_string()));
+
+    let pet = component(&schemas, "Pet");
+    assert_eq!(pet.description, Some("A pet in the store.".to_string()));
+    let properties = pet.properties.unwrap();
+    assert_eq!(properties.len(), 5);
+    assert!(properties.contains_key("petName"));
+    assert!(properties.contains_key("created_by"));
+    assert!(!properties.contains_key("internal"));
+    assert_eq!(properties["id"].description, Some("The unique id.".to_string()));
+    assert_eq!(properties["kind"].ref_path, Some("#/components/schemas/Category".to_string()));
+
+    let required = pet.required.unwrap();
+    assert!(required.contains(&"petName".to_string()));
+    assert!(!required.contains(&"tag".to_string()));
+
+    let category = component(&schemas, "Category");
+    assert_eq!(category.enum_values, Some(vec!["HOUSE_CAT".to_string(), "DOG".to_string()]));
+}
+
+#[test]
+fn test_enums() {
+    let mut schemas = Schemas::new();
+    Event::schema_ref(&mut schemas);
+    let event = component(&schemas, "Event");
+    let variants = event.one_of.unwrap();
+    assert_eq!(variants.len(), 2);
+    match variants[1] {
+        ObjectOrReference::Object(ref deleted) => {
+            assert_eq!(deleted.properties.as_ref().unwrap()["type"].enum_values, Some(vec!["Deleted".to_string()]));
+        }
+        _ => panic!("expected an inline schema"),
+    }
+
+    Id::schema_ref(&mut schemas);
+    let id = component(&schemas, "Id");
+    assert_eq!(id.one_of.map(|variants| variants.len()), Some(2));
+}
+
+#[derive(Deserialize, OpenApiSchema)]
+struct PetQuery {
+    limit: Option<u32>,
+}
+
+#[derive(Deserialize, OpenApiSchema)]
+struct NewPet {
+    name: String,
+}
+
+/// Find a pet by id.
+///
+/// Returns the pet with the given id.
+#[openapi_operation(get, "/owners/{owner}/pets/{id}", tags("pets"), response(404, "Pet not found"))]
+fn get_pet(_path: Path<(String, u64)>, _query: Query<PetQuery>) -> actix_web::Result<Json<Category>> {
+    Ok(Json(Category::Dog))
+}
+
+#[openapi_operation(post, "/pets", operation_id = "createPet", status = 201)]
+fn create_pet(body: Json<NewPet>) -> String {
+    body.into_inner().name
+}
+
+#[test]
+fn test_operations() {
+    let spec = Spec::builder()
+        .title("Petstore")
+        .version("1.0.0")
+        .operation::<get_pet>()
+        .operation::<create_pet>()
+        .build()
+        .unwrap();
+
+    let get = spec.paths["/owners/{owner}/pets/{id}"].get.clone().unwrap();
+    assert_eq!(get.operation_id, Some("get_pet".to_string()));
+    assert_eq!(get.summary, Some("Find a pet by id.".to_string()));
+    assert_eq!(get.description, Some("Returns the pet with the given id.".to_string()));
+    assert_eq!(get.tags, Some(vec!["pets".to_string()]));
+    assert_eq!(get.parameters.unwrap().len(), 3);
+    assert_eq!(get.responses.keys().collect::<Vec<_>>(), vec!["200", "404"]);
+
+    let post = spec.paths["/pets"].post.clone().unwrap();
+    assert_eq!(post.operation_id, Some("createPet".to_string()));
+    assert!(post.request_body.is_some());
+    assert!(post.responses["201"].content.as_ref().unwrap().contains_key("text/plain"));
+    assert!(spec.components.unwrap().schemas.unwrap().contains_key("NewPet"));
+}
+
+mod store {
+    use super::*;
+
+    #[derive(Serialize, OpenApiSchema)]
+    pub struct Pet {
+        pub name: String,
+    }
+
+    #[derive(Serialize, OpenApiSchema)]
+    #[openapi(rename = "StorePet")]
+    pub struct RenamedPet {
+        pub name: String,
+    }
+}
+
+#[test]
+fn test_name_collision() {
+    let result = Spec::builder()
+        .title("Petstore")
+        .version("1.0.0")
+        .schema::<Pet>()
+        .schema::<store::Pet>()
+        .schema::<Pet>()
+        .build();
+
+    match result {
+        Err(Error::InvalidSpec(problems)) => {
+            assert!(problems.contains("schema `Pet` is registered by both `derive::Pet` and `derive::store::Pet`"));
+        }
+        other => panic!("expected an invalid spec, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_schema_rename() {
+    let mut schemas = Schemas::new();
+    Pet::schema_ref(&mut schemas);
+    Pet::schema_ref(&mut schemas);
+    let schema = store::RenamedPet::schema_ref(&mut schemas);
+    assert_eq!(schema.ref_path, Some("#/components/schemas/StorePet".to_string()));
+    assert!(schemas.contains_key("Pet"));
+}
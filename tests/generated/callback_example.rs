@@ -0,0 +1 @@
+// Generated from an OpenApi document, do not edit.
@@ -0,0 +1,14 @@
+// Generated from an OpenApi document, do not edit.
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Link {
+    #[serde(rename = "self")]
+    pub self_: String,
+    #[serde(rename = "type")]
+    pub r#type: String,
+    pub expires: Option<String>,
+    #[serde(rename = "crate", skip_serializing_if = "Option::is_none")]
+    pub crate_: Option<String>,
+    #[serde(rename = "relTarget", skip_serializing_if = "Option::is_none")]
+    pub rel_target: Option<String>,
+}
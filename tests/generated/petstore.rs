@@ -0,0 +1,17 @@
+// Generated from an OpenApi document, do not edit.
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Pet {
+    pub id: i64,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+}
+
+pub type Pets = Vec<Pet>;
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Error {
+    pub code: i32,
+    pub message: String,
+}
@@ -0,0 +1,21 @@
+// Generated from an OpenApi document, do not edit.
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Pet {
+    #[serde(flatten)]
+    pub new_pet: NewPet,
+    pub id: i64,
+}
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct NewPet {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Error {
+    pub code: i32,
+    pub message: String,
+}
@@ -0,0 +1,25 @@
+// Generated from an OpenApi document, do not edit.
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct DataSetListApisItem {
+    /// To be used as a dataset parameter value
+    #[serde(rename = "apiKey", skip_serializing_if = "Option::is_none")]
+    pub api_key: Option<String>,
+    /// To be used as a version parameter value
+    #[serde(rename = "apiVersionNumber", skip_serializing_if = "Option::is_none")]
+    pub api_version_number: Option<String>,
+    /// The URL describing the dataset's fields
+    #[serde(rename = "apiUrl", skip_serializing_if = "Option::is_none")]
+    pub api_url: Option<String>,
+    /// A URL to the API console for each API
+    #[serde(rename = "apiDocumentationUrl", skip_serializing_if = "Option::is_none")]
+    pub api_documentation_url: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct DataSetList {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub apis: Option<Vec<DataSetListApisItem>>,
+}